@@ -0,0 +1,59 @@
+//! Throughput of ID generation from many threads
+//!
+//! Compares a single [`IdGenerator`] shared by every thread, which uses the thread-local RNG, with
+//! the same generator fed from one RNG behind a `Mutex`. Run with `cargo bench --bench contention`.
+
+use std::{
+    hint::black_box,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use human_friendly_ids::generator::IdGenerator;
+use rand::{SeedableRng, rngs::StdRng};
+
+/// IDs generated by each thread per measurement
+const IDS_PER_THREAD: u32 = 200_000;
+
+static GENERATOR: IdGenerator = IdGenerator::new(16);
+
+/// Time for `threads` threads to each run `generate` [`IDS_PER_THREAD`] times
+fn measure(threads: u32, generate: &(dyn Fn() + Sync)) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..IDS_PER_THREAD {
+                    generate();
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+/// Millions of IDs per second
+fn throughput(threads: u32, elapsed: Duration) -> f64 {
+    f64::from(threads * IDS_PER_THREAD) / elapsed.as_secs_f64() / 1e6
+}
+
+fn main() {
+    let locked = Mutex::new(StdRng::from_os_rng());
+
+    println!("threads  shared (M ids/s)  locked rng (M ids/s)");
+    for threads in [1, 2, 4, 8, 16] {
+        let shared = measure(threads, &|| {
+            black_box(GENERATOR.generate());
+        });
+        let contended = measure(threads, &|| {
+            let mut rng = locked.lock().expect("not poisoned");
+            black_box(GENERATOR.generate_with_rng(&mut *rng));
+        });
+        println!(
+            "{threads:>7}  {:>17.2}  {:>20.2}",
+            throughput(threads, shared),
+            throughput(threads, contended)
+        );
+    }
+}
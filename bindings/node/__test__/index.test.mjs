@@ -0,0 +1,29 @@
+// Smoke test of the built module, run with `npm test` after `npm run build`
+import assert from 'node:assert/strict'
+import { createRequire } from 'node:module'
+import { test } from 'node:test'
+
+const { generate, validate, isValid, normalize } = createRequire(import.meta.url)('../index.js')
+
+test('generated IDs validate', () => {
+  const id = generate(12)
+  assert.equal(id.length, 12)
+  assert.equal(validate(id.toUpperCase()), id)
+  assert.ok(isValid(id))
+})
+
+test('generate rejects lengths outside the parseable range', () => {
+  assert.throws(() => generate(3), { code: 'too_short' })
+  assert.throws(() => generate(4097), { code: 'too_long' })
+  assert.equal(generate(4096).length, 4096)
+})
+
+test('errors carry stable codes', () => {
+  assert.throws(() => validate('wcfytxww4opin4jmjjes4ccfa'), { code: 'invalid_check_bit' })
+  assert.throws(() => validate('wc'), { code: 'too_short' })
+  assert.ok(!isValid('wcfytxww4opin4jmjjes4ccfa'))
+})
+
+test('normalize matches parsing', () => {
+  assert.equal(normalize('WCFYTXWVV4OP1N'), 'wcfytxww4opin')
+})
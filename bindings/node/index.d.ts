@@ -0,0 +1,20 @@
+/**
+ * Generate a new ID of a given length, including the check character
+ *
+ * Throws with `code` `too_short` or `too_long` if `length` is outside the lengths `validate`
+ * accepts.
+ */
+export declare function generate(length: number): string
+
+/**
+ * Validate an ID, returning it in canonical form
+ *
+ * Throws with a stable `code` such as `invalid_check_bit` if the ID is invalid.
+ */
+export declare function validate(id: string): string
+
+/** Whether an ID is valid after normalization */
+export declare function isValid(id: string): boolean
+
+/** Normalize user input the same way as when parsing, without validating it */
+export declare function normalize(input: string): string
@@ -0,0 +1,23 @@
+// Loads the native module built by `napi build --platform` for the current platform
+'use strict'
+
+const { platform, arch } = process
+
+/** Whether this Linux system uses musl rather than glibc, e.g. Alpine */
+function isMusl() {
+  const { glibcVersionRuntime } = process.report.getReport().header
+  return !glibcVersionRuntime
+}
+
+function abi() {
+  switch (platform) {
+    case 'linux':
+      return isMusl() ? '-musl' : '-gnu'
+    case 'win32':
+      return '-msvc'
+    default:
+      return ''
+  }
+}
+
+module.exports = require(`./human-friendly-ids.${platform}-${arch}${abi()}.node`)
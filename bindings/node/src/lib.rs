@@ -0,0 +1,67 @@
+// bindings/node/src/lib.rs
+//! Node.js bindings, so services written in TypeScript validate IDs exactly like the Rust ones
+//!
+//! Errors are thrown with the stable [`IdError::code`] as their `code`, e.g. `invalid_check_bit`,
+//! so callers can match on them without parsing English messages.
+
+use std::str::FromStr;
+
+use human_friendly_ids::{
+    Id, alphabet,
+    error::IdError,
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+};
+use napi::Error;
+use napi_derive::napi;
+
+/// Result whose error carries an [`IdError::code`] as its status
+type Result<T> = napi::Result<T, &'static str>;
+
+/// Generate a new ID of a given length, including the check character
+///
+/// ## Errors
+///
+/// - `too_short` if `length` is below the minimum ID length
+/// - `too_long` if `length` is above the longest ID [`validate`] accepts
+#[napi]
+pub fn generate(length: u32) -> Result<String> {
+    let length = usize::try_from(length).map_err(|_| id_error(&IdError::TooLong))?;
+    if length < MIN_LENGTH {
+        return Err(id_error(&IdError::TooShort));
+    }
+    if length > MAX_PARSE_LENGTH {
+        return Err(id_error(&IdError::TooLong));
+    }
+    Ok(Id::new(length).into())
+}
+
+/// Validate an ID, returning it in canonical form
+///
+/// ## Errors
+///
+/// Throws an error with the [`IdError::code`] as its `code` if the ID is invalid.
+#[napi]
+pub fn validate(id: String) -> Result<String> {
+    Id::from_str(&id)
+        .map(String::from)
+        .map_err(|e| id_error(&e))
+}
+
+/// Whether an ID is valid after normalization
+#[napi]
+#[must_use]
+pub fn is_valid(id: String) -> bool {
+    Id::from_str(&id).is_ok()
+}
+
+/// Normalize user input the same way as when parsing, without validating it
+#[napi]
+#[must_use]
+pub fn normalize(input: String) -> String {
+    alphabet::normalize_string(&input)
+}
+
+/// Error thrown to JavaScript for `error`, with its code as the `code` property
+fn id_error(error: &IdError) -> Error<&'static str> {
+    Error::new(error.code(), error.to_string())
+}
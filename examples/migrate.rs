@@ -0,0 +1,109 @@
+//! Re-issue a file of IDs under another format version
+//!
+//! ```sh
+//! cargo run --example migrate -- --from v1 --to v2 --in ids.txt --out ids_new.csv
+//! ```
+//!
+//! Reads one ID per line and writes a `legacy,migrated` CSV, see
+//! [`migrate_lines`](human_friendly_ids::migrate::migrate_lines). IDs that can't be migrated are
+//! listed on stderr, and the exit code is non-zero if there were any.
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    process::ExitCode,
+};
+
+use human_friendly_ids::{checksum::FormatVersion, migrate::migrate_lines};
+
+/// Shown when the arguments can't be parsed
+const USAGE: &str = "usage: migrate --from <version> --to <version> --in <ids.txt> --out <ids.csv>";
+
+/// Command line arguments
+struct Args {
+    /// Format the input IDs are in
+    from: FormatVersion,
+    /// Format to re-issue the IDs in
+    to: FormatVersion,
+    /// Path to read IDs from, one per line
+    input: String,
+    /// Path to write the CSV to
+    output: String,
+}
+
+impl Args {
+    /// Parse `--flag value` pairs, each flag given exactly once
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let (mut from, mut to, mut input, mut output) = (None, None, None, None);
+        while let Some(flag) = args.next() {
+            let slot = match flag.as_str() {
+                "--from" => &mut from,
+                "--to" => &mut to,
+                "--in" => &mut input,
+                "--out" => &mut output,
+                _ => return Err(format!("unknown argument {flag}")),
+            };
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {flag}"))?;
+            if slot.replace(value).is_some() {
+                return Err(format!("{flag} given more than once"));
+            }
+        }
+
+        let version = |name: &str, value: Option<String>| {
+            let value = value.ok_or_else(|| format!("missing {name}"))?;
+            value
+                .parse::<FormatVersion>()
+                .map_err(|e| format!("{e}: {value}"))
+        };
+        Ok(Self {
+            from: version("--from", from)?,
+            to: version("--to", to)?,
+            input: input.ok_or("missing --in")?,
+            output: output.ok_or("missing --out")?,
+        })
+    }
+}
+
+/// Migrate the input file, returning whether every ID was migrated
+fn run(args: &Args) -> Result<bool, Box<dyn Error>> {
+    let input = BufReader::new(File::open(&args.input)?);
+    let mut output = BufWriter::new(File::create(&args.output)?);
+    let report = migrate_lines(input, &mut output, args.from, args.to)?;
+    output.flush()?;
+
+    for failure in &report.failures {
+        eprintln!(
+            "{}:{}: {}: {}",
+            args.input, failure.line, failure.input, failure.error
+        );
+    }
+    eprintln!(
+        "migrated {} IDs from {} to {}, {} failed",
+        report.migrated,
+        args.from,
+        args.to,
+        report.failures.len()
+    );
+    Ok(report.is_complete())
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// src/adaptive.rs
+//! Generator lengthening its IDs as more are issued
+//!
+//! Short IDs are friendlier, but the chance of two colliding grows with the square of the number
+//! issued. An [`AdaptiveGenerator`] starts short and, once the birthday bound on the collision
+//! probability would pass a configured threshold, switches to longer IDs. Each switch is reported
+//! as a [`GenerationEvent::Lengthened`] to the generator's observer and recorded by
+//! [`telemetry`](crate::telemetry).
+
+use std::sync::{
+    PoisonError, RwLock, RwLockReadGuard,
+    atomic::{AtomicU64, Ordering},
+};
+
+use rand_core::RngCore;
+
+use crate::{
+    Id,
+    generator::{GenerationEvent, IdGenerator},
+};
+
+/// Generator switching to longer IDs to keep the collision probability below a threshold
+///
+/// The probability is estimated from the number of IDs issued so far, which the generator counts
+/// itself and can be told about, e.g. when restarting or when other instances issue IDs too.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{adaptive::AdaptiveGenerator, generator::IdGenerator};
+///
+/// let generator = AdaptiveGenerator::new(IdGenerator::new(4), 0.001);
+/// for _ in 0..1_000 {
+///     let _ = generator.generate();
+/// }
+/// assert!(generator.length() > 4);
+/// assert!(generator.collision_probability() <= 0.001);
+/// ```
+#[derive(Debug)]
+pub struct AdaptiveGenerator {
+    /// Generator for the current length, only written when lengthening
+    generator: RwLock<IdGenerator>,
+    /// Highest acceptable probability of any two issued IDs being equal
+    max_collision_probability: f64,
+    /// Number of IDs issued
+    issued: AtomicU64,
+    /// Number of IDs the current generator can issue without passing the threshold
+    capacity: AtomicU64,
+}
+
+impl AdaptiveGenerator {
+    /// Start generating with `generator`, lengthening its IDs to keep the probability of a
+    /// collision among all issued IDs at most `max_collision_probability`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_collision_probability` is not strictly between zero and one.
+    #[must_use]
+    pub fn new(generator: IdGenerator, max_collision_probability: f64) -> Self {
+        assert!(
+            max_collision_probability > 0.0 && max_collision_probability < 1.0,
+            "collision probability must be between 0 and 1, got {max_collision_probability}"
+        );
+        let capacity = capacity(generator.entropy_bits(), max_collision_probability);
+        Self {
+            generator: RwLock::new(generator),
+            max_collision_probability,
+            issued: AtomicU64::new(0),
+            capacity: AtomicU64::new(capacity),
+        }
+    }
+
+    /// Count `issued` IDs as already issued, e.g. those persisted before a restart
+    #[must_use]
+    pub fn with_issued(self, issued: u64) -> Self {
+        self.record_issued(issued);
+        self
+    }
+
+    /// Count `count` more IDs as issued, e.g. by other instances sharing the same ID space
+    ///
+    /// Lengthens IDs straight away if the threshold has been passed.
+    pub fn record_issued(&self, count: u64) {
+        let issued = self.add_issued(count);
+        self.reserve(issued);
+    }
+
+    /// Number of IDs issued, including those recorded with [`AdaptiveGenerator::record_issued`]
+    #[must_use]
+    pub fn issued(&self) -> u64 {
+        self.issued.load(Ordering::Relaxed)
+    }
+
+    /// Total length of IDs currently generated, including the check character
+    #[must_use]
+    pub fn length(&self) -> usize {
+        self.current().length()
+    }
+
+    /// Copy of the generator currently in use
+    #[must_use]
+    pub fn generator(&self) -> IdGenerator {
+        self.current().clone()
+    }
+
+    /// Estimated probability of any two issued IDs being equal, had they all been generated at the
+    /// current length
+    #[must_use]
+    pub fn collision_probability(&self) -> f64 {
+        collision_probability(self.issued(), self.current().entropy_bits())
+    }
+
+    /// Generate a new ID using the default RNG backend, see [`crate::rng`]
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn generate(&self) -> Id {
+        let mut rng = crate::rng::default_rng();
+        self.generate_with_rng(&mut rng)
+    }
+
+    /// Generate a new ID using the given RNG, lengthening IDs first if needed
+    #[must_use]
+    pub fn generate_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> Id {
+        let issued = self.add_issued(1);
+        self.reserve(issued);
+        self.current().generate_with_rng(rng)
+    }
+
+    /// Count `count` more IDs as issued, saturating rather than wrapping, and return the new total
+    fn add_issued(&self, count: u64) -> u64 {
+        let (Ok(previous) | Err(previous)) =
+            self.issued
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |issued| {
+                    Some(issued.saturating_add(count))
+                });
+        previous.saturating_add(count)
+    }
+
+    /// Lengthen IDs if `issued` IDs would pass the threshold at the current length
+    fn reserve(&self, issued: u64) {
+        if issued <= self.capacity.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // A panic while holding the lock can't leave a half-built generator behind.
+        let mut generator = self
+            .generator
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if issued <= self.capacity.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let from = generator.length();
+        let lengthened = generator
+            .clone()
+            .with_min_entropy(required_bits(issued, self.max_collision_probability));
+        let capacity = capacity(lengthened.entropy_bits(), self.max_collision_probability);
+        *generator = lengthened.clone();
+        self.capacity.store(capacity.max(issued), Ordering::Relaxed);
+
+        // Release the lock first, so the observer can read the generator it is told about.
+        drop(generator);
+        lengthened.notify(&GenerationEvent::Lengthened {
+            from,
+            to: lengthened.length(),
+            issued,
+        });
+    }
+
+    /// The generator currently in use
+    fn current(&self) -> RwLockReadGuard<'_, IdGenerator> {
+        self.generator
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// `-ln(1 - p)`, the expected number of colliding pairs at which `p` is the collision probability
+fn expected_pairs(p: f64) -> f64 {
+    -(-p).ln_1p()
+}
+
+/// Birthday bound on the probability of a collision among `issued` IDs with `bits` of entropy
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "an estimate, precise well beyond the number of IDs anyone issues"
+)]
+fn collision_probability(issued: u64, bits: f64) -> f64 {
+    let n = issued as f64;
+    let pairs = n * (n - 1.0).max(0.0) / 2.0;
+    -(-pairs / bits.exp2()).exp_m1()
+}
+
+/// Entropy needed for `issued` IDs to collide with probability at most `p`
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "an estimate, precise well beyond the number of IDs anyone issues"
+)]
+fn required_bits(issued: u64, p: f64) -> f64 {
+    let n = issued as f64;
+    (n * n / (2.0 * expected_pairs(p))).log2()
+}
+
+/// Number of IDs with `bits` of entropy that can be issued before the collision probability passes
+/// `p`
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "saturating float casts clamp to the range of u64"
+)]
+fn capacity(bits: f64, p: f64) -> u64 {
+    (2.0 * bits.exp2() * expected_pairs(p)).sqrt() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+    use super::*;
+    use crate::rng::default_rng;
+
+    #[test]
+    fn lengthens_before_passing_the_threshold() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&events);
+        let base = IdGenerator::new(4).with_observer(move |event| {
+            if let GenerationEvent::Lengthened { from, to, issued } = event {
+                log.lock().unwrap().push((*from, *to, *issued));
+            }
+        });
+        let generator = AdaptiveGenerator::new(base, 0.01);
+
+        let mut rng = default_rng();
+        let mut last_len = 4;
+        for _ in 0..5_000 {
+            let id = generator.generate_with_rng(&mut rng);
+            assert!(id.len() >= last_len);
+            last_len = id.len();
+            assert!(generator.collision_probability() <= 0.01);
+        }
+        assert_eq!(generator.issued(), 5_000);
+
+        let events = events.lock().unwrap();
+        assert!(!events.is_empty());
+        assert_eq!(events.first().map(|e| e.0), Some(4));
+        assert_eq!(events.last().map(|e| e.1), Some(generator.length()));
+        for pair in events.windows(2) {
+            assert_eq!(pair[0].1, pair[1].0);
+            assert!(pair[0].2 < pair[1].2);
+        }
+    }
+
+    #[test]
+    fn lengthens_when_told_about_issued_ids() {
+        let generator = AdaptiveGenerator::new(IdGenerator::new(8), 1e-6);
+        let initial = generator.length();
+        generator.record_issued(1);
+        assert_eq!(generator.length(), initial);
+
+        let restarted = AdaptiveGenerator::new(IdGenerator::new(8), 1e-6).with_issued(1_000_000);
+        assert!(restarted.length() > initial);
+        assert!(restarted.collision_probability() <= 1e-6);
+        assert_eq!(restarted.generator().length(), restarted.length());
+    }
+
+    #[test]
+    fn observers_can_read_the_generator() {
+        let adaptive = Arc::new(OnceLock::<Weak<AdaptiveGenerator>>::new());
+        let lengths = Arc::new(Mutex::new(Vec::new()));
+        let (observed, log) = (Arc::clone(&adaptive), Arc::clone(&lengths));
+        let base = IdGenerator::new(4).with_observer(move |event| {
+            if let (GenerationEvent::Lengthened { to, .. }, Some(generator)) =
+                (event, observed.get().and_then(Weak::upgrade))
+            {
+                log.lock().unwrap().push((*to, generator.length()));
+            }
+        });
+        let generator = Arc::new(AdaptiveGenerator::new(base, 0.01));
+        adaptive.set(Arc::downgrade(&generator)).unwrap();
+
+        generator.record_issued(1_000);
+        let lengths = lengths.lock().unwrap();
+        assert_eq!(lengths.len(), 1);
+        assert_eq!(lengths[0], (generator.length(), generator.length()));
+    }
+
+    #[test]
+    fn issued_count_saturates() {
+        let generator = AdaptiveGenerator::new(IdGenerator::new(8), 0.01).with_issued(u64::MAX - 1);
+        generator.record_issued(u64::MAX);
+        assert_eq!(generator.issued(), u64::MAX);
+        let _ = generator.generate();
+        assert_eq!(generator.issued(), u64::MAX);
+    }
+
+    #[test]
+    fn birthday_bound() {
+        // 23 people sharing 365 birthdays collide about half the time.
+        let bits = 365_f64.log2();
+        assert!((collision_probability(23, bits) - 0.5).abs() < 0.01);
+        assert!(collision_probability(1, bits) == 0.0);
+        assert_eq!(capacity(bits, 0.5), 22);
+        assert!(required_bits(23, 0.5) < bits + 0.1);
+    }
+}
@@ -1,19 +1,111 @@
 // src/alphabet.rs
 //! Character handling and validation for user-friendly IDs
 
+use std::borrow::Cow;
+
 use crate::error::IdError;
 
-/// Primary generation alphabet (23 characters)
-pub const GEN_ALPHABET: [char; 23] = [
+/// Characters of the generation alphabet, which every other alphabet starts with
+///
+/// The check alphabet extends this with [`CHECK_ONLY_CHARS`] rather than repeating it, so the two
+/// can only diverge on purpose.
+const FRIENDLY_CHARS: [char; 23] = [
     'a', 'b', 'c', 'd', 'e', 'f', 'h', 'i', 'j', 'k', 'm', 'n', 'o', 'p', 'r', 's', 't', 'w', 'x',
     'y', '3', '4', 'v',
 ];
 
-/// Check bit alphabet (23 characters)
-pub const CHECK_ALPHABET: [char; 23] = [
-    'a', 'b', 'c', 'd', 'e', 'f', 'h', 'i', 'j', 'k', 'm', 'n', 'o', 'p', 'r', 's', 't', 'w', 'x',
-    'y', '3', '4', 'v',
-];
+/// Characters only valid as the check character, appended to the generation alphabet to form
+/// the check alphabet
+///
+/// Empty, so both alphabets are currently the same. Changing this changes the check character
+/// of existing IDs.
+const CHECK_ONLY_CHARS: [char; 0] = [];
+
+/// Primary generation alphabet (23 characters)
+pub const GEN_ALPHABET: [char; 23] = FRIENDLY_CHARS;
+
+/// Check bit alphabet, the generation alphabet followed by any check-only characters (23
+/// characters)
+pub const CHECK_ALPHABET: [char; GEN_ALPHABET.len() + CHECK_ONLY_CHARS.len()] =
+    extend_alphabet(&GEN_ALPHABET, &CHECK_ONLY_CHARS);
+
+/// Symbols only valid as the check character of [`FormatVersion::V3`](crate::checksum::FormatVersion::V3)
+/// IDs
+///
+/// None of them look or sound like an alphabet character, and with them the alphabet size is the
+/// prime 29.
+const EXTENDED_CHECK_ONLY_CHARS: [char; 6] = ['*', '=', '+', '#', '%', '~'];
+
+/// Check alphabet of [`FormatVersion::V3`](crate::checksum::FormatVersion::V3), the generation
+/// alphabet followed by six symbols (29 characters)
+///
+/// A larger check alphabet lowers the chance of a mistyped ID being accepted from 1 in 23 to 1 in
+/// 29, while the body keeps to the generation alphabet.
+pub const EXTENDED_CHECK_ALPHABET: [char; GEN_ALPHABET.len() + EXTENDED_CHECK_ONLY_CHARS.len()] =
+    extend_alphabet(&GEN_ALPHABET, &EXTENDED_CHECK_ONLY_CHARS);
+
+/// Concatenate `base` and `extra` into an alphabet of `N` characters
+///
+/// Panics, at compile time when used in a const, if `N` isn't the combined length or the result
+/// is not a valid alphabet, see [`validate_alphabet`].
+#[allow(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "const fn will fail early"
+)]
+const fn extend_alphabet<const N: usize>(base: &[char], extra: &[char]) -> [char; N] {
+    assert!(
+        base.len() + extra.len() == N,
+        "alphabet length must match its characters"
+    );
+    let mut chars = ['\0'; N];
+    let mut i = 0;
+    while i < N {
+        chars[i] = if i < base.len() {
+            base[i]
+        } else {
+            extra[i - base.len()]
+        };
+        i += 1;
+    }
+    validate_alphabet(&chars);
+    chars
+}
+
+/// Panic unless `chars` is usable as an alphabet
+///
+/// Characters must be ASCII, unchanged by lowercasing and [`normalize_char`], and unique, so every
+/// character has exactly one value and survives parsing.
+#[allow(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "const fn will fail early"
+)]
+const fn validate_alphabet(chars: &[char]) {
+    assert!(chars.len() > 2, "alphabet must have more than 2 characters");
+    assert!(
+        chars.len() < u8::MAX as usize,
+        "alphabet is too large for lookup table"
+    );
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        // IDs are ASCII by construction, see `Id::as_ascii_bytes`.
+        assert!(c.is_ascii(), "alphabet must be ASCII");
+        assert!(
+            c.to_ascii_lowercase() == c && normalize_char(c) == c,
+            "alphabet characters must be unchanged by normalization"
+        );
+        let mut j = i + 1;
+        while j < chars.len() {
+            assert!(chars[j] != c, "alphabet characters must be unique");
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+const _: () = validate_alphabet(&GEN_ALPHABET);
 
 /// LUT for check alphabet character lookup
 #[allow(
@@ -25,12 +117,8 @@ const CHECK_LOOKUP: [u8; 256] = {
     let mut lookup = [0; 256];
     let mut i = 0;
     while i < CHECK_ALPHABET.len() {
-        if i >= u8::MAX as usize {
-            panic!("Check alphabet is too large for lookup table");
-        } else {
-            lookup[CHECK_ALPHABET[i] as usize] = i as u8;
-            i += 1;
-        }
+        lookup[CHECK_ALPHABET[i] as usize] = i as u8;
+        i += 1;
     }
     lookup
 };
@@ -48,14 +136,215 @@ pub const fn normalize_char(c: char) -> char {
     }
 }
 
+/// Character pairs that read as a single character, with the character normalization replaces
+/// them by
+///
+/// Sequences are replaced in a single left-to-right pass, so a replacement must not itself be part
+/// of a sequence, which is checked at compile time.
+pub const AMBIGUOUS_SEQUENCES: &[(char, char, char)] = &[('r', 'n', 'm'), ('v', 'v', 'w')];
+
+// A replacement forming a new sequence would make normalization depend on the number of passes.
+const _: () = {
+    let mut i = 0;
+    while i < AMBIGUOUS_SEQUENCES.len() {
+        let replacement = AMBIGUOUS_SEQUENCES[i].2;
+        let mut j = 0;
+        while j < AMBIGUOUS_SEQUENCES.len() {
+            assert!(
+                AMBIGUOUS_SEQUENCES[j].0 != replacement && AMBIGUOUS_SEQUENCES[j].1 != replacement,
+                "ambiguous sequence replacements must not be part of a sequence"
+            );
+            j += 1;
+        }
+        i += 1;
+    }
+};
+
+/// Replacement for `first` followed by `second`, if they form an ambiguous sequence
+#[must_use]
+pub const fn ambiguous_replacement(first: char, second: char) -> Option<char> {
+    let mut i = 0;
+    while i < AMBIGUOUS_SEQUENCES.len() {
+        let (a, b, replacement) = AMBIGUOUS_SEQUENCES[i];
+        if a == first && b == second {
+            return Some(replacement);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Whether `c` is the first character of an ambiguous sequence, e.g. `r` of `rn`
+#[must_use]
+pub const fn starts_ambiguous_sequence(c: char) -> bool {
+    let mut i = 0;
+    while i < AMBIGUOUS_SEQUENCES.len() {
+        if AMBIGUOUS_SEQUENCES[i].0 == c {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Foldings applied instead of lowercasing for characters whose Unicode lowercase form is not a
+/// single alphabet character
+///
+/// Lowercasing is locale-independent, so the Turkish dotted capital `İ` becomes `i` followed by a
+/// combining dot and the dotless `ı` is left as is. Both are folded to `i`, as Turkish users type
+/// them for the `i` and `I` in IDs.
+pub const TURKISH_FOLDINGS: &[(char, char)] = &[('\u{130}', 'i'), ('\u{131}', 'i')];
+
+/// Case foldings applied while normalizing, on top of Unicode lowercasing
+///
+/// [`TURKISH_FOLDINGS`] are always applied, other locale-specific foldings can be added with
+/// [`CaseFolding::with_foldings`] and used with [`normalize_string_with`] or
+/// [`Id::from_str_with_folding`](crate::Id::from_str_with_folding).
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::alphabet::{CaseFolding, normalize_string_with};
+///
+/// // Lithuanian users may type a dotted i with an accent.
+/// const LITHUANIAN: CaseFolding = CaseFolding::with_foldings(&[('\u{12f}', 'i')]);
+/// assert_eq!(normalize_string_with("W\u{12f}\u{130}", &LITHUANIAN), "wii");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaseFolding {
+    /// Additional `(from, to)` foldings, checked before the built-in ones
+    extra: &'static [(char, char)],
+}
+
+impl CaseFolding {
+    /// Only the built-in foldings
+    pub const DEFAULT: Self = Self { extra: &[] };
+
+    /// Also fold each `(from, to)` pair, taking precedence over the built-in foldings
+    #[must_use]
+    pub const fn with_foldings(foldings: &'static [(char, char)]) -> Self {
+        Self { extra: foldings }
+    }
+
+    /// The character `c` folds to, or `None` if it is lowercased as usual
+    #[must_use]
+    pub fn fold(&self, c: char) -> Option<char> {
+        self.extra
+            .iter()
+            .chain(TURKISH_FOLDINGS)
+            .find(|(from, _)| *from == c)
+            .map(|(_, to)| *to)
+    }
+}
+
+impl Default for CaseFolding {
+    #[cfg_attr(test, mutants::skip)]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Invisible characters that rich text and copy-paste insert into IDs, removed by
+/// [`strip_invisible`]
+///
+/// Zero-width spaces, non-joiners, joiners and word joiners, byte order marks, soft hyphens, and
+/// the directional marks, embeddings and isolates that right-to-left text wraps IDs in, e.g. with
+/// [`Id::bidi_isolated`](crate::Id::bidi_isolated).
+pub const INVISIBLE_CHARS: &[char] = &[
+    '\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{feff}', '\u{ad}', '\u{200e}', '\u{200f}',
+    '\u{202a}', '\u{202b}', '\u{202c}', '\u{202d}', '\u{202e}', '\u{2066}', '\u{2067}', '\u{2068}',
+    '\u{2069}',
+];
+
+/// Remove every [`INVISIBLE_CHARS`] character from `s`, only allocating if there are any
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::alphabet::strip_invisible;
+///
+/// assert_eq!(strip_invisible("wcf\u{200b}ytx\u{ad}ww"), "wcfytxww");
+/// ```
+#[must_use]
+pub fn strip_invisible(s: &str) -> Cow<'_, str> {
+    if s.contains(INVISIBLE_CHARS) {
+        Cow::Owned(s.replace(INVISIBLE_CHARS, ""))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Apply Unicode compatibility normalization (NFKC) to `s`, only allocating if it isn't ASCII
+///
+/// Styled "fancy text" such as mathematical alphanumerics, fullwidth forms and circled letters
+/// becomes plain ASCII, so IDs pasted from styled social media posts can be parsed.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::alphabet::fold_compatibility;
+///
+/// assert_eq!(fold_compatibility("\u{1d5ee}\u{ff41}\u{24d0}"), "aaa");
+/// ```
+#[cfg(feature = "unicode-normalization")]
+#[must_use]
+pub fn fold_compatibility(s: &str) -> Cow<'_, str> {
+    use unicode_normalization::UnicodeNormalization;
+
+    if s.is_ascii() {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(s.nfkc().collect())
+    }
+}
+
 /// Normalize and replace ambiguous sequences in a string
+#[must_use]
 pub fn normalize_string(s: &str) -> String {
-    s.to_lowercase()
-        .chars()
-        .map(normalize_char)
-        .collect::<String>()
-        .replace("rn", "m")
-        .replace("vv", "w")
+    normalize_string_with(s, &CaseFolding::DEFAULT)
+}
+
+/// Normalize and replace ambiguous sequences in a string, with additional case foldings
+#[must_use]
+pub fn normalize_string_with(s: &str, folding: &CaseFolding) -> String {
+    let mut normalized = String::with_capacity(s.len());
+    if s.is_ascii() && folding.extra.is_empty() {
+        // Lowercasing ASCII never changes the length, so this is a single allocation.
+        push_normalized(
+            &mut normalized,
+            s.bytes().map(|b| char::from(b.to_ascii_lowercase())),
+        );
+    } else {
+        let mut lowercase = String::with_capacity(s.len());
+        for c in s.chars() {
+            match folding.fold(c) {
+                Some(folded) => lowercase.push(folded),
+                None => lowercase.extend(c.to_lowercase()),
+            }
+        }
+        push_normalized(&mut normalized, lowercase.chars());
+    }
+    normalized
+}
+
+/// Normalize lowercase `chars` onto `out`, replacing ambiguous sequences as they complete
+fn push_normalized(out: &mut String, chars: impl Iterator<Item = char>) {
+    // First character of a possible ambiguous sequence, held back until the next one is known.
+    let mut pending = None;
+    for c in chars.map(normalize_char) {
+        if let Some(first) = pending.take() {
+            if let Some(replacement) = ambiguous_replacement(first, c) {
+                out.push(replacement);
+                continue;
+            }
+            out.push(first);
+        }
+        if starts_ambiguous_sequence(c) {
+            pending = Some(c);
+        } else {
+            out.push(c);
+        }
+    }
+    if let Some(first) = pending {
+        out.push(first);
+    }
 }
 
 /// Validate a character against the check alphabet
@@ -71,42 +360,134 @@ pub fn validate_char(c: char) -> Result<(), IdError> {
     }
 }
 
+/// Validate a character of an ID body against the generation alphabet
+///
+/// Check characters may come from a larger alphabet, see [`EXTENDED_CHECK_ALPHABET`], so only the
+/// body of an ID can be validated with this.
+///
+/// ## Errors
+///
+/// - [`IdError::InvalidCharacter`] if the character is not in the generation alphabet
+pub fn validate_gen_char(c: char) -> Result<(), IdError> {
+    if GEN_ALPHABET.contains(&c) {
+        Ok(())
+    } else {
+        Err(IdError::InvalidCharacter)
+    }
+}
+
+/// Value of a check alphabet character in the check character sum
+///
+/// Returns `None` for characters outside the check alphabet.
+pub(crate) fn check_value(c: char) -> Option<u8> {
+    if CHECK_ALPHABET.contains(&c) {
+        CHECK_LOOKUP.get(c as usize).copied()
+    } else {
+        None
+    }
+}
+
+/// Validate an ID that is already in canonical form, usable in const contexts
+///
+/// Unlike parsing, nothing is normalized: every character must already be in its alphabet, so
+/// uppercase letters and ambiguous sequences such as `rn` are rejected.
+///
+/// ## Errors
+///
+/// - [`IdError::TooShort`] if `s` is shorter than four characters
+/// - [`IdError::InvalidCharacter`] if a character is not in the generation alphabet, or the check
+///   character not in the check alphabet
+/// - [`IdError::InvalidSequence`] if `s` contains `rn` or `vv`
+/// - [`IdError::InvalidCheckBit`] if the check character doesn't match
+#[allow(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "indices are bounds checked by the loop, and sums can't overflow for any string that fits in memory"
+)]
+pub(crate) const fn validate_canonical(s: &str) -> Result<(), IdError> {
+    let bytes = s.as_bytes();
+    if bytes.len() < crate::id::MIN_LENGTH {
+        return Err(IdError::TooShort);
+    }
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        // The body is validated against the generation alphabet, the check character against
+        // the check alphabet.
+        let alphabet: &[char] = if i + 1 < bytes.len() {
+            &GEN_ALPHABET
+        } else {
+            &CHECK_ALPHABET
+        };
+        let mut known = false;
+        let mut j = 0;
+        while j < alphabet.len() {
+            if alphabet[j] as u32 == b as u32 {
+                known = true;
+            }
+            j += 1;
+        }
+        if !known {
+            return Err(IdError::InvalidCharacter);
+        }
+
+        if i > 0 && ambiguous_replacement(bytes[i - 1] as char, b as char).is_some() {
+            return Err(IdError::InvalidSequence);
+        }
+        i += 1;
+    }
+
+    let (body, check) = bytes.split_at(bytes.len() - 1);
+    let Ok(body) = std::str::from_utf8(body) else {
+        return Err(IdError::InvalidCharacter);
+    };
+    match calculate_check_char(body) {
+        Ok(expected) if expected as u32 == check[0] as u32 => Ok(()),
+        Ok(_) => Err(IdError::InvalidCheckBit),
+        Err(e) => Err(e),
+    }
+}
+
 /// Calculate expected check character for a string
 ///
+/// Usable in const contexts, e.g. to derive the check character of a well-known ID at compile
+/// time.
+///
 /// ## Errors
 ///
-/// - [`IdError::InvalidCharacter`] if a character is not in the check alphabet
-/// - [`IdError::InvalidCheckBit`] if the check bit calculation fails
-pub fn calculate_check_char(s: &str) -> Result<char, IdError> {
+/// - [`IdError::InvalidCharacter`] if a character is outside the first 256 code points
+#[allow(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "indices are bounds checked by the loop, and sums can't overflow for any string that fits in memory"
+)]
+pub const fn calculate_check_char(s: &str) -> Result<char, IdError> {
     const _: () = assert!(
         std::mem::size_of::<usize>() == 8,
         "This function is only safe on 64-bit platforms"
     );
 
-    let sum: u64 = s
-        .chars()
-        .map(|c| {
-            CHECK_LOOKUP
-                .get(c as usize)
-                .copied()
-                .ok_or(IdError::InvalidCharacter)
-                .map(u64::from)
-        })
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .sum();
-
-    #[allow(
-        clippy::cast_possible_truncation,
-        reason = "u64 -> usize is safe, and we check that this is only used on 64-bit platforms."
-    )]
-    let index = (sum
-        .checked_rem(CHECK_ALPHABET.len() as u64)
-        .ok_or(IdError::InvalidCheckBit)?) as usize;
-    CHECK_ALPHABET
-        .get(index)
-        .copied()
-        .ok_or(IdError::InvalidCheckBit)
+    let bytes = s.as_bytes();
+    let mut sum = 0_usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        // Only code points below 256 have a lookup entry. Those above 127 are encoded as two bytes
+        // with a lead byte of 0xC2 or 0xC3, anything else non-ASCII is out of range.
+        let code_point = match b {
+            0x00..=0x7F => b as usize,
+            0xC2 | 0xC3 if i + 1 < bytes.len() => {
+                i += 1;
+                ((b as usize & 0x1F) << 6) | (bytes[i] as usize & 0x3F)
+            }
+            _ => return Err(IdError::InvalidCharacter),
+        };
+        sum += CHECK_LOOKUP[code_point] as usize;
+        i += 1;
+    }
+
+    Ok(CHECK_ALPHABET[sum % CHECK_ALPHABET.len()])
 }
 
 #[cfg(test)]
@@ -123,6 +504,138 @@ mod tests {
         insta::assert_debug_snapshot!(crate::alphabet::CHECK_LOOKUP);
     }
 
+    #[test]
+    fn check_alphabet_extends_generation_alphabet() {
+        use crate::alphabet::{CHECK_ALPHABET, CHECK_ONLY_CHARS, GEN_ALPHABET, extend_alphabet};
+
+        assert_eq!(
+            CHECK_ALPHABET.get(..GEN_ALPHABET.len()),
+            Some(&GEN_ALPHABET[..])
+        );
+        assert_eq!(
+            CHECK_ALPHABET.get(GEN_ALPHABET.len()..),
+            Some(&CHECK_ONLY_CHARS[..])
+        );
+        assert_eq!(
+            extend_alphabet::<25>(&GEN_ALPHABET, &['*', '=']).get(23..),
+            Some(&['*', '='][..])
+        );
+    }
+
+    #[test]
+    #[should_panic = "alphabet characters must be unique"]
+    fn duplicate_alphabet_characters_are_rejected() {
+        crate::alphabet::extend_alphabet::<24>(&crate::alphabet::GEN_ALPHABET, &['a']);
+    }
+
+    #[test]
+    #[should_panic = "alphabet characters must be unchanged by normalization"]
+    fn normalized_alphabet_characters_are_rejected() {
+        crate::alphabet::extend_alphabet::<24>(&crate::alphabet::GEN_ALPHABET, &['0']);
+    }
+
+    #[test]
+    fn check_char_is_const() {
+        const CHECK: Result<char, crate::error::IdError> =
+            crate::alphabet::calculate_check_char("wcfytxww4opin4jmjjes4ccf");
+        assert_eq!(CHECK, Ok('d'));
+    }
+
+    #[test]
+    fn check_char_handles_non_ascii() {
+        use crate::{alphabet::calculate_check_char, error::IdError};
+
+        // Code points below 256 outside the alphabet count as zero, anything above is rejected.
+        assert_eq!(
+            calculate_check_char("ab\u{e9}c"),
+            calculate_check_char("abc")
+        );
+        assert_eq!(
+            calculate_check_char("ab\u{ff}c"),
+            calculate_check_char("abc")
+        );
+        assert_eq!(
+            calculate_check_char("ab\u{100}c"),
+            Err(IdError::InvalidCharacter)
+        );
+        assert_eq!(
+            calculate_check_char("ab\u{1F600}c"),
+            Err(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn validate_canonical_agrees_with_parsing() {
+        use crate::alphabet::validate_canonical;
+        use crate::error::IdError;
+
+        for _ in 0..1_000 {
+            let id = Id::new(12);
+            assert_eq!(validate_canonical(&id), Ok(()));
+            assert_eq!(
+                validate_canonical(&id.to_uppercase()),
+                Err(IdError::InvalidCharacter)
+            );
+        }
+        assert_eq!(validate_canonical("wcf"), Err(IdError::TooShort));
+        assert_eq!(validate_canonical("wcfytxww4opin4jmjjes4ccfd"), Ok(()));
+        assert_eq!(
+            validate_canonical("wcfytxww4opin4jmjjes4ccfe"),
+            Err(IdError::InvalidCheckBit)
+        );
+        assert_eq!(validate_canonical("arnmx"), Err(IdError::InvalidSequence));
+        assert_eq!(validate_canonical("avvwx"), Err(IdError::InvalidSequence));
+    }
+
+    #[test]
+    fn ascii_fast_path_matches_unicode_lowercasing() {
+        use rand::Rng;
+
+        let unicode = |s: &str| {
+            s.replace(['\u{130}', '\u{131}'], "i")
+                .to_lowercase()
+                .chars()
+                .map(crate::alphabet::normalize_char)
+                .collect::<String>()
+                .replace("rn", "m")
+                .replace("vv", "w")
+        };
+        let mut rng = rand::rng();
+        for _ in 0..10_000 {
+            let len = rng.random_range(0..32);
+            let s = (0..len)
+                .map(|_| char::from(rng.random_range(0x20_u8..0x7f)))
+                .collect::<String>();
+            assert_eq!(normalize_string(&s), unicode(&s), "{s:?}");
+        }
+        for s in ["ÀBÇ", "WCFY\u{130}", "rn\u{212a}vv"] {
+            assert_eq!(normalize_string(s), unicode(s), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn ambiguous_sequences_are_replaced_in_one_pass() {
+        use rand::{Rng, seq::IndexedRandom};
+
+        let two_pass = |s: &str| normalize_string(s).replace("rn", "m").replace("vv", "w");
+        let mut rng = rand::rng();
+        let chars = ['r', 'n', 'v', 'u', 'm', 'w', 'R', 'N', 'V', 'a'];
+        for _ in 0..10_000 {
+            let len = rng.random_range(0..16);
+            let s = (0..len)
+                .filter_map(|_| chars.choose(&mut rng))
+                .collect::<String>();
+            assert_eq!(normalize_string(&s), two_pass(&s), "{s:?}");
+            assert_eq!(
+                normalize_string(&normalize_string(&s)),
+                normalize_string(&s)
+            );
+        }
+        assert_eq!(normalize_string("rrnn"), "rmn");
+        assert_eq!(normalize_string("vvv"), "wv");
+        assert_eq!(normalize_string("UVuR"), "wvr");
+    }
+
     #[test]
     fn edge_case_1() {
         let id = String::from("9qg6G8B2Z5SIl170O");
@@ -0,0 +1,330 @@
+// src/audit.rs
+//! Bulk validation of stored IDs, e.g. after a change to the normalization rules
+//!
+//! [`validate_lines`] keeps every offending line. For nightly integrity jobs over far more IDs than
+//! fit in memory, [`validate_all`] (with the `rayon` feature) and [`validate_stream`] (with the
+//! `async` feature) only count results and keep the first few failures, see [`BulkSummary`].
+
+use std::collections::HashMap;
+
+use crate::{Id, checksum::FormatVersion, error::IdError};
+
+/// A line that failed to validate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Offender {
+    /// Line number, starting from 1
+    pub line: usize,
+    /// The line as read, without surrounding whitespace
+    pub input: String,
+    /// Why the line is not a valid ID
+    pub error: IdError,
+}
+
+/// Summary of a bulk validation, see [`validate_lines`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationReport {
+    /// Number of non-blank lines checked
+    pub total: usize,
+    /// Number of lines that are valid IDs, including non-canonical ones
+    pub valid: usize,
+    /// Line numbers of valid IDs that are not stored in canonical form, e.g. uppercase or containing
+    /// characters that normalize to others
+    pub non_canonical: Vec<usize>,
+    /// Number of invalid lines by error
+    pub errors: HashMap<IdError, usize>,
+    /// Every invalid line, in order
+    pub offenders: Vec<Offender>,
+}
+
+impl ValidationReport {
+    /// Number of invalid lines
+    #[must_use]
+    pub fn invalid(&self) -> usize {
+        self.offenders.len()
+    }
+
+    /// Number of invalid lines that failed with `error`
+    #[must_use]
+    pub fn count(&self, error: &IdError) -> usize {
+        self.errors.get(error).copied().unwrap_or_default()
+    }
+
+    /// Whether every line is a valid ID
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.offenders.is_empty()
+    }
+}
+
+/// Validate one ID per line, summarizing the results
+///
+/// Surrounding whitespace, including the `\r` of Windows line endings, is ignored, and blank lines
+/// are skipped but still counted towards line numbers.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{audit::validate_lines, error::IdError};
+///
+/// let export = "wcfytxww4opin4jmjjes4ccfd\nWCFYTXWW4OPIN4JMJJES4CCFD\n\nabc\n";
+/// let report = validate_lines(export.lines());
+/// assert_eq!(report.total, 3);
+/// assert_eq!(report.valid, 2);
+/// assert_eq!(report.non_canonical, vec![2]);
+/// assert_eq!(report.count(&IdError::TooShort), 1);
+/// assert_eq!(report.offenders[0].line, 4);
+/// ```
+pub fn validate_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for (line, input) in (1..).zip(lines) {
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        report.total = report.total.saturating_add(1);
+
+        match Id::parse_versioned(input, FormatVersion::V1) {
+            Ok(id) => {
+                report.valid = report.valid.saturating_add(1);
+                if id.as_str() != input {
+                    report.non_canonical.push(line);
+                }
+            }
+            Err(error) => {
+                let count = report.errors.entry(error.clone()).or_default();
+                *count = count.saturating_add(1);
+                report.offenders.push(Offender {
+                    line,
+                    input: input.to_owned(),
+                    error,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Aggregate statistics of a bulk validation, see [`validate_all`] and [`validate_stream`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BulkSummary {
+    /// Number of IDs checked
+    pub total: u64,
+    /// Number of valid IDs, including non-canonical ones
+    pub valid: u64,
+    /// Number of valid IDs not stored in canonical form
+    pub non_canonical: u64,
+    /// Number of invalid IDs by error
+    pub errors: HashMap<IdError, u64>,
+    /// The earliest invalid IDs, in input order, where `line` is the position starting from 1
+    pub failures: Vec<Offender>,
+}
+
+impl BulkSummary {
+    /// Number of invalid IDs
+    #[must_use]
+    pub fn invalid(&self) -> u64 {
+        self.total.saturating_sub(self.valid)
+    }
+
+    /// Whether every ID is valid
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.valid == self.total
+    }
+
+    /// Validate the ID at `position`, keeping at most `max_failures` failures
+    #[cfg(any(feature = "rayon", feature = "async"))]
+    fn record(&mut self, position: usize, input: &str, max_failures: usize) {
+        self.total = self.total.saturating_add(1);
+        match Id::parse_versioned(input, FormatVersion::V1) {
+            Ok(id) => {
+                self.valid = self.valid.saturating_add(1);
+                if id.as_str() != input {
+                    self.non_canonical = self.non_canonical.saturating_add(1);
+                }
+            }
+            Err(error) => {
+                let count = self.errors.entry(error.clone()).or_default();
+                *count = count.saturating_add(1);
+                if self.failures.len() < max_failures {
+                    self.failures.push(Offender {
+                        line: position.saturating_add(1),
+                        input: input.to_owned(),
+                        error,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Combine the summaries of two parts of the input, keeping the earliest failures
+    #[cfg(feature = "rayon")]
+    fn merge(mut self, other: Self, max_failures: usize) -> Self {
+        self.total = self.total.saturating_add(other.total);
+        self.valid = self.valid.saturating_add(other.valid);
+        self.non_canonical = self.non_canonical.saturating_add(other.non_canonical);
+        for (error, count) in other.errors {
+            let total = self.errors.entry(error).or_default();
+            *total = total.saturating_add(count);
+        }
+        self.failures.extend(other.failures);
+        self.failures.sort_by_key(|offender| offender.line);
+        self.failures.truncate(max_failures);
+        self
+    }
+}
+
+/// Validate IDs in parallel with `rayon`, keeping the first `max_failures` failures
+///
+/// IDs are validated as given, without trimming whitespace. Failures are numbered by their
+/// position in the input, so the same input always gives the same summary however the work was
+/// split between threads.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, audit::validate_all, error::IdError};
+/// use rayon::prelude::*;
+///
+/// let mut ids = (0..10_000).map(|_| Id::new(12).to_string()).collect::<Vec<_>>();
+/// ids[42] = String::from("abc");
+///
+/// let summary = validate_all(ids.par_iter(), 10);
+/// assert_eq!(summary.total, 10_000);
+/// assert_eq!(summary.invalid(), 1);
+/// assert_eq!(summary.failures[0].line, 43);
+/// assert_eq!(summary.errors[&IdError::TooShort], 1);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn validate_all<I>(ids: I, max_failures: usize) -> BulkSummary
+where
+    I: rayon::iter::IndexedParallelIterator,
+    I::Item: AsRef<str>,
+{
+    use rayon::iter::ParallelIterator;
+
+    ids.enumerate()
+        .fold(BulkSummary::default, |mut summary, (position, id)| {
+            summary.record(position, id.as_ref(), max_failures);
+            summary
+        })
+        .reduce(BulkSummary::default, |a, b| a.merge(b, max_failures))
+}
+
+/// Validate IDs as they arrive from a stream, keeping the first `max_failures` failures
+///
+/// Pairs with async database drivers, whose result sets are streams: rows are validated as they
+/// are fetched and dropped straight away, so memory use doesn't grow with the table. IDs are
+/// validated as given, without trimming whitespace.
+///
+/// # Example
+/// ```
+/// use futures_util::stream;
+/// use human_friendly_ids::{Id, audit::validate_stream};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let rows = stream::iter([Id::new(12).to_string(), String::from("abc")]);
+/// let summary = validate_stream(rows, 10).await;
+/// assert_eq!(summary.total, 2);
+/// assert_eq!(summary.failures[0].line, 2);
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn validate_stream<S>(ids: S, max_failures: usize) -> BulkSummary
+where
+    S: futures_util::Stream,
+    S::Item: AsRef<str>,
+{
+    use futures_util::StreamExt;
+
+    let mut summary = BulkSummary::default();
+    let mut ids = std::pin::pin!(ids.enumerate());
+    while let Some((position, id)) = ids.next().await {
+        summary.record(position, id.as_ref(), max_failures);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_lines() {
+        let ids = (0..10).map(|_| Id::new(12)).collect::<Vec<_>>();
+        let mut lines = ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+        lines.push(String::from("  "));
+        lines.push(format!("{}\r", ids[0].to_uppercase()));
+        lines.push(String::from("ab"));
+        lines.push(String::from("wc#ytxww"));
+        lines.push(String::from("ab"));
+
+        let report = validate_lines(lines.iter().map(String::as_str));
+        assert_eq!(report.total, 14);
+        assert_eq!(report.valid, 11);
+        assert_eq!(report.invalid(), 3);
+        assert_eq!(report.non_canonical, vec![12]);
+        assert_eq!(report.count(&IdError::TooShort), 2);
+        assert_eq!(report.count(&IdError::InvalidCheckBit), 1);
+        assert_eq!(report.count(&IdError::TooLong), 0);
+        assert_eq!(
+            report.offenders.iter().map(|o| o.line).collect::<Vec<_>>(),
+            vec![13, 14, 15]
+        );
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn empty_input_is_clean() {
+        let report = validate_lines(std::iter::empty());
+        assert_eq!(report, ValidationReport::default());
+        assert!(report.is_clean());
+    }
+
+    /// Inputs with known failures at positions 3, 500 and 9,000 and a non-canonical ID
+    #[cfg(any(feature = "rayon", feature = "async"))]
+    fn bulk_input() -> Vec<String> {
+        let mut ids = (0..10_000)
+            .map(|_| Id::new(12).to_string())
+            .collect::<Vec<_>>();
+        ids[2] = String::from("ab");
+        ids[499] = String::from("wcfytxww4opin4jmjjes4ccfe");
+        ids[8_999] = String::from("ab");
+        ids[7] = ids[7].to_uppercase();
+        ids
+    }
+
+    #[cfg(any(feature = "rayon", feature = "async"))]
+    fn assert_bulk_summary(summary: &BulkSummary) {
+        assert_eq!(summary.total, 10_000);
+        assert_eq!(summary.valid, 9_997);
+        assert_eq!(summary.invalid(), 3);
+        assert_eq!(summary.non_canonical, 1);
+        assert_eq!(summary.errors[&IdError::TooShort], 2);
+        assert_eq!(summary.errors[&IdError::InvalidCheckBit], 1);
+        assert_eq!(
+            summary.failures.iter().map(|o| o.line).collect::<Vec<_>>(),
+            vec![3, 500]
+        );
+        assert!(!summary.is_clean());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn validates_in_parallel() {
+        use rayon::prelude::*;
+
+        let ids = bulk_input();
+        for _ in 0..10 {
+            assert_bulk_summary(&validate_all(ids.par_iter(), 2));
+        }
+        assert!(validate_all(Vec::<String>::new().into_par_iter(), 2).is_clean());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn validates_streams() {
+        let ids = bulk_input();
+        assert_bulk_summary(&validate_stream(futures_util::stream::iter(&ids), 2).await);
+    }
+}
@@ -0,0 +1,354 @@
+// src/builder.rs
+//! Piecewise ID construction, with a running checksum or one calculated when sealed
+
+use crate::{
+    Id,
+    alphabet::{self, CHECK_ALPHABET},
+    checksum::FormatVersion,
+    error::IdError,
+    id::MIN_LENGTH,
+};
+
+/// Incrementally assembles an [`Id`], keeping the check character up to date as it goes
+///
+/// Useful when an ID is made of several parts, e.g. a fixed prefix, an encoded counter and a
+/// random tail, as the checksum is never recomputed from scratch.
+///
+/// Characters are validated by an [`IdBody`], so both accept exactly the same bodies.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, builder::IdBuilder};
+///
+/// let mut builder = IdBuilder::new();
+/// builder.push_str("acme")?.push_char('4')?;
+/// builder.push_str(&Id::new(6)[..5])?;
+/// let id = builder.finish()?;
+/// assert!(id.starts_with("acme4"));
+/// # Ok::<(), human_friendly_ids::error::IdError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdBuilder {
+    /// Characters pushed so far
+    body: IdBody,
+    /// Sum of the check values of `body`, modulo the check alphabet length
+    sum: usize,
+}
+
+impl IdBuilder {
+    /// Create an empty builder
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            body: IdBody::new(),
+            sum: 0,
+        }
+    }
+
+    /// Create an empty builder with space for a body of `capacity` characters
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            body: IdBody::with_capacity(capacity),
+            sum: 0,
+        }
+    }
+
+    /// Append a character from the generation alphabet
+    ///
+    /// ## Errors
+    ///
+    /// See [`IdBody::push`].
+    pub fn push_char(&mut self, c: char) -> Result<&mut Self, IdError> {
+        self.body.push(c)?;
+        self.add_to_sum(c);
+        Ok(self)
+    }
+
+    /// Append every character of `s`, see [`IdBuilder::push_char`]
+    ///
+    /// Nothing is appended if any character is rejected.
+    ///
+    /// ## Errors
+    ///
+    /// See [`IdBody::push`].
+    pub fn push_str(&mut self, s: &str) -> Result<&mut Self, IdError> {
+        self.body.push_str(s)?;
+        for c in s.chars() {
+            self.add_to_sum(c);
+        }
+        Ok(self)
+    }
+
+    /// The check character for the characters pushed so far
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - the sum is always reduced modulo the alphabet length."
+    )]
+    #[must_use]
+    pub fn current_check_char(&self) -> char {
+        CHECK_ALPHABET
+            .get(self.sum)
+            .copied()
+            .expect("sum is reduced modulo the check alphabet length")
+    }
+
+    /// The characters pushed so far
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.body.as_str()
+    }
+
+    /// Number of characters pushed so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.body.len()
+    }
+
+    /// Whether no characters have been pushed yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Append the check character and produce the [`Id`]
+    ///
+    /// ## Errors
+    ///
+    /// See [`IdBody::seal_as`].
+    pub fn finish(self) -> Result<Id, IdError> {
+        let check_char = self.current_check_char();
+        self.body.seal_with(check_char)
+    }
+
+    /// Add the check value of `c`, which the body has just accepted, to the running sum
+    fn add_to_sum(&mut self, c: char) {
+        let value = alphabet::check_value(c).expect("the body only accepts alphabet characters");
+        self.sum = self.sum.saturating_add(usize::from(value)) % CHECK_ALPHABET.len();
+    }
+}
+
+/// The body of an ID under construction, which only ever holds valid body characters
+///
+/// Unlike [`IdBuilder`], the check character is calculated once when the body is sealed, so it can
+/// be sealed in any [`FormatVersion`].
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, builder::IdBody, checksum::FormatVersion};
+///
+/// let mut body = IdBody::new();
+/// body.push_str("acme")?;
+/// body.push('4')?;
+/// assert_eq!(body.as_str(), "acme4");
+///
+/// let id = body.clone().seal()?;
+/// assert_eq!(IdBody::from(&id), body);
+///
+/// let v2 = body.seal_as(FormatVersion::V2)?;
+/// assert_eq!(Id::from_str_versioned(&v2, FormatVersion::V2), Ok(v2));
+/// # Ok::<(), human_friendly_ids::error::IdError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IdBody(String);
+
+impl IdBody {
+    /// Create an empty body
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(String::new())
+    }
+
+    /// Create an empty body with space for `capacity` characters
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(String::with_capacity(capacity.saturating_add(1)))
+    }
+
+    /// Append a character from the generation alphabet
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if `c` is not in the generation alphabet
+    /// - [`IdError::InvalidSequence`] if `c` would form an ambiguous sequence such as `rn`
+    pub fn push(&mut self, c: char) -> Result<(), IdError> {
+        alphabet::validate_gen_char(c)?;
+        if is_ambiguous(self.0.chars().next_back(), c) {
+            return Err(IdError::InvalidSequence);
+        }
+        self.0.push(c);
+        Ok(())
+    }
+
+    /// Append every character of `s`, see [`IdBody::push`]
+    ///
+    /// Nothing is appended if any character is rejected.
+    ///
+    /// ## Errors
+    ///
+    /// See [`IdBody::push`].
+    pub fn push_str(&mut self, s: &str) -> Result<(), IdError> {
+        let len = self.0.len();
+        for c in s.chars() {
+            if let Err(e) = self.push(c) {
+                self.0.truncate(len);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// The characters pushed so far
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Number of characters pushed so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no characters have been pushed yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Append the [`FormatVersion::V1`] check character, as used by [`Id::new`], and produce the
+    /// [`Id`]
+    ///
+    /// ## Errors
+    ///
+    /// See [`IdBody::seal_as`].
+    pub fn seal(self) -> Result<Id, IdError> {
+        self.seal_as(FormatVersion::V1)
+    }
+
+    /// Append the check character of `version` and produce the [`Id`]
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if the resulting ID would be too short to parse
+    /// - [`IdError::InvalidSequence`] if the check character would form an ambiguous sequence with
+    ///   the last character pushed
+    pub fn seal_as(self, version: FormatVersion) -> Result<Id, IdError> {
+        let check_char = version.algorithm().check_char(&self.0)?;
+        self.seal_with(check_char)
+    }
+
+    /// Append `check_char` and produce the [`Id`], see [`IdBody::seal_as`]
+    fn seal_with(self, check_char: char) -> Result<Id, IdError> {
+        if self.0.len() < MIN_LENGTH.saturating_sub(1) {
+            return Err(IdError::TooShort);
+        }
+
+        if is_ambiguous(self.0.chars().next_back(), check_char) {
+            return Err(IdError::InvalidSequence);
+        }
+
+        let mut id = self.0;
+        id.push(check_char);
+        Ok(Id(id.into()))
+    }
+}
+
+impl From<&Id> for IdBody {
+    /// The body of an existing ID, e.g. to re-seal it in another format
+    fn from(id: &Id) -> Self {
+        Self(id.body().to_owned())
+    }
+}
+
+impl AsRef<str> for IdBody {
+    #[cfg_attr(test, mutants::skip)]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Whether `next` following `last` would be rewritten by normalization
+const fn is_ambiguous(last: Option<char>, next: char) -> bool {
+    match last {
+        Some(last) => alphabet::ambiguous_replacement(last, next).is_some(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn running_check_matches_full_calculation() {
+        let id = Id::from_str("wcfytxww4opin4jmjjes4ccfd").expect("valid");
+        let mut builder = IdBuilder::with_capacity(24);
+        for c in id[..24].chars() {
+            builder.push_char(c).expect("alphabet character");
+            assert_eq!(
+                Ok(builder.current_check_char()),
+                alphabet::calculate_check_char(builder.as_str())
+            );
+        }
+        assert_eq!(builder.finish(), Ok(id));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let mut builder = IdBuilder::new();
+        assert_eq!(
+            builder.push_char('l').err(),
+            Some(IdError::InvalidCharacter)
+        );
+        assert_eq!(
+            builder.push_str("arn").err(),
+            Some(IdError::InvalidSequence)
+        );
+        assert!(
+            builder.is_empty(),
+            "failed pushes must not modify the builder"
+        );
+        assert_eq!(builder.clone().finish(), Err(IdError::TooShort));
+
+        // Body sums to the index of 'n', so the check character would turn "r" into "m".
+        builder.push_str("aybr").expect("valid body");
+        assert_eq!(builder.current_check_char(), 'n');
+        assert_eq!(builder.finish(), Err(IdError::InvalidSequence));
+    }
+
+    #[test]
+    fn body_seals_like_builder() {
+        for _ in 0..1_000 {
+            let id = Id::new(12);
+            let mut body = IdBody::with_capacity(11);
+            let mut builder = IdBuilder::with_capacity(11);
+            for c in id.body().chars() {
+                body.push(c).expect("alphabet character");
+                builder.push_char(c).expect("alphabet character");
+            }
+            assert_eq!(body, IdBody::from(&id));
+            assert_eq!(body.clone().seal(), builder.finish());
+            assert_eq!(body.seal(), Ok(id));
+        }
+    }
+
+    #[test]
+    fn body_rejects_invalid_input() {
+        let mut body = IdBody::new();
+        assert_eq!(body.push('l'), Err(IdError::InvalidCharacter));
+        assert_eq!(body.push_str("avv"), Err(IdError::InvalidSequence));
+        assert!(body.is_empty(), "failed pushes must not modify the body");
+        assert_eq!(body.clone().seal(), Err(IdError::TooShort));
+
+        body.push_str("aybr").expect("valid body");
+        assert_eq!(body.clone().seal(), Err(IdError::InvalidSequence));
+        for version in FormatVersion::ALL {
+            if let Ok(id) = body.clone().seal_as(*version) {
+                assert_eq!(Id::from_str_versioned(&id, *version), Ok(id));
+            }
+        }
+    }
+}
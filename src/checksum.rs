@@ -0,0 +1,426 @@
+// src/checksum.rs
+//! Check character algorithms and the transcription errors they detect
+//!
+//! IDs issued before [`FormatVersion::V2`] use the [`ChecksumAlgorithm::Sum`] check character,
+//! which can't tell `wcfy` from `wfcy`. [`Id::from_str`](std::str::FromStr::from_str) and
+//! [`Id::new`](crate::Id::new) stay on [`FormatVersion::V1`] so stored IDs remain valid, new
+//! deployments should opt into [`FormatVersion::V2`] through
+//! [`IdGenerator::with_format`](crate::generator::IdGenerator::with_format) and
+//! [`Id::from_str_versioned`](crate::Id::from_str_versioned). [`FormatVersion::V3`] additionally
+//! draws the check character from a larger alphabet, for a lower false-accept rate.
+
+use thiserror::Error;
+
+use crate::{
+    alphabet::{self, CHECK_ALPHABET, EXTENDED_CHECK_ALPHABET, GEN_ALPHABET},
+    error::IdError,
+};
+
+/// Body length the [`analysis`] is run against, i.e. IDs of 25 characters
+pub const ANALYSIS_BODY_LENGTH: usize = 24;
+
+/// Algorithm used to derive the check character from the body of an ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumAlgorithm {
+    /// Sum of the character values modulo the alphabet size
+    #[default]
+    Sum,
+    /// Sum of the character values, each multiplied by a power of [`WEIGHT_BASE`], modulo the
+    /// alphabet size
+    ///
+    /// The alphabet size is prime and the base is a primitive root, so every position has a
+    /// distinct non-zero weight within a cycle of 22 characters. This catches adjacent
+    /// transpositions as well as everything [`ChecksumAlgorithm::Sum`] catches.
+    Weighted,
+    /// [`ChecksumAlgorithm::Weighted`] over the larger [`EXTENDED_CHECK_ALPHABET`], with powers of
+    /// [`EXTENDED_WEIGHT_BASE`]
+    ///
+    /// The alphabet size is again prime, so this keeps every guarantee of
+    /// [`ChecksumAlgorithm::Weighted`] within a cycle of 28 characters, and accepts a random
+    /// mistyped ID less often.
+    Extended,
+}
+
+/// Base of the position weights used by [`ChecksumAlgorithm::Weighted`]
+pub const WEIGHT_BASE: u64 = 5;
+
+/// Base of the position weights used by [`ChecksumAlgorithm::Extended`], a primitive root of 29
+pub const EXTENDED_WEIGHT_BASE: u64 = 2;
+
+impl ChecksumAlgorithm {
+    /// Every available algorithm
+    pub const ALL: &'static [Self] = &[Self::Sum, Self::Weighted, Self::Extended];
+
+    /// Alphabet the check character is drawn from
+    ///
+    /// Always the generation alphabet followed by zero or more check-only characters.
+    #[must_use]
+    pub const fn check_alphabet(self) -> &'static [char] {
+        match self {
+            Self::Sum | Self::Weighted => &CHECK_ALPHABET,
+            Self::Extended => &EXTENDED_CHECK_ALPHABET,
+        }
+    }
+
+    /// Calculate the check character of `body`
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if a character is not in the check alphabet
+    /// - [`IdError::InvalidCheckBit`] if the check bit calculation fails
+    pub fn check_char(self, body: &str) -> Result<char, IdError> {
+        match self {
+            Self::Sum => alphabet::calculate_check_char(body),
+            Self::Weighted => weighted_check_char(body, &CHECK_ALPHABET, WEIGHT_BASE),
+            Self::Extended => {
+                weighted_check_char(body, &EXTENDED_CHECK_ALPHABET, EXTENDED_WEIGHT_BASE)
+            }
+        }
+    }
+}
+
+/// Version of the ID format, which determines the check character algorithm
+///
+/// Defaults to [`FormatVersion::V1`], the format [`Id::new`](crate::Id::new) and
+/// [`Id::from_str`](std::str::FromStr::from_str) use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatVersion {
+    /// The original format, using [`ChecksumAlgorithm::Sum`]
+    #[default]
+    V1,
+    /// Position-weighted check characters, using [`ChecksumAlgorithm::Weighted`]
+    V2,
+    /// Position-weighted check characters from a larger alphabet, using
+    /// [`ChecksumAlgorithm::Extended`]
+    ///
+    /// The check character may be a symbol such as `*` or `#`, which must be percent-encoded in
+    /// URLs.
+    V3,
+    /// [`FormatVersion::V1`] with the check character calculated over the body as typed, before
+    /// normalization, see [`ChecksumInput::Raw`]
+    ///
+    /// For validating IDs from systems that calculate check characters this way. Generated and
+    /// parsed IDs are in canonical form, which is the same as in [`FormatVersion::V1`].
+    V1Raw,
+}
+
+/// Form of the body a check character is calculated over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumInput {
+    /// The body after normalization, so `WCFY` and `wcfy` share a check character
+    #[default]
+    Normalized,
+    /// The body as typed, lowercased but before any other normalization
+    ///
+    /// Characters normalization would rewrite, such as `0` for `o`, count as zero in
+    /// [`ChecksumAlgorithm::Sum`] and are rejected by the other algorithms.
+    Raw,
+}
+
+impl FormatVersion {
+    /// Every format version, oldest first
+    pub const ALL: &'static [Self] = &[Self::V1, Self::V2, Self::V3, Self::V1Raw];
+
+    /// Check character algorithm used by this version
+    #[must_use]
+    pub const fn algorithm(self) -> ChecksumAlgorithm {
+        match self {
+            Self::V1 | Self::V1Raw => ChecksumAlgorithm::Sum,
+            Self::V2 => ChecksumAlgorithm::Weighted,
+            Self::V3 => ChecksumAlgorithm::Extended,
+        }
+    }
+
+    /// Form of the body this version calculates check characters over
+    #[must_use]
+    pub const fn checksum_input(self) -> ChecksumInput {
+        match self {
+            Self::V1 | Self::V2 | Self::V3 => ChecksumInput::Normalized,
+            Self::V1Raw => ChecksumInput::Raw,
+        }
+    }
+}
+
+/// A name that isn't one of the [`FormatVersion`]s
+#[derive(Error, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[error("Unknown format version")]
+pub struct UnknownFormatVersion;
+
+impl std::str::FromStr for FormatVersion {
+    type Err = UnknownFormatVersion;
+
+    /// Parse a version name, `v1`, `v2`, `v3` or `v1-raw`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "v1" => Ok(Self::V1),
+            "v2" => Ok(Self::V2),
+            "v3" => Ok(Self::V3),
+            "v1-raw" => Ok(Self::V1Raw),
+            _ => Err(UnknownFormatVersion),
+        }
+    }
+}
+
+impl std::fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::V1 => f.write_str("v1"),
+            Self::V2 => f.write_str("v2"),
+            Self::V3 => f.write_str("v3"),
+            Self::V1Raw => f.write_str("v1-raw"),
+        }
+    }
+}
+
+/// Calculate the position-weighted check character of `body` over `alphabet`
+///
+/// Body characters take their value from the generation alphabet, which every check alphabet
+/// starts with.
+fn weighted_check_char(body: &str, alphabet: &[char], base: u64) -> Result<char, IdError> {
+    let modulus = alphabet.len() as u64;
+    let mut sum = 0_u64;
+    let mut weight = 1_u64;
+
+    for c in body.chars() {
+        let value = alphabet::check_value(c).ok_or(IdError::InvalidCharacter)?;
+        sum = sum
+            .saturating_add(u64::from(value).saturating_mul(weight))
+            .checked_rem(modulus)
+            .ok_or(IdError::InvalidCheckBit)?;
+        weight = weight
+            .saturating_mul(base)
+            .checked_rem(modulus)
+            .ok_or(IdError::InvalidCheckBit)?;
+    }
+
+    usize::try_from(sum)
+        .ok()
+        .and_then(|index| alphabet.get(index).copied())
+        .ok_or(IdError::InvalidCheckBit)
+}
+
+/// How many errors of one class an algorithm detects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detection {
+    /// Number of errors that changed the check character
+    pub detected: usize,
+    /// Number of errors tried
+    pub total: usize,
+}
+
+impl Detection {
+    /// Fraction of errors detected, from `0.0` to `1.0`
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+
+        #[allow(clippy::cast_precision_loss, reason = "the counts are far below 2^52")]
+        let (detected, total) = (self.detected as f64, self.total as f64);
+        detected / total
+    }
+
+    /// Whether every error of this class is detected
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.detected == self.total
+    }
+
+    /// Record whether a single error was detected
+    const fn record(&mut self, detected: bool) {
+        self.total = self.total.saturating_add(1);
+        if detected {
+            self.detected = self.detected.saturating_add(1);
+        }
+    }
+}
+
+/// Error classes detected by a single algorithm, see [`analysis`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Analysis {
+    /// Algorithm analysed
+    pub algorithm: ChecksumAlgorithm,
+    /// One character replaced by another, e.g. `wcfy` → `wcty`
+    pub single_substitution: Detection,
+    /// Two different adjacent characters swapped, e.g. `wcfy` → `wfcy`
+    pub adjacent_transposition: Detection,
+    /// A doubled character replaced by another doubled character, e.g. `waay` → `wbby`
+    pub twin_substitution: Detection,
+}
+
+/// Report which error classes each available algorithm detects
+///
+/// Every error of each class is tried exhaustively at every position of a body of
+/// [`ANALYSIS_BODY_LENGTH`] characters, so the results are exact rather than sampled.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::checksum::{ChecksumAlgorithm, analysis};
+///
+/// let sum = analysis()
+///     .into_iter()
+///     .find(|a| a.algorithm == ChecksumAlgorithm::Sum)
+///     .unwrap();
+/// assert!(sum.single_substitution.is_complete());
+/// assert_eq!(sum.adjacent_transposition.detected, 0);
+/// ```
+#[must_use]
+pub fn analysis() -> Vec<Analysis> {
+    ChecksumAlgorithm::ALL
+        .iter()
+        .map(|algorithm| analyse(*algorithm))
+        .collect()
+}
+
+/// Exhaustively analyse a single algorithm
+fn analyse(algorithm: ChecksumAlgorithm) -> Analysis {
+    let mut report = Analysis {
+        algorithm,
+        single_substitution: Detection {
+            detected: 0,
+            total: 0,
+        },
+        adjacent_transposition: Detection {
+            detected: 0,
+            total: 0,
+        },
+        twin_substitution: Detection {
+            detected: 0,
+            total: 0,
+        },
+    };
+
+    // Every algorithm only looks at each character's value and position, so the surrounding
+    // characters can be fixed without loss of generality.
+    let filler = GEN_ALPHABET.first().copied().unwrap_or('a');
+    let body = |edits: &[(usize, char)]| {
+        let mut chars = vec![filler; ANALYSIS_BODY_LENGTH];
+        for (position, c) in edits {
+            if let Some(slot) = chars.get_mut(*position) {
+                *slot = *c;
+            }
+        }
+        chars.into_iter().collect::<String>()
+    };
+    let detects = |original: String, mistyped: String| {
+        algorithm.check_char(&original).ok() != algorithm.check_char(&mistyped).ok()
+    };
+
+    for position in 0..ANALYSIS_BODY_LENGTH {
+        let next = position.saturating_add(1);
+        for a in GEN_ALPHABET {
+            for b in GEN_ALPHABET.into_iter().filter(|b| *b != a) {
+                report
+                    .single_substitution
+                    .record(detects(body(&[(position, a)]), body(&[(position, b)])));
+
+                if next < ANALYSIS_BODY_LENGTH {
+                    report.adjacent_transposition.record(detects(
+                        body(&[(position, a), (next, b)]),
+                        body(&[(position, b), (next, a)]),
+                    ));
+                    report.twin_substitution.record(detects(
+                        body(&[(position, a), (next, a)]),
+                        body(&[(position, b), (next, b)]),
+                    ));
+                }
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_parsing_and_generation() {
+        assert_eq!(FormatVersion::default(), FormatVersion::V1);
+        assert_eq!(
+            FormatVersion::default().algorithm(),
+            ChecksumAlgorithm::default()
+        );
+        let id = crate::Id::new(12);
+        assert_eq!(
+            crate::Id::from_str_versioned(&id, FormatVersion::default()),
+            Ok(id)
+        );
+    }
+
+    #[test]
+    fn snapshot_analysis() {
+        // Integrators rely on these guarantees, so any change must be caught in review.
+        insta::assert_debug_snapshot!(analysis());
+    }
+
+    #[test]
+    fn sum_guarantees() {
+        let sum = analyse(ChecksumAlgorithm::Sum);
+        assert!(sum.single_substitution.is_complete());
+        assert!(sum.twin_substitution.is_complete());
+        assert_eq!(sum.adjacent_transposition.detected, 0);
+        assert!(sum.adjacent_transposition.rate().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn weighted_guarantees() {
+        let weighted = analyse(ChecksumAlgorithm::Weighted);
+        assert!(weighted.single_substitution.is_complete());
+        assert!(weighted.adjacent_transposition.is_complete());
+        assert!(weighted.twin_substitution.is_complete());
+    }
+
+    #[test]
+    fn extended_guarantees() {
+        let extended = analyse(ChecksumAlgorithm::Extended);
+        assert!(extended.single_substitution.is_complete());
+        assert!(extended.adjacent_transposition.is_complete());
+        assert!(extended.twin_substitution.is_complete());
+    }
+
+    #[test]
+    fn extended_check_chars_cover_the_larger_alphabet() {
+        use std::collections::HashSet;
+
+        let mut seen = HashSet::new();
+        for _ in 0..2_000 {
+            let id = crate::generator::IdGenerator::new(12)
+                .with_format(FormatVersion::V3)
+                .generate();
+            let check = id.chars().next_back().expect("IDs are never empty");
+            assert!(EXTENDED_CHECK_ALPHABET.contains(&check));
+            seen.insert(check);
+            assert_eq!(
+                crate::Id::from_str_versioned(&id, FormatVersion::V3),
+                Ok(id)
+            );
+        }
+        assert_eq!(seen.len(), EXTENDED_CHECK_ALPHABET.len());
+        assert_eq!(
+            ChecksumAlgorithm::Extended.check_char("wcf*"),
+            Err(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn weighted_rejects_unknown_characters() {
+        assert_eq!(
+            ChecksumAlgorithm::Weighted.check_char("wcf-y"),
+            Err(IdError::InvalidCharacter)
+        );
+        assert_eq!(ChecksumAlgorithm::Weighted.check_char(""), Ok('a'));
+    }
+
+    #[test]
+    fn sum_matches_parsing() {
+        let id = crate::Id::new(25);
+        let (body, check) = id.split_at(24);
+        assert_eq!(
+            ChecksumAlgorithm::Sum.check_char(body).map(String::from),
+            Ok(check.to_string())
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// src/compat.rs
+//! Compatibility adapters for RNGs from older versions of `rand`
+
+/// Adapter allowing a `rand_core` 0.6 (`rand` 0.8-era) RNG to be used for generation
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, compat::Compat06};
+/// # struct OldRng(u64);
+/// # impl rand_core_06::RngCore for OldRng {
+/// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+/// #     fn next_u64(&mut self) -> u64 { self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1); self.0 }
+/// #     fn fill_bytes(&mut self, dest: &mut [u8]) { rand_core_06::impls::fill_bytes_via_next(self, dest) }
+/// #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core_06::Error> { self.fill_bytes(dest); Ok(()) }
+/// # }
+///
+/// let mut old_rng = OldRng(42);
+/// let id = Id::new_with_rng(12, &mut Compat06(&mut old_rng));
+/// assert_eq!(id.len(), 12);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compat06<R>(pub R);
+
+impl<R: rand_core_06::RngCore> rand_core::RngCore for Compat06<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0.fill_bytes(dst);
+    }
+}
+
+impl<R: rand_core_06::RngCore + rand_core_06::CryptoRng> rand_core::CryptoRng for Compat06<R> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal `rand_core` 0.6 RNG, replaying a fixed sequence of values
+    struct ReplayRng(std::vec::IntoIter<u64>);
+
+    impl rand_core_06::RngCore for ReplayRng {
+        fn next_u32(&mut self) -> u32 {
+            u32::try_from(self.next_u64() >> 32).expect("shifted into range")
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next().expect("ran out of values")
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core_06::impls::fill_bytes_via_next(self, dest);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core_06::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn old_rngs_drive_generation() {
+        let values = (0..1_000_u64)
+            .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+            .collect::<Vec<_>>();
+        let a = crate::Id::new_with_rng(25, &mut Compat06(ReplayRng(values.clone().into_iter())));
+        let b = crate::Id::new_with_rng(25, &mut Compat06(ReplayRng(values.into_iter())));
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 25);
+    }
+}
@@ -0,0 +1,295 @@
+// src/composite.rs
+//! Hierarchical composite IDs such as `acct-wcfytd.inv-kmop3s`
+//!
+//! Each segment pairs a kind (`acct`, `inv`) with an [`Id`] that carries its own check character,
+//! so every level of the hierarchy can be validated independently. At most
+//! [`MAX_PARTS`](crate::segmented::MAX_PARTS) levels are accepted.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    Id,
+    segmented::{JoinError, MAX_PARTS, fmt_joined, parse_joined},
+};
+
+/// Separator between the segments of a composite ID
+pub const SEGMENT_SEPARATOR: char = '.';
+
+/// Separator between the kind and the ID within a segment
+pub const KIND_SEPARATOR: char = '-';
+
+/// Maximum length of a segment kind
+pub const MAX_KIND_LENGTH: usize = 16;
+
+/// A single level of a [`CompositeId`], e.g. `inv-kmop3s`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// Kind of entity this segment identifies, lowercase ASCII letters and digits
+    kind: String,
+    /// Identifier of the entity
+    id: Id,
+}
+
+impl Segment {
+    /// Create a segment from a kind and an ID
+    ///
+    /// ## Errors
+    ///
+    /// - [`JoinError::InvalidKind`] if `kind` is empty, longer than [`MAX_KIND_LENGTH`] or contains
+    ///   anything but ASCII letters and digits
+    pub fn new(kind: &str, id: Id) -> Result<Self, JoinError> {
+        if kind.is_empty()
+            || kind.len() > MAX_KIND_LENGTH
+            || !kind.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(JoinError::InvalidKind);
+        }
+
+        Ok(Self {
+            kind: kind.to_ascii_lowercase(),
+            id,
+        })
+    }
+
+    /// Kind of entity this segment identifies
+    #[must_use]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Identifier of the entity
+    #[must_use]
+    pub const fn id(&self) -> &Id {
+        &self.id
+    }
+}
+
+impl FromStr for Segment {
+    type Err = JoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = s.split_once(KIND_SEPARATOR).ok_or(JoinError::MissingKind)?;
+        Self::new(kind, id.parse()?)
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.kind, KIND_SEPARATOR, self.id)
+    }
+}
+
+/// A parent/child chain of [`Segment`]s, e.g. `acct-wcfytd.inv-kmop3s`
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, composite::CompositeId};
+///
+/// let account = CompositeId::root("acct", Id::new(8)).unwrap();
+/// let invoice = account.child("inv", Id::new(8)).unwrap();
+///
+/// let parsed: CompositeId = invoice.to_string().parse().unwrap();
+/// assert_eq!(parsed, invoice);
+/// assert_eq!(parsed.parent(), Some(account));
+/// assert_eq!(parsed.leaf().kind(), "inv");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompositeId {
+    /// Segments from the root down, never empty
+    segments: Vec<Segment>,
+}
+
+impl CompositeId {
+    /// Create a composite ID with a single root segment
+    ///
+    /// ## Errors
+    ///
+    /// See [`Segment::new`].
+    pub fn root(kind: &str, id: Id) -> Result<Self, JoinError> {
+        Ok(Self {
+            segments: vec![Segment::new(kind, id)?],
+        })
+    }
+
+    /// Create a composite ID nested one level below this one
+    ///
+    /// ## Errors
+    ///
+    /// - [`JoinError::TooManyParts`] if this ID is already [`MAX_PARTS`] levels deep
+    /// - Any error from [`Segment::new`]
+    pub fn child(&self, kind: &str, id: Id) -> Result<Self, JoinError> {
+        if self.segments.len() >= MAX_PARTS {
+            return Err(JoinError::TooManyParts);
+        }
+        let mut segments = self.segments.clone();
+        segments.push(Segment::new(kind, id)?);
+        Ok(Self { segments })
+    }
+
+    /// The composite ID one level up, or `None` for a root
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        let (_, parents) = self.segments.split_last()?;
+        (!parents.is_empty()).then(|| Self {
+            segments: parents.to_vec(),
+        })
+    }
+
+    /// Segments from the root down
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// The most deeply nested segment
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - segments are never empty."
+    )]
+    #[must_use]
+    pub fn leaf(&self) -> &Segment {
+        self.segments
+            .last()
+            .expect("composite IDs have at least one segment")
+    }
+
+    /// The ID of the first segment of a given kind
+    #[must_use]
+    pub fn get(&self, kind: &str) -> Option<&Id> {
+        self.segments
+            .iter()
+            .find(|segment| segment.kind.eq_ignore_ascii_case(kind))
+            .map(Segment::id)
+    }
+}
+
+impl FromStr for CompositeId {
+    type Err = JoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments = parse_joined(s, SEGMENT_SEPARATOR, Segment::from_str)?;
+        Ok(Self { segments })
+    }
+}
+
+impl TryFrom<String> for CompositeId {
+    type Error = JoinError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl fmt::Display for CompositeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_joined(f, &self.segments, SEGMENT_SEPARATOR)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize composite IDs in their joined string form
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::CompositeId;
+    use crate::segmented::serde_impl::{deserialize, serialize};
+
+    impl Serialize for CompositeId {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serialize(self, serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CompositeId {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::IdError;
+
+    #[test]
+    fn parse_and_display() {
+        let account = Id::new(6);
+        let invoice = Id::new(6);
+        let raw = format!("ACCT-{}.inv-{}", account, invoice.to_uppercase());
+
+        let composite = CompositeId::from_str(&raw).expect("valid composite ID");
+        assert_eq!(composite.segments().len(), 2);
+        assert_eq!(composite.get("acct"), Some(&account));
+        assert_eq!(composite.get("inv"), Some(&invoice));
+        assert_eq!(composite.get("user"), None);
+        assert_eq!(
+            composite.to_string(),
+            format!("acct-{}.inv-{}", account, invoice)
+        );
+        assert_eq!(composite.parent().and_then(|p| p.parent()), None);
+    }
+
+    #[test]
+    fn each_segment_is_validated() {
+        let valid = Id::new(6);
+        let mut broken = valid.to_string();
+        broken.pop();
+        broken.push(if valid.ends_with('a') { 'b' } else { 'a' });
+
+        assert_eq!(
+            CompositeId::from_str(&format!("acct-{valid}.inv-{broken}")),
+            Err(JoinError::Id(IdError::InvalidCheckBit))
+        );
+        assert_eq!(
+            CompositeId::from_str(&format!("acct-{valid}.{valid}")),
+            Err(JoinError::MissingKind)
+        );
+        assert_eq!(
+            CompositeId::from_str(&format!("-{valid}")),
+            Err(JoinError::InvalidKind)
+        );
+        assert_eq!(
+            CompositeId::from_str(&format!("a_b-{valid}")),
+            Err(JoinError::InvalidKind)
+        );
+    }
+
+    #[test]
+    fn depth_is_bounded() {
+        let mut composite = CompositeId::root("l", Id::new(6)).expect("valid kind");
+        while composite.segments().len() < MAX_PARTS {
+            composite = composite.child("l", Id::new(6)).expect("valid kind");
+        }
+        assert_eq!(
+            composite.child("l", Id::new(6)),
+            Err(JoinError::TooManyParts)
+        );
+        assert_eq!(
+            CompositeId::from_str(&composite.to_string()),
+            Ok(composite.clone())
+        );
+        assert_eq!(
+            CompositeId::from_str(&format!("{composite}.l-{}", Id::new(6))),
+            Err(JoinError::TooManyParts)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let composite = CompositeId::root("acct", Id::new(8))
+            .and_then(|c| c.child("inv", Id::new(8)))
+            .expect("valid kinds");
+        let serialized = serde_json::to_string(&composite).expect("serializes");
+        assert_eq!(serialized, format!("\"{composite}\""));
+        let deserialized: CompositeId = serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, composite);
+    }
+}
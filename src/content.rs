@@ -0,0 +1,241 @@
+// src/content.rs
+//! Content-addressed and keyed IDs derived from a hash of arbitrary bytes
+//!
+//! The digest of the input keys a `ChaCha20` keystream, whose bytes are mapped to the generation
+//! alphabet by a fixed derivation. Identical payloads therefore always map to identical IDs, which
+//! never change with the generator, and the IDs obey the same sequence rules as randomly generated
+//! ones.
+//!
+//! The hash function is always passed explicitly: enabling a feature anywhere in the dependency
+//! graph must not change which IDs are derived.
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+
+use crate::{
+    Id,
+    alphabet::{self, GEN_ALPHABET},
+};
+
+/// Bytes of the keystream at or above this are skipped, so every character is equally likely
+const SAMPLE_LIMIT: usize = 256 - 256 % GEN_ALPHABET.len();
+
+/// Context string used to stretch arbitrary keys into a BLAKE3 key
+#[cfg(feature = "blake3")]
+const BLAKE3_KEY_CONTEXT: &str = "human-friendly-ids 2025-01-01 Id::derive key";
+
+/// Hash function used to derive content-addressed IDs
+///
+/// IDs derived with different hash functions are unrelated, so pick one and stick with it for a
+/// given dataset. There is deliberately no default: features are additive, so a default that
+/// depended on them could change under you when another crate enables one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentHash {
+    /// BLAKE3, enabled by the `blake3` feature
+    #[cfg(feature = "blake3")]
+    Blake3,
+    /// SHA-256, enabled by the `sha2` feature
+    #[cfg(feature = "sha2")]
+    Sha256,
+}
+
+impl ContentHash {
+    /// Every hash function enabled by features
+    pub const ALL: &'static [Self] = &[
+        #[cfg(feature = "blake3")]
+        Self::Blake3,
+        #[cfg(feature = "sha2")]
+        Self::Sha256,
+    ];
+
+    /// Hash `bytes` to a 32 byte digest
+    fn digest(self, bytes: &[u8]) -> [u8; 32] {
+        match self {
+            #[cfg(feature = "blake3")]
+            Self::Blake3 => *blake3::hash(bytes).as_bytes(),
+            #[cfg(feature = "sha2")]
+            Self::Sha256 => {
+                use sha2::Digest;
+                sha2::Sha256::digest(bytes).into()
+            }
+        }
+    }
+
+    /// Compute a keyed digest of `bytes`, BLAKE3 in keyed mode or HMAC-SHA256
+    pub(crate) fn keyed_digest(self, key: &[u8], bytes: &[u8]) -> [u8; 32] {
+        match self {
+            #[cfg(feature = "blake3")]
+            Self::Blake3 => {
+                let key = blake3::derive_key(BLAKE3_KEY_CONTEXT, key);
+                *blake3::keyed_hash(&key, bytes).as_bytes()
+            }
+            #[cfg(feature = "sha2")]
+            Self::Sha256 => {
+                use hmac::{KeyInit, Mac};
+                let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(bytes);
+                mac.finalize().into_bytes().into()
+            }
+        }
+    }
+}
+
+impl Id {
+    /// Derive an ID of a given length from the contents of `bytes`
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, content::ContentHash};
+    ///
+    /// let hash = ContentHash::ALL[0];
+    /// let a = Id::from_content_with(hash, b"hello world", 12);
+    /// let b = Id::from_content_with(hash, b"hello world", 12);
+    /// assert_eq!(a, b);
+    /// ```
+    #[must_use]
+    pub fn from_content_with(hash: ContentHash, bytes: &[u8], len: usize) -> Self {
+        from_digest(hash.digest(bytes), len)
+    }
+
+    /// Derive an ID of a given length from `input` using a secret `key`
+    ///
+    /// The same key and input always give the same ID, which makes this suitable for idempotency
+    /// keys: a client can re-derive the ID for a retried request, while anyone without the key
+    /// cannot predict which IDs will be issued.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, content::ContentHash};
+    ///
+    /// let hash = ContentHash::ALL[0];
+    /// let key = b"server-side secret";
+    /// let a = Id::derive_with(hash, key, b"POST /orders client=42 nonce=7", 16);
+    /// let b = Id::derive_with(hash, key, b"POST /orders client=42 nonce=7", 16);
+    /// assert_eq!(a, b);
+    /// assert_ne!(
+    ///     a,
+    ///     Id::derive_with(hash, b"another secret", b"POST /orders client=42 nonce=7", 16)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn derive_with(hash: ContentHash, key: &[u8], input: &[u8], len: usize) -> Self {
+        from_digest(hash.keyed_digest(key, input), len)
+    }
+}
+
+/// Map a digest to an ID of `len` characters
+///
+/// Bytes of the `ChaCha20` keystream keyed by the digest are mapped to [`GEN_ALPHABET`] by
+/// rejection sampling, skipping characters that would form an ambiguous sequence, and ending the
+/// body with a character that can't start one. Derived IDs are persisted, so this must never
+/// change, nor depend on [`IdGenerator`](crate::generator::IdGenerator).
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "Internal invariant - bodies of generation alphabet characters have a check character."
+)]
+fn from_digest(digest: [u8; 32], len: usize) -> Id {
+    let body_len = len.saturating_sub(1);
+    let mut stream = ChaCha20Rng::from_seed(digest);
+    let mut body = String::with_capacity(len);
+    let mut block = [0_u8; 64];
+    while body.len() < body_len {
+        stream.fill_bytes(&mut block);
+        for byte in block.map(usize::from) {
+            if body.len() == body_len {
+                break;
+            }
+            let Some(&c) = GEN_ALPHABET
+                .get(byte % GEN_ALPHABET.len())
+                .filter(|_| byte < SAMPLE_LIMIT)
+            else {
+                continue;
+            };
+            let is_last = body.len().saturating_add(1) == body_len;
+            let ambiguous = body
+                .chars()
+                .next_back()
+                .is_some_and(|last| alphabet::ambiguous_replacement(last, c).is_some());
+            if ambiguous || (is_last && alphabet::starts_ambiguous_sequence(c)) {
+                continue;
+            }
+            body.push(c);
+        }
+    }
+
+    let check_char = alphabet::calculate_check_char(&body)
+        .expect("generation alphabet bodies have a check character");
+    body.push(check_char);
+    Id(body.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn identical_content_gives_identical_ids() {
+        for &hash in ContentHash::ALL {
+            let a = Id::from_content_with(hash, b"some upload", 25);
+            let b = Id::from_content_with(hash, b"some upload", 25);
+            let c = Id::from_content_with(hash, b"another upload", 25);
+            assert_eq!(a, b);
+            assert_ne!(a, c);
+            assert_eq!(a.len(), 25);
+            assert_eq!(Id::from_str(&a), Ok(a));
+        }
+    }
+
+    #[test]
+    fn derived_ids_depend_on_key() {
+        for &hash in ContentHash::ALL {
+            let a = Id::derive_with(hash, b"key one", b"request", 25);
+            assert_eq!(a, Id::derive_with(hash, b"key one", b"request", 25));
+            assert_ne!(a, Id::derive_with(hash, b"key two", b"request", 25));
+            assert_ne!(a, Id::from_content_with(hash, b"request", 25));
+            assert_eq!(Id::from_str(&a), Ok(a));
+        }
+    }
+
+    #[test]
+    fn derived_ids_are_always_valid() {
+        for &hash in ContentHash::ALL {
+            for i in 0..2_000_u32 {
+                let len = 4 + (i as usize % 40);
+                let id = Id::from_content_with(hash, &i.to_le_bytes(), len);
+                assert_eq!(id.len(), len);
+                assert_eq!(Id::from_str(&id), Ok(id));
+            }
+        }
+    }
+
+    #[test]
+    fn golden_vectors() {
+        // Callers persist derived IDs, so these must never change, whatever happens to the
+        // generator.
+        #[cfg(feature = "blake3")]
+        {
+            assert_eq!(
+                Id::from_content_with(ContentHash::Blake3, b"hello world", 25).as_str(),
+                "ci3nxswfweaocoenoie4vpxde"
+            );
+            assert_eq!(
+                Id::derive_with(ContentHash::Blake3, b"key", b"hello world", 25).as_str(),
+                "3yimoxyreyyvyfprpsceimapv"
+            );
+        }
+        #[cfg(feature = "sha2")]
+        {
+            assert_eq!(
+                Id::from_content_with(ContentHash::Sha256, b"hello world", 25).as_str(),
+                "mnv3ywcjwhepki3caadxnjhmp"
+            );
+            assert_eq!(
+                Id::derive_with(ContentHash::Sha256, b"key", b"hello world", 25).as_str(),
+                "jodnjybjhnbayrvtwhjvwf4ce"
+            );
+        }
+    }
+}
@@ -0,0 +1,538 @@
+// src/coupon.rs
+//! Coupon codes carrying a campaign prefix and an optional expiry, e.g. `summer24-bkxa-wcfyt3`
+//!
+//! The expiry is embedded as a day count, so whether a code has expired can be checked without a
+//! lookup. The check character covers the prefix and expiry as well as the random part, so typos
+//! in any of them are caught.
+//!
+//! The check character is not a signature: anyone can recompute it after moving a code to another
+//! campaign or extending its expiry. The embedded expiry is advisory, for showing to users and
+//! rejecting stale codes early, so servers must still look up each redeemed code and enforce its
+//! campaign and expiry themselves.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rand_core::RngCore;
+
+use crate::{
+    alphabet,
+    encoding::BYTES_ALPHABET,
+    error::IdError,
+    generator::MAX_COLLISION_ATTEMPTS,
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+    tagged::{TAG_SEPARATOR, TagError, generate_sealed, parse_sealed, validate_tag},
+};
+
+/// Number of characters used to embed an expiry
+pub const EXPIRY_LENGTH: usize = 4;
+
+/// Seconds in a day, the resolution of embedded expiries
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// A campaign to generate coupon codes for
+///
+/// # Example
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use human_friendly_ids::coupon::{Coupon, CouponCampaign};
+///
+/// let expiry = SystemTime::now() + Duration::from_secs(30 * 86_400);
+/// let campaign = CouponCampaign::new("SUMMER24", 8)
+///     .unwrap()
+///     .with_expiry(expiry)
+///     .unwrap();
+///
+/// let coupons = campaign.generate_batch(100).unwrap();
+/// assert_eq!(coupons.len(), 100);
+///
+/// let redeemed: Coupon = coupons[0].to_string().to_uppercase().parse().unwrap();
+/// assert_eq!(redeemed.campaign(), "summer24");
+/// assert!(!redeemed.is_expired());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CouponCampaign {
+    /// Lowercase campaign prefix
+    prefix: String,
+    /// Expiry as days since the Unix epoch
+    expiry_days: Option<u32>,
+    /// Length of the random part, including the check character
+    len: usize,
+}
+
+impl CouponCampaign {
+    /// Create a campaign whose codes start with `prefix` and end in `len` random characters
+    ///
+    /// ## Errors
+    ///
+    /// - [`TagError::EmptyTag`] if `prefix` is empty
+    /// - [`TagError::InvalidTag`] if `prefix` is longer than
+    ///   [`MAX_TAG_LENGTH`](crate::tagged::MAX_TAG_LENGTH) or contains anything but ASCII letters
+    ///   and digits
+    /// - [`TagError::Id`] with [`IdError::TooShort`] if `len` is below [`MIN_LENGTH`]
+    pub fn new(prefix: &str, len: usize) -> Result<Self, TagError> {
+        let prefix = validate_tag(prefix)?;
+        if len < MIN_LENGTH {
+            return Err(IdError::TooShort.into());
+        }
+        Ok(Self {
+            prefix,
+            expiry_days: None,
+            len,
+        })
+    }
+
+    /// Embed an expiry in every code, rounded down to the start of its UTC day
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooLong`] if the expiry is too far in the future to embed, over 500 years
+    pub fn with_expiry(mut self, expiry: SystemTime) -> Result<Self, IdError> {
+        let days = expiry
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .checked_div(SECONDS_PER_DAY)
+            .unwrap_or_default();
+        let days = u32::try_from(days)
+            .ok()
+            .filter(|days| encode_days(*days).is_some())
+            .ok_or(IdError::TooLong)?;
+        self.expiry_days = Some(days);
+        Ok(self)
+    }
+
+    /// Generate a coupon code
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn generate(&self) -> Coupon {
+        let mut rng = crate::rng::default_rng();
+        self.generate_with_rng(&mut rng)
+    }
+
+    /// Generate a coupon code using the given RNG
+    #[must_use]
+    pub fn generate_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> Coupon {
+        let expiry = self.expiry_days.and_then(encode_days);
+        let code = generate_sealed(&covered(&self.prefix, expiry.as_deref()), self.len, rng);
+        Coupon {
+            prefix: self.prefix.clone(),
+            expiry,
+            code,
+        }
+    }
+
+    /// Generate `count` distinct coupon codes
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`CouponCampaign::generate_batch_with_rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    pub fn generate_batch(&self, count: usize) -> Result<Vec<Coupon>, IdError> {
+        let mut rng = crate::rng::default_rng();
+        self.generate_batch_with_rng(&mut rng, count)
+    }
+
+    /// Generate `count` distinct coupon codes using the given RNG
+    ///
+    /// Gives up once [`MAX_COLLISION_ATTEMPTS`] codes in a row have already been generated, as
+    /// the campaign's codes are then too short for `count` of them.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if no unused code was found in [`MAX_COLLISION_ATTEMPTS`] attempts
+    pub fn generate_batch_with_rng<R: RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        count: usize,
+    ) -> Result<Vec<Coupon>, IdError> {
+        let mut seen = HashSet::with_capacity(count);
+        let mut coupons = Vec::with_capacity(count);
+        let mut collisions = 0_usize;
+        while coupons.len() < count {
+            let coupon = self.generate_with_rng(rng);
+            if seen.insert(coupon.code.clone()) {
+                coupons.push(coupon);
+                collisions = 0;
+            } else {
+                collisions = collisions.saturating_add(1);
+                if collisions >= MAX_COLLISION_ATTEMPTS {
+                    return Err(IdError::TooShort);
+                }
+            }
+        }
+        Ok(coupons)
+    }
+}
+
+/// A coupon code, see [`CouponCampaign`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coupon {
+    /// Lowercase campaign prefix
+    prefix: String,
+    /// Embedded expiry, in [`BYTES_ALPHABET`]
+    expiry: Option<String>,
+    /// Normalized random part followed by the check character
+    code: String,
+}
+
+impl Coupon {
+    /// The campaign prefix, in lowercase
+    #[must_use]
+    pub fn campaign(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The random part, including the check character
+    #[must_use]
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Start of the UTC day the coupon expires on, if it has an expiry
+    #[must_use]
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        let days = decode_days(self.expiry.as_deref()?)?;
+        UNIX_EPOCH.checked_add(Duration::from_secs(
+            u64::from(days).saturating_mul(SECONDS_PER_DAY),
+        ))
+    }
+
+    /// Whether the coupon had expired at `now`
+    #[must_use]
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        self.expires_at().is_some_and(|expiry| now >= expiry)
+    }
+
+    /// Whether the coupon has expired
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemTime::now())
+    }
+}
+
+/// Write coupons as CSV with a header row: the code, its campaign and its expiry date, if any
+///
+/// Expiries are written as UTC dates, e.g. `2024-08-31`.
+///
+/// ## Errors
+///
+/// Any error from `writer`.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::coupon::{CouponCampaign, write_csv};
+///
+/// let coupons = CouponCampaign::new("promo", 8)
+///     .unwrap()
+///     .generate_batch(3)
+///     .unwrap();
+/// let mut csv = Vec::new();
+/// write_csv(&mut csv, &coupons).unwrap();
+///
+/// let csv = String::from_utf8(csv).unwrap();
+/// assert_eq!(csv.lines().next(), Some("code,campaign,expires"));
+/// assert_eq!(csv.lines().count(), 4);
+/// ```
+pub fn write_csv<W: Write>(mut writer: W, coupons: &[Coupon]) -> io::Result<()> {
+    writeln!(writer, "code,campaign,expires")?;
+    for coupon in coupons {
+        // Codes are letters, digits and separators, so nothing needs quoting.
+        write!(writer, "{},{},", coupon, coupon.prefix)?;
+        if let Some(days) = coupon.expiry.as_deref().and_then(decode_days) {
+            let (year, month, day) = civil_from_days(days);
+            write!(writer, "{year:04}-{month:02}-{day:02}")?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// What the check character covers besides the random part
+fn covered(prefix: &str, expiry: Option<&str>) -> String {
+    match expiry {
+        Some(expiry) => format!("{prefix}{TAG_SEPARATOR}{expiry}"),
+        None => prefix.to_owned(),
+    }
+}
+
+/// Write a day count as [`EXPIRY_LENGTH`] characters, or `None` if it doesn't fit
+fn encode_days(days: u32) -> Option<String> {
+    let base = u32::try_from(BYTES_ALPHABET.len()).ok()?;
+    let mut rest = days;
+    let mut chars = [' '; EXPIRY_LENGTH];
+    for slot in chars.iter_mut().rev() {
+        *slot = *BYTES_ALPHABET.get(usize::try_from(rest.checked_rem(base)?).ok()?)?;
+        rest = rest.checked_div(base)?;
+    }
+    (rest == 0).then(|| chars.iter().collect())
+}
+
+/// Read a day count written by [`encode_days`]
+fn decode_days(s: &str) -> Option<u32> {
+    let base = u32::try_from(BYTES_ALPHABET.len()).ok()?;
+    s.chars().try_fold(0_u32, |acc, c| {
+        let digit = BYTES_ALPHABET.iter().position(|d| *d == c)?;
+        acc.checked_mul(base)?
+            .checked_add(u32::try_from(digit).ok()?)
+    })
+}
+
+/// Convert days since the Unix epoch to a proleptic Gregorian `(year, month, day)`
+///
+/// Howard Hinnant's `civil_from_days` algorithm, restricted to dates after the epoch.
+fn civil_from_days(days: u32) -> (u32, u32, u32) {
+    let z = days.saturating_add(719_468);
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u32::from(month <= 2);
+    (year, month, day)
+}
+
+impl FromStr for Coupon {
+    type Err = TagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_PARSE_LENGTH {
+            return Err(IdError::TooLong.into());
+        }
+        let mut parts = s.split(TAG_SEPARATOR);
+        let prefix = validate_tag(parts.next().unwrap_or_default())?;
+        let (expiry, rest) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(rest), None, _) => (None, rest),
+            (Some(expiry), Some(rest), None) => (Some(alphabet::normalize_string(expiry)), rest),
+            (None, ..) => return Err(TagError::MissingSeparator),
+            (Some(_), Some(_), Some(_)) => return Err(TagError::TooManyParts),
+        };
+        if expiry
+            .as_deref()
+            .is_some_and(|expiry| expiry.len() != EXPIRY_LENGTH || decode_days(expiry).is_none())
+        {
+            return Err(IdError::InvalidCharacter.into());
+        }
+
+        let code = parse_sealed(&covered(&prefix, expiry.as_deref()), rest)?;
+        Ok(Self {
+            prefix,
+            expiry,
+            code,
+        })
+    }
+}
+
+impl TryFrom<String> for Coupon {
+    type Error = TagError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl fmt::Display for Coupon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix, TAG_SEPARATOR)?;
+        if let Some(expiry) = &self.expiry {
+            write!(f, "{}{}", expiry, TAG_SEPARATOR)?;
+        }
+        write!(f, "{}", self.code)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize coupons in their joined string form
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::Coupon;
+
+    impl Serialize for Coupon {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Coupon {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-08-31T12:00:00Z
+    const AUGUST_31: u64 = 1_725_105_600;
+
+    #[test]
+    fn roundtrip() {
+        let expiry = UNIX_EPOCH + Duration::from_secs(AUGUST_31);
+        let campaign = CouponCampaign::new("Summer24", 8)
+            .expect("valid prefix")
+            .with_expiry(expiry)
+            .expect("near expiry");
+        for coupon in campaign.generate_batch(1_000).expect("long enough") {
+            assert_eq!(coupon.campaign(), "summer24");
+            assert_eq!(coupon.code().len(), 8);
+            assert_eq!(
+                coupon.expires_at(),
+                Some(UNIX_EPOCH + Duration::from_secs(AUGUST_31 - 12 * 3_600))
+            );
+            assert_eq!(
+                Coupon::from_str(&coupon.to_string().to_uppercase()),
+                Ok(coupon)
+            );
+        }
+
+        let coupon = CouponCampaign::new("promo", 6).expect("valid").generate();
+        assert_eq!(coupon.to_string().matches(TAG_SEPARATOR).count(), 1);
+        assert_eq!(coupon.expires_at(), None);
+        assert!(!coupon.is_expired());
+        assert_eq!(Coupon::from_str(&coupon.to_string()), Ok(coupon));
+    }
+
+    #[test]
+    fn expiry_is_checked() {
+        let expiry = UNIX_EPOCH + Duration::from_secs(AUGUST_31);
+        let coupon = CouponCampaign::new("promo", 8)
+            .expect("valid")
+            .with_expiry(expiry)
+            .expect("near expiry")
+            .generate();
+        assert!(coupon.is_expired());
+        assert!(!coupon.is_expired_at(expiry - Duration::from_secs(SECONDS_PER_DAY)));
+    }
+
+    #[test]
+    fn prefix_and_expiry_are_covered_by_check_char() {
+        let expiry = UNIX_EPOCH + Duration::from_secs(AUGUST_31);
+        let campaign = CouponCampaign::new("promo", 8)
+            .expect("valid")
+            .with_expiry(expiry)
+            .expect("near expiry");
+        let later = encode_days(decode_days("bkxa").expect("valid") + 1).expect("fits");
+        for _ in 0..1_000 {
+            let coupon = campaign.generate();
+            let extended = format!("promo-{later}-{}", coupon.code());
+            assert_eq!(
+                Coupon::from_str(&extended),
+                Err(TagError::Id(IdError::InvalidCheckBit))
+            );
+            let moved = coupon.to_string().replacen("promo", "promos", 1);
+            assert_eq!(
+                Coupon::from_str(&moved),
+                Err(TagError::Id(IdError::InvalidCheckBit))
+            );
+        }
+    }
+
+    #[test]
+    fn batches_larger_than_the_keyspace_are_rejected() {
+        // Four characters leave three random ones, under 30,000 possible codes.
+        let campaign = CouponCampaign::new("promo", 4).expect("valid");
+        let mut rng = crate::rng::default_rng();
+        assert_eq!(
+            campaign.generate_batch_with_rng(&mut rng, 100_000),
+            Err(IdError::TooShort)
+        );
+        assert_eq!(
+            campaign
+                .generate_batch_with_rng(&mut rng, 100)
+                .map(|coupons| coupons.len()),
+            Ok(100)
+        );
+    }
+
+    #[test]
+    fn days_encoding_roundtrips() {
+        for days in [0, 1, 20, 21, 19_966, 194_480] {
+            let encoded = encode_days(days).expect("fits");
+            assert_eq!(encoded.len(), EXPIRY_LENGTH);
+            assert_eq!(decode_days(&encoded), Some(days));
+        }
+        assert_eq!(encode_days(194_481), None);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_966), (2024, 8, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+
+    #[test]
+    fn csv_export() {
+        let expiry = UNIX_EPOCH + Duration::from_secs(AUGUST_31);
+        let coupons = vec![
+            CouponCampaign::new("promo", 8)
+                .expect("valid")
+                .with_expiry(expiry)
+                .expect("near expiry")
+                .generate(),
+            CouponCampaign::new("promo", 8).expect("valid").generate(),
+        ];
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &coupons).expect("writes to a Vec");
+        let csv = String::from_utf8(csv).expect("UTF-8");
+        assert_eq!(
+            csv,
+            format!(
+                "code,campaign,expires\n{},promo,2024-08-31\n{},promo,\n",
+                coupons[0], coupons[1]
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_coupons_are_rejected() {
+        assert_eq!(
+            CouponCampaign::new("promo", 3),
+            Err(TagError::Id(IdError::TooShort))
+        );
+        assert_eq!(
+            CouponCampaign::new("promo", 8)
+                .expect("valid")
+                .with_expiry(UNIX_EPOCH + Duration::from_secs(u64::MAX / 2)),
+            Err(IdError::TooLong)
+        );
+        assert_eq!(Coupon::from_str("wcfytd3"), Err(TagError::MissingSeparator));
+        assert_eq!(
+            Coupon::from_str("promo-bkxr-wcfytd3"),
+            Err(TagError::Id(IdError::InvalidCharacter))
+        );
+        assert_eq!(Coupon::from_str("promo-a-b-c"), Err(TagError::TooManyParts));
+        assert_eq!(
+            Coupon::from_str(&format!("promo-{}", "a".repeat(MAX_PARSE_LENGTH))),
+            Err(TagError::Id(IdError::TooLong))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let coupon = CouponCampaign::new("promo", 8).expect("valid").generate();
+        let serialized = serde_json::to_string(&coupon).expect("serializes");
+        assert_eq!(serialized, format!("\"{coupon}\""));
+        let deserialized: Coupon = serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, coupon);
+    }
+}
@@ -0,0 +1,372 @@
+// src/cursor.rs
+//! Compact pagination cursors, e.g. `fmbdx3kaocyw`
+//!
+//! A [`Cursor`] packs an offset, and optionally a sort key and a timestamp, into an ID through
+//! [`Id::from_bytes`]. The state is encoded, not encrypted: [`Id::to_bytes`] reverses it, so
+//! anyone holding a cursor can read its sort key. A mistyped or truncated cursor fails its check
+//! character instead of silently paging from the wrong place.
+//!
+//! Cursors are not signed: a client able to encode its own can page anywhere it could with an
+//! offset parameter, so access checks still belong in the query, and sort keys must not contain
+//! anything the client may not see.
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Id, error::IdError};
+
+/// Longest sort key in bytes, so every encoded cursor parses back within
+/// [`MAX_PARSE_LENGTH`](crate::id::MAX_PARSE_LENGTH)
+pub const MAX_SORT_KEY_LENGTH: usize = 2_048;
+
+/// Format version, in the high nibble of the first byte, next to the flags
+const FORMAT_VERSION: u8 = 0x10;
+
+/// Flag set when a timestamp follows the offset
+const HAS_TIMESTAMP: u8 = 0x01;
+
+/// Flag set when a sort key ends the cursor
+const HAS_SORT_KEY: u8 = 0x02;
+
+/// Position in a paginated listing
+///
+/// # Example
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use human_friendly_ids::cursor::Cursor;
+///
+/// let issued = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let cursor = Cursor::new(40)
+///     .with_sort_key("2024-06-01")
+///     .unwrap()
+///     .with_timestamp(issued);
+///
+/// let token = cursor.to_string();
+/// let decoded: Cursor = token.to_uppercase().parse().unwrap();
+/// assert_eq!(decoded, cursor);
+/// assert_eq!(decoded.offset(), 40);
+/// assert_eq!(decoded.sort_key(), Some("2024-06-01"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Cursor {
+    /// Number of items already returned
+    offset: u64,
+    /// Key of the last item returned, for keyset pagination
+    sort_key: Option<String>,
+    /// Seconds since the Unix epoch, e.g. when the listing was first requested
+    timestamp: Option<u64>,
+}
+
+impl Cursor {
+    /// Create a cursor at `offset`
+    #[must_use]
+    pub const fn new(offset: u64) -> Self {
+        Self {
+            offset,
+            sort_key: None,
+            timestamp: None,
+        }
+    }
+
+    /// Attach the sort key of the last item returned
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooLong`] if `sort_key` is longer than [`MAX_SORT_KEY_LENGTH`] bytes
+    pub fn with_sort_key(mut self, sort_key: impl Into<String>) -> Result<Self, IdError> {
+        let sort_key = sort_key.into();
+        if sort_key.len() > MAX_SORT_KEY_LENGTH {
+            return Err(IdError::TooLong);
+        }
+        self.sort_key = Some(sort_key);
+        Ok(self)
+    }
+
+    /// Attach a timestamp, truncated to whole seconds
+    ///
+    /// Times before the Unix epoch are stored as the epoch.
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        let secs = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.timestamp = Some(secs);
+        self
+    }
+
+    /// Number of items already returned
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Sort key of the last item returned, if set
+    #[must_use]
+    pub fn sort_key(&self) -> Option<&str> {
+        self.sort_key.as_deref()
+    }
+
+    /// Timestamp, if set, at second resolution
+    #[must_use]
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+            .and_then(|secs| UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+    }
+
+    /// Encode the cursor as an ID
+    ///
+    /// The ID always parses back with [`Id::from_str`], as sort keys are limited to
+    /// [`MAX_SORT_KEY_LENGTH`].
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - the flags byte alone gives the ID its minimum length."
+    )]
+    #[must_use]
+    pub fn encode(&self) -> Id {
+        let mut flags = FORMAT_VERSION;
+        if self.timestamp.is_some() {
+            flags |= HAS_TIMESTAMP;
+        }
+        if self.sort_key.is_some() {
+            flags |= HAS_SORT_KEY;
+        }
+
+        let mut bytes = vec![flags];
+        write_varint(&mut bytes, self.offset);
+        if let Some(secs) = self.timestamp {
+            write_varint(&mut bytes, secs);
+        }
+        if let Some(sort_key) = &self.sort_key {
+            bytes.extend_from_slice(sort_key.as_bytes());
+        }
+        Id::from_bytes(&bytes).expect("cursors are at least two bytes")
+    }
+
+    /// Decode a cursor from its encoded form
+    ///
+    /// ## Errors
+    ///
+    /// - See [`Id::from_str`] for an invalid ID, e.g. a mistyped cursor
+    /// - [`IdError::InvalidCharacter`] if the ID was not created from bytes
+    /// - [`IdError::InvalidSequence`] if the bytes are not a cursor
+    pub fn decode(s: &str) -> Result<Self, IdError> {
+        let bytes = Id::from_str(s)?.to_bytes()?;
+        let (&flags, mut rest) = bytes.split_first().ok_or(IdError::InvalidSequence)?;
+        if flags & !(HAS_TIMESTAMP | HAS_SORT_KEY) != FORMAT_VERSION {
+            return Err(IdError::InvalidSequence);
+        }
+
+        let offset = read_varint(&mut rest)?;
+        let timestamp = if flags & HAS_TIMESTAMP == 0 {
+            None
+        } else {
+            Some(read_varint(&mut rest)?)
+        };
+        let sort_key = if flags & HAS_SORT_KEY == 0 {
+            if !rest.is_empty() {
+                return Err(IdError::InvalidSequence);
+            }
+            None
+        } else {
+            let key = std::str::from_utf8(rest).map_err(|_| IdError::InvalidSequence)?;
+            Some(key.to_owned())
+        };
+
+        Ok(Self {
+            offset,
+            sort_key,
+            timestamp,
+        })
+    }
+}
+
+/// Append `value` as a LEB128 varint
+pub(crate) fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let low = u8::try_from(value & 0x7f).unwrap_or_default();
+        value >>= 7;
+        if value == 0 {
+            bytes.push(low);
+            return;
+        }
+        bytes.push(low | 0x80);
+    }
+}
+
+/// Read a LEB128 varint from the start of `bytes`, advancing past it
+///
+/// ## Errors
+///
+/// - [`IdError::InvalidSequence`] if the varint is truncated or overflows a `u64`
+pub(crate) fn read_varint(bytes: &mut &[u8]) -> Result<u64, IdError> {
+    let mut value = 0_u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(IdError::InvalidSequence)?;
+        *bytes = rest;
+        let low = u64::from(byte & 0x7f);
+        if shift == 63 && low > 1 {
+            return Err(IdError::InvalidSequence);
+        }
+        value |= low << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(IdError::InvalidSequence)
+}
+
+impl FromStr for Cursor {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+impl TryFrom<String> for Cursor {
+    type Error = IdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::decode(&value)
+    }
+}
+
+impl From<&Cursor> for Id {
+    fn from(cursor: &Cursor) -> Self {
+        cursor.encode()
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.encode(), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::Cursor;
+
+    impl Serialize for Cursor {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Cursor {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = String::deserialize(deserializer)?;
+            Cursor::decode(&s).map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let cursors = [
+            Cursor::new(0),
+            Cursor::new(u64::MAX),
+            Cursor::new(20).with_sort_key("").expect("short key"),
+            Cursor::new(1_000)
+                .with_sort_key("ünïcode key")
+                .expect("short key"),
+            Cursor::new(7).with_timestamp(UNIX_EPOCH),
+            Cursor::new(128)
+                .with_sort_key("2024-06-01T00:00:00Z")
+                .expect("short key")
+                .with_timestamp(UNIX_EPOCH + Duration::from_secs(u64::MAX >> 1)),
+        ];
+        for cursor in cursors {
+            let encoded = cursor.to_string();
+            assert_eq!(
+                Id::from_str(&encoded).map(|id| id.to_string()),
+                Ok(encoded.clone())
+            );
+            assert_eq!(Cursor::decode(&encoded), Ok(cursor));
+        }
+    }
+
+    #[test]
+    fn longest_cursors_parse_back() {
+        let cursor = Cursor::new(u64::MAX)
+            .with_sort_key("\u{10ffff}".repeat(MAX_SORT_KEY_LENGTH / 4))
+            .expect("at the limit")
+            .with_timestamp(UNIX_EPOCH + Duration::from_secs(u64::MAX >> 1));
+        assert_eq!(Cursor::decode(&cursor.to_string()), Ok(cursor));
+
+        assert_eq!(
+            Cursor::new(0).with_sort_key("a".repeat(MAX_SORT_KEY_LENGTH + 1)),
+            Err(IdError::TooLong)
+        );
+    }
+
+    #[test]
+    fn timestamps_are_truncated_to_seconds() {
+        let cursor = Cursor::new(1).with_timestamp(UNIX_EPOCH + Duration::from_millis(1_999));
+        assert_eq!(
+            cursor.timestamp(),
+            Some(UNIX_EPOCH + Duration::from_secs(1))
+        );
+        let before_epoch = Cursor::new(1).with_timestamp(UNIX_EPOCH - Duration::from_secs(5));
+        assert_eq!(before_epoch.timestamp(), Some(UNIX_EPOCH));
+        assert_eq!(Cursor::new(1).timestamp(), None);
+    }
+
+    #[test]
+    fn typos_are_caught() {
+        let encoded = Cursor::new(42)
+            .with_sort_key("abc")
+            .expect("short key")
+            .to_string();
+        let (rest, last) = encoded.split_at(encoded.len() - 1);
+        let wrong = if last == "a" { "b" } else { "a" };
+        assert_eq!(
+            Cursor::decode(&format!("{rest}{wrong}")),
+            Err(IdError::InvalidCheckBit)
+        );
+        assert_eq!(Cursor::decode(&encoded[1..]).ok(), None);
+    }
+
+    #[test]
+    fn foreign_ids_are_rejected() {
+        // Valid IDs from bytes, but not cursors.
+        for bytes in [
+            &[0x20, 0][..],
+            &[0x10, 0, 1],
+            &[0x10, 0x80],
+            &[0x13, 1, 0xff],
+        ] {
+            let id = Id::from_bytes(bytes).expect("long enough");
+            assert_eq!(Cursor::decode(&id), Err(IdError::InvalidSequence));
+        }
+        let overflow = [&[0x10][..], &[0xff; 9], &[0x02]].concat();
+        let id = Id::from_bytes(&overflow).expect("long enough");
+        assert_eq!(Cursor::decode(&id), Err(IdError::InvalidSequence));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let cursor = Cursor::new(3).with_sort_key("name").expect("short key");
+        let json = serde_json::to_string(&cursor).expect("serializes");
+        assert_eq!(json, format!("\"{cursor}\""));
+        assert_eq!(serde_json::from_str::<Cursor>(&json).ok(), Some(cursor));
+    }
+}
@@ -0,0 +1,526 @@
+// src/custom.rs
+//! IDs over a custom alphabet, declared with [`alphabet!`](crate::alphabet!)
+//!
+//! The built-in [`crate::Id`] is tuned for its own alphabet, with sequence rules such as `rn` → `m`
+//! that don't generalise. A custom alphabet only normalizes case and its listed confusions, and uses
+//! the sum of character positions as its check character.
+//!
+//! Presets are provided for common needs: [`Keypad`] for entry on a telephone keypad, and
+//! [`LicenseKey`] for printed license keys.
+
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use rand_core::RngCore;
+
+use crate::{
+    error::IdError,
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+};
+
+/// Marker in [`Alphabet::LOOKUP`] for bytes outside the alphabet
+const NOT_IN_ALPHABET: u8 = u8::MAX;
+
+/// A set of characters IDs are made of, usually declared with [`alphabet!`](crate::alphabet!)
+pub trait Alphabet {
+    /// Characters of the alphabet, lowercase ASCII, in check character order
+    const CHARS: &'static str;
+
+    /// Characters that are normalized into the alphabet before validating, e.g. `('0', 'o')`
+    const CONFUSIONS: &'static [(char, char)];
+
+    /// Position of each byte in [`Alphabet::CHARS`], or [`u8::MAX`] if it is not in the alphabet
+    const LOOKUP: [u8; 256] = build_lookup(Self::CHARS);
+
+    /// Whether IDs are generated and displayed in uppercase, input is accepted in either case
+    const UPPERCASE: bool = false;
+}
+
+/// Check, at compile time when used in a const, that an alphabet is unambiguous
+///
+/// # Panics
+///
+/// If the alphabet has fewer than two or more than 254 characters, isn't lowercase ASCII, contains
+/// a duplicate, or a confusion maps from a character in the alphabet or to one outside it.
+#[doc(hidden)]
+#[allow(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "indices are bounds checked by the loops"
+)]
+pub const fn validate_alphabet(chars: &str, confusions: &[(char, char)]) {
+    let bytes = chars.as_bytes();
+    assert!(
+        bytes.len() >= 2,
+        "alphabet must have at least two characters"
+    );
+    assert!(
+        bytes.len() < NOT_IN_ALPHABET as usize,
+        "alphabet is too large for the check character lookup table"
+    );
+
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii(), "alphabet must be ASCII");
+        assert!(
+            !bytes[i].is_ascii_uppercase(),
+            "alphabet must be lowercase, input is lowercased before validating"
+        );
+        let mut j = i + 1;
+        while j < bytes.len() {
+            assert!(
+                bytes[i] != bytes[j],
+                "alphabet contains a duplicate character"
+            );
+            j += 1;
+        }
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < confusions.len() {
+        let (from, to) = confusions[i];
+        assert!(
+            !contains(bytes, from),
+            "confusions must not map from a character in the alphabet"
+        );
+        assert!(
+            !from.is_ascii_uppercase(),
+            "confusions must map from lowercase characters, input is lowercased first"
+        );
+        assert!(
+            contains(bytes, to),
+            "confusions must map to a character in the alphabet"
+        );
+        i += 1;
+    }
+}
+
+/// Whether an ASCII alphabet contains `c`
+#[allow(clippy::indexing_slicing, reason = "bounds checked by the loop")]
+const fn contains(bytes: &[u8], c: char) -> bool {
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] as u32 == c as u32 {
+            return true;
+        }
+        i = i.saturating_add(1);
+    }
+    false
+}
+
+/// Build the lookup table behind [`Alphabet::LOOKUP`]
+#[allow(
+    clippy::indexing_slicing,
+    clippy::cast_possible_truncation,
+    reason = "alphabets are validated to fit the table"
+)]
+const fn build_lookup(chars: &str) -> [u8; 256] {
+    let bytes = chars.as_bytes();
+    let mut lookup = [NOT_IN_ALPHABET; 256];
+    let mut i = 0;
+    while i < bytes.len() && i < NOT_IN_ALPHABET as usize {
+        lookup[bytes[i] as usize] = i as u8;
+        i = i.saturating_add(1);
+    }
+    lookup
+}
+
+/// Declare a custom [`Alphabet`], checked for ambiguities at compile time
+///
+/// The alphabet must be lowercase ASCII without duplicates. Each confusion maps a character that
+/// is not in the alphabet to one that is, and is applied after lowercasing the input. Add
+/// `uppercase: true` to generate and display IDs in uppercase.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{alphabet, custom::CustomId};
+///
+/// alphabet! {
+///     /// Crockford's base 32
+///     pub Crockford {
+///         chars: "0123456789abcdefghjkmnpqrstvwxyz",
+///         confusions: { 'o' => '0', 'i' => '1', 'l' => '1' },
+///     }
+/// }
+///
+/// let id = CustomId::<Crockford>::new(10);
+/// let parsed: CustomId<Crockford> = id.to_uppercase().replace('1', "L").parse().unwrap();
+/// assert_eq!(parsed, id);
+/// ```
+///
+/// Ambiguous alphabets fail to compile:
+/// ```compile_fail
+/// human_friendly_ids::alphabet! {
+///     Broken {
+///         chars: "abcdefa",
+///         confusions: {},
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! alphabet {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident {
+            chars: $chars:literal,
+            confusions: { $($from:literal => $to:literal),* $(,)? } $(,)?
+            $(uppercase: $uppercase:literal $(,)?)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        $vis struct $name;
+
+        const _: () = $crate::custom::validate_alphabet($chars, &[$(($from, $to)),*]);
+
+        impl $crate::custom::Alphabet for $name {
+            const CHARS: &'static str = $chars;
+            const CONFUSIONS: &'static [(char, char)] = &[$(($from, $to)),*];
+            $(const UPPERCASE: bool = $uppercase;)?
+        }
+    };
+}
+
+alphabet! {
+    /// Digits only, for IDs keyed on a telephone keypad, e.g. read back to an IVR system
+    ///
+    /// Letters share keys on a keypad, so none are included. Letters that look like digits are
+    /// accepted when an ID is typed rather than keyed.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::custom::{CustomId, Keypad};
+    ///
+    /// let id = CustomId::<Keypad>::new(10);
+    /// assert!(id.bytes().all(|b| b.is_ascii_digit()));
+    /// ```
+    pub Keypad {
+        chars: "0123456789",
+        confusions: { 'o' => '0', 'i' => '1', 'l' => '1', 'z' => '2', 's' => '5', 'b' => '8' },
+    }
+}
+
+alphabet! {
+    /// Uppercase letters and digits for printed license keys
+    ///
+    /// Both sides of every common confusion, such as `O`/`0`, `I`/`1`/`L` and `S`/`5`, are left out
+    /// rather than normalized, as keys are often read off packaging in poor print. Vowels are left
+    /// out too, so keys never spell words.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::custom::{CustomId, LicenseKey};
+    ///
+    /// let key = CustomId::<LicenseKey>::new(20);
+    /// assert_eq!(key.as_str(), key.to_uppercase());
+    /// assert_eq!(key.to_lowercase().parse::<CustomId<LicenseKey>>(), Ok(key));
+    /// ```
+    pub LicenseKey {
+        chars: "bcdfghjkmpqrtvwxy2346789",
+        confusions: { 'u' => 'v', 'z' => '2' },
+        uppercase: true,
+    }
+}
+
+/// An ID over a custom [`Alphabet`], with a check character
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomId<A> {
+    /// Normalized ID including the check character
+    value: String,
+    /// Alphabet the ID is made of
+    alphabet: PhantomData<A>,
+}
+
+impl<A: Alphabet> CustomId<A> {
+    /// Generate a new ID with a given length using the given RNG
+    ///
+    /// IDs shorter than [`MIN_LENGTH`] never parse back, so shorter lengths are raised to it.
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - every generated character is in the alphabet."
+    )]
+    #[must_use]
+    pub fn new_with_rng<R: RngCore + ?Sized>(len: usize, rng: &mut R) -> Self {
+        let len = len.max(MIN_LENGTH);
+        let chars = A::CHARS.as_bytes();
+        let mut value = String::with_capacity(len);
+        for _ in 0..len.saturating_sub(1) {
+            if let Some(b) = chars.get(random_index(rng, chars.len())) {
+                value.push(char::from(*b));
+            }
+        }
+        let check_char = check_char::<A>(&value).expect("generated from the alphabet");
+        value.push(check_char);
+        if A::UPPERCASE {
+            value.make_ascii_uppercase();
+        }
+
+        Self {
+            value,
+            alphabet: PhantomData,
+        }
+    }
+
+    /// Generate a new ID with a given length, at least [`MIN_LENGTH`]
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        let mut rng = crate::rng::default_rng();
+        Self::new_with_rng(len, &mut rng)
+    }
+
+    /// Get string slice representation
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Pick a uniformly distributed index below `len`, rejecting biased values
+pub(crate) fn random_index<R: RngCore + ?Sized>(rng: &mut R, len: usize) -> usize {
+    let len = u32::try_from(len).unwrap_or(u32::MAX).max(1);
+    let zone = u32::MAX - u32::MAX % len;
+    loop {
+        let value = rng.next_u32();
+        if value < zone {
+            return usize::try_from(value % len).unwrap_or_default();
+        }
+    }
+}
+
+/// Sum of character positions modulo the alphabet size
+pub(crate) fn check_char<A: Alphabet>(body: &str) -> Result<char, IdError> {
+    let mut sum = 0_usize;
+    for b in body.bytes() {
+        let value = A::LOOKUP
+            .get(usize::from(b))
+            .copied()
+            .filter(|v| *v != NOT_IN_ALPHABET)
+            .ok_or(IdError::InvalidCharacter)?;
+        sum = sum.saturating_add(usize::from(value));
+    }
+
+    let chars = A::CHARS.as_bytes();
+    sum.checked_rem(chars.len())
+        .and_then(|idx| chars.get(idx))
+        .map(|b| char::from(*b))
+        .ok_or(IdError::InvalidCheckBit)
+}
+
+/// Lowercase `s` and apply the confusions of the alphabet
+pub(crate) fn normalize<A: Alphabet>(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .map(|c| {
+            A::CONFUSIONS
+                .iter()
+                .find_map(|(from, to)| (*from == c).then_some(*to))
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+impl<A: Alphabet> FromStr for CustomId<A> {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_PARSE_LENGTH {
+            return Err(IdError::TooLong);
+        }
+        let mut normalized = normalize::<A>(s);
+
+        if normalized.len() < MIN_LENGTH {
+            return Err(IdError::TooShort);
+        }
+
+        let (body, check) = normalized
+            .split_at_checked(normalized.len().saturating_sub(1))
+            .ok_or(IdError::InvalidCharacter)?;
+        if check
+            .bytes()
+            .any(|b| A::LOOKUP.get(usize::from(b)) == Some(&NOT_IN_ALPHABET))
+        {
+            return Err(IdError::InvalidCharacter);
+        }
+        if check != check_char::<A>(body)?.to_string() {
+            return Err(IdError::InvalidCheckBit);
+        }
+        if A::UPPERCASE {
+            normalized.make_ascii_uppercase();
+        }
+
+        Ok(Self {
+            value: normalized,
+            alphabet: PhantomData,
+        })
+    }
+}
+
+impl<A: Alphabet> TryFrom<String> for CustomId<A> {
+    type Error = IdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl<A> fmt::Display for CustomId<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl<A> AsRef<str> for CustomId<A> {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl<A> std::ops::Deref for CustomId<A> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl<A> From<CustomId<A>> for String {
+    fn from(id: CustomId<A>) -> Self {
+        id.value
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize custom alphabet IDs as strings, validating them when deserializing
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::{Alphabet, CustomId};
+
+    impl<A> Serialize for CustomId<A> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&self.value)
+        }
+    }
+
+    impl<'de, A: Alphabet> Deserialize<'de> for CustomId<A> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::alphabet! {
+        /// Digits only, with the usual letter look-alikes
+        Digits {
+            chars: "0123456789",
+            confusions: { 'o' => '0', 'i' => '1', 'l' => '1', 's' => '5', 'b' => '8' },
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for _ in 0..1_000 {
+            let id = CustomId::<Digits>::new(12);
+            assert_eq!(id.len(), 12);
+            assert!(id.bytes().all(|b| b.is_ascii_digit()));
+            assert_eq!(CustomId::<Digits>::from_str(&id), Ok(id));
+        }
+    }
+
+    #[test]
+    fn confusions_are_normalized() {
+        // 1 + 2 + 3 = 6
+        let id = CustomId::<Digits>::from_str("o1236").expect("valid");
+        assert_eq!(CustomId::<Digits>::from_str("OI236"), Ok(id.clone()));
+        assert_eq!(CustomId::<Digits>::from_str("ol236"), Ok(id));
+    }
+
+    #[test]
+    fn short_lengths_are_raised_to_the_minimum() {
+        for len in 0..MIN_LENGTH {
+            let id = CustomId::<Digits>::new(len);
+            assert_eq!(id.len(), MIN_LENGTH);
+            assert_eq!(CustomId::<Digits>::from_str(&id), Ok(id));
+        }
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        assert_eq!(CustomId::<Digits>::from_str("012"), Err(IdError::TooShort));
+        assert_eq!(
+            CustomId::<Digits>::from_str(&"0".repeat(MAX_PARSE_LENGTH + 1)),
+            Err(IdError::TooLong)
+        );
+        assert_eq!(
+            CustomId::<Digits>::from_str("01237"),
+            Err(IdError::InvalidCheckBit)
+        );
+        assert_eq!(
+            CustomId::<Digits>::from_str("0a236"),
+            Err(IdError::InvalidCharacter)
+        );
+        assert_eq!(
+            CustomId::<Digits>::from_str("0123x"),
+            Err(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn keypad_ids_are_digits() {
+        for _ in 0..1_000 {
+            let id = CustomId::<Keypad>::new(10);
+            assert!(id.bytes().all(|b| b.is_ascii_digit()));
+            let typed = id.replace('0', "O").replace('1', "l");
+            assert_eq!(CustomId::<Keypad>::from_str(&typed), Ok(id));
+        }
+    }
+
+    #[test]
+    fn license_keys_are_uppercase() {
+        for _ in 0..1_000 {
+            let key = CustomId::<LicenseKey>::new(20);
+            assert!(
+                key.bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            );
+            assert!(!key.contains(['O', '0', 'I', '1', 'L', 'S', '5']));
+            let typed = key.to_lowercase().replace('v', "u");
+            assert_eq!(CustomId::<LicenseKey>::from_str(&typed), Ok(key));
+        }
+    }
+
+    #[test]
+    fn lookup_matches_chars() {
+        assert_eq!(Digits::LOOKUP[usize::from(b'0')], 0);
+        assert_eq!(Digits::LOOKUP[usize::from(b'9')], 9);
+        assert_eq!(Digits::LOOKUP[usize::from(b'a')], NOT_IN_ALPHABET);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let id = CustomId::<Digits>::new(8);
+        let serialized = serde_json::to_string(&id).expect("serializes");
+        assert_eq!(serialized, format!("\"{id}\""));
+        let deserialized: CustomId<Digits> =
+            serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, id);
+    }
+}
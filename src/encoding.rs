@@ -0,0 +1,385 @@
+// src/encoding.rs
+//! Conversions between IDs and bytes, base58 and base62, for interop with other identifier schemes
+//!
+//! Bytes are written as a big-endian number in [`BYTES_ALPHABET`] followed by the usual check
+//! character. The alphabet leaves out `r` and `v`, so converted IDs never contain an ambiguous
+//! sequence and any input of three or more bytes converts. As with base58, each leading zero byte
+//! becomes a leading zero digit, so the conversions round-trip exactly.
+//!
+//! Only IDs created by these conversions can be converted back: randomly generated IDs may contain
+//! `r` or `v` and are rejected with [`IdError::InvalidCharacter`].
+//!
+//! Counters are the exception: [`Id::from_counter`] writes them zero-padded to a fixed width with
+//! [`SORTABLE_ALPHABET`], so that IDs sort in the same order as the counters they encode.
+
+use crate::{
+    Id,
+    alphabet::{self, GEN_ALPHABET},
+    error::IdError,
+    id::MIN_LENGTH,
+};
+
+/// Body characters of IDs converted from bytes, in digit order
+pub const BYTES_ALPHABET: [char; 21] = {
+    let mut chars = ['a'; 21];
+    let mut i = 0;
+    let mut j = 0;
+    while i < GEN_ALPHABET.len() {
+        if GEN_ALPHABET[i] != 'r' && GEN_ALPHABET[i] != 'v' {
+            chars[j] = GEN_ALPHABET[i];
+            j += 1;
+        }
+        i += 1;
+    }
+    chars
+};
+
+/// The characters of [`BYTES_ALPHABET`] in ASCII order, used as digits by [`Id::from_counter`]
+pub const SORTABLE_ALPHABET: [char; 21] = [
+    '3', '4', 'a', 'b', 'c', 'd', 'e', 'f', 'h', 'i', 'j', 'k', 'm', 'n', 'o', 'p', 's', 't', 'w',
+    'x', 'y',
+];
+
+/// Width at which [`Id::from_counter`] can write any `u64`
+pub const FULL_COUNTER_WIDTH: usize = 15;
+
+/// The Bitcoin base58 alphabet, in digit order
+pub const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The base62 alphabet, in digit order: digits, then uppercase, then lowercase letters
+pub const BASE62_ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+impl Id {
+    /// Convert bytes to an ID, see the [module documentation](crate::encoding)
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if the resulting ID would be below the minimum length, e.g. for a
+    ///   single non-zero byte
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_bytes(&[0, 1, 2, 3]).unwrap();
+    /// assert_eq!(id.to_bytes(), Ok(vec![0, 1, 2, 3]));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IdError> {
+        let digits = convert(
+            bytes.iter().map(|b| usize::from(*b)),
+            256,
+            BYTES_ALPHABET.len(),
+        );
+        let mut id = digits
+            .into_iter()
+            .filter_map(|d| BYTES_ALPHABET.get(d))
+            .collect::<String>();
+        if id.len() < MIN_LENGTH.saturating_sub(1) {
+            return Err(IdError::TooShort);
+        }
+
+        let check_char = alphabet::calculate_check_char(&id)?;
+        id.push(check_char);
+        Ok(Self(id.into()))
+    }
+
+    /// Convert this ID back to the bytes it was created from with [`Id::from_bytes`]
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if the ID contains a character outside [`BYTES_ALPHABET`],
+    ///   i.e. it was not created from bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IdError> {
+        let digits = self
+            .body()
+            .chars()
+            .map(|c| {
+                BYTES_ALPHABET
+                    .iter()
+                    .position(|d| *d == c)
+                    .ok_or(IdError::InvalidCharacter)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        convert(digits, BYTES_ALPHABET.len(), 256)
+            .into_iter()
+            .map(|d| u8::try_from(d).map_err(|_| IdError::InvalidCharacter))
+            .collect()
+    }
+
+    /// Encode a counter as an ID of `width` digits plus a check character
+    ///
+    /// Digits are written most significant first and zero-padded, so for a fixed `width`, IDs sort
+    /// lexically in the same order as their counters. IDs from an increasing counter, e.g. a
+    /// database sequence, are then always appended at the end of an index over the ID column.
+    /// A `width` of [`FULL_COUNTER_WIDTH`] fits every `u64`.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if `width` is below the minimum length of an ID body
+    /// - [`IdError::TooLong`] if `counter` needs more than `width` digits
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let ids = [9, 10, 500, 501].map(|n| Id::from_counter(n, 6).unwrap());
+    /// assert!(ids.iter().map(Id::as_str).is_sorted());
+    /// assert_eq!(ids[2].to_counter(), Ok(500));
+    /// ```
+    pub fn from_counter(counter: u64, width: usize) -> Result<Self, IdError> {
+        if width < MIN_LENGTH.saturating_sub(1) {
+            return Err(IdError::TooShort);
+        }
+
+        let base = SORTABLE_ALPHABET.len() as u64;
+        let mut digits = Vec::with_capacity(width);
+        let mut rest = counter;
+        while rest > 0 {
+            digits.push(usize::try_from(rest % base).unwrap_or_default());
+            rest /= base;
+        }
+        if digits.len() > width {
+            return Err(IdError::TooLong);
+        }
+        digits.resize(width, 0);
+
+        let mut id = digits
+            .into_iter()
+            .rev()
+            .filter_map(|d| SORTABLE_ALPHABET.get(d))
+            .collect::<String>();
+        let check_char = alphabet::calculate_check_char(&id)?;
+        id.push(check_char);
+        Ok(Self(id.into()))
+    }
+
+    /// Decode the counter of an ID created with [`Id::from_counter`]
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if the ID contains a character outside
+    ///   [`SORTABLE_ALPHABET`], i.e. it was not created from a counter
+    /// - [`IdError::TooLong`] if the counter does not fit in a `u64`
+    pub fn to_counter(&self) -> Result<u64, IdError> {
+        let base = SORTABLE_ALPHABET.len() as u64;
+        self.body().chars().try_fold(0_u64, |acc, c| {
+            let digit = SORTABLE_ALPHABET
+                .iter()
+                .position(|d| *d == c)
+                .ok_or(IdError::InvalidCharacter)?;
+            acc.checked_mul(base)
+                .and_then(|acc| acc.checked_add(digit as u64))
+                .ok_or(IdError::TooLong)
+        })
+    }
+
+    /// Convert a base58 string, e.g. a Bitcoin-style identifier, to an ID
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if `s` contains a character outside [`BASE58_ALPHABET`]
+    /// - [`IdError::TooShort`] if the resulting ID would be below the minimum length
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_base58("3yZe7d").unwrap();
+    /// assert_eq!(id.to_base58().as_deref(), Ok("3yZe7d"));
+    /// ```
+    pub fn from_base58(s: &str) -> Result<Self, IdError> {
+        Self::from_bytes(&decode(s, BASE58_ALPHABET)?)
+    }
+
+    /// Convert this ID to the base58 string it was created from with [`Id::from_base58`]
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::to_bytes`].
+    pub fn to_base58(&self) -> Result<String, IdError> {
+        Ok(encode(&self.to_bytes()?, BASE58_ALPHABET))
+    }
+
+    /// Convert a base62 string, e.g. a short URL code, to an ID
+    ///
+    /// Leading zero digits are significant and are preserved, as in base58.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if `s` contains a character outside [`BASE62_ALPHABET`]
+    /// - [`IdError::TooShort`] if the resulting ID would be below the minimum length
+    pub fn from_base62(s: &str) -> Result<Self, IdError> {
+        Self::from_bytes(&decode(s, BASE62_ALPHABET)?)
+    }
+
+    /// Convert this ID to the base62 string it was created from with [`Id::from_base62`]
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::to_bytes`].
+    pub fn to_base62(&self) -> Result<String, IdError> {
+        Ok(encode(&self.to_bytes()?, BASE62_ALPHABET))
+    }
+}
+
+/// Write `bytes` in base `alphabet.len()`
+fn encode(bytes: &[u8], alphabet: &str) -> String {
+    let chars = alphabet.chars().collect::<Vec<_>>();
+    convert(bytes.iter().map(|b| usize::from(*b)), 256, chars.len())
+        .into_iter()
+        .filter_map(|d| chars.get(d))
+        .collect()
+}
+
+/// Read `s` as a number in base `alphabet.len()`, returning its bytes
+fn decode(s: &str, alphabet: &str) -> Result<Vec<u8>, IdError> {
+    let digits = s
+        .chars()
+        .map(|c| alphabet.chars().position(|d| d == c))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(IdError::InvalidCharacter)?;
+
+    convert(digits, alphabet.chars().count(), 256)
+        .into_iter()
+        .map(|d| u8::try_from(d).map_err(|_| IdError::InvalidCharacter))
+        .collect()
+}
+
+/// Convert big-endian `digits` from base `from` to base `to`, keeping leading zeros
+fn convert(digits: impl IntoIterator<Item = usize>, from: usize, to: usize) -> Vec<usize> {
+    let mut leading_zeros = 0_usize;
+    let mut seen_non_zero = false;
+    // Little-endian digits of the result so far.
+    let mut result: Vec<usize> = Vec::new();
+
+    for digit in digits {
+        if digit == 0 && !seen_non_zero {
+            leading_zeros = leading_zeros.saturating_add(1);
+            continue;
+        }
+        seen_non_zero = true;
+
+        let mut carry = digit;
+        for slot in &mut result {
+            carry = carry.saturating_add(slot.saturating_mul(from));
+            *slot = carry.checked_rem(to).unwrap_or_default();
+            carry = carry.checked_div(to).unwrap_or_default();
+        }
+        while carry > 0 {
+            result.push(carry.checked_rem(to).unwrap_or_default());
+            carry = carry.checked_div(to).unwrap_or_default();
+        }
+    }
+
+    result.resize(result.len().saturating_add(leading_zeros), 0);
+    result.reverse();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn bytes_roundtrip() {
+        let mut rng = rand::rng();
+        for len in 0..40 {
+            for _ in 0..100 {
+                let mut bytes = vec![0_u8; len];
+                rand::RngCore::fill_bytes(&mut rng, &mut bytes);
+                match Id::from_bytes(&bytes) {
+                    Ok(id) => {
+                        assert_eq!(Id::from_str(&id), Ok(id.clone()));
+                        assert_eq!(id.to_bytes(), Ok(bytes));
+                    }
+                    Err(e) => {
+                        assert_eq!(e, IdError::TooShort);
+                        assert!(len < 3);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn leading_zeros_are_preserved() {
+        let id = Id::from_bytes(&[0, 0, 0]).expect("three zero digits");
+        assert!(id.starts_with("aaa"));
+        assert_eq!(id.to_bytes(), Ok(vec![0, 0, 0]));
+        assert_ne!(Id::from_bytes(&[0, 0, 1, 0]), Id::from_bytes(&[0, 1, 0]));
+    }
+
+    #[test]
+    fn sortable_alphabet_is_sorted_bytes_alphabet() {
+        assert!(SORTABLE_ALPHABET.is_sorted());
+        let mut sorted = BYTES_ALPHABET;
+        sorted.sort_unstable();
+        assert_eq!(sorted, SORTABLE_ALPHABET);
+    }
+
+    #[test]
+    fn counters_sort_numerically() {
+        let mut rng = rand::rng();
+        let mut counters = (0..1_000)
+            .map(|_| rand::Rng::random::<u64>(&mut rng))
+            .chain([0, 1, 20, 21, 22, u64::MAX])
+            .collect::<Vec<_>>();
+        counters.sort_unstable();
+
+        let ids = counters
+            .iter()
+            .map(|n| Id::from_counter(*n, FULL_COUNTER_WIDTH).expect("every u64 fits"))
+            .collect::<Vec<_>>();
+        assert!(ids.iter().map(Id::as_str).is_sorted());
+        for (id, n) in ids.iter().zip(&counters) {
+            assert_eq!(Id::from_str(id), Ok(id.clone()));
+            assert_eq!(id.to_counter(), Ok(*n));
+        }
+    }
+
+    #[test]
+    fn counter_width_is_enforced() {
+        assert_eq!(Id::from_counter(0, 2), Err(IdError::TooShort));
+        assert_eq!(Id::from_counter(21 * 21 * 21, 3), Err(IdError::TooLong));
+        assert!(Id::from_counter(21 * 21 * 21 - 1, 3).is_ok());
+        assert_eq!(Id::from_counter(0, 20).expect("wide").to_counter(), Ok(0));
+        let overflow = Id::append_check_char(&"y".repeat(FULL_COUNTER_WIDTH)).expect("valid body");
+        assert_eq!(overflow.to_counter(), Err(IdError::TooLong));
+        let random = Id::append_check_char("rabcd").expect("valid body");
+        assert_eq!(random.to_counter(), Err(IdError::InvalidCharacter));
+    }
+
+    #[test]
+    fn base58_matches_reference_vectors() {
+        // From the Bitcoin Core test vectors.
+        assert_eq!(decode("1112", BASE58_ALPHABET), Ok(vec![0, 0, 0, 1]));
+        assert_eq!(
+            decode("2g", BASE58_ALPHABET),
+            Ok(vec![0x61]),
+            "single character"
+        );
+        assert_eq!(encode(b"hello world", BASE58_ALPHABET), "StV1DL6CwTryKyV");
+        assert_eq!(
+            decode("0OIl", BASE58_ALPHABET),
+            Err(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn base62_roundtrip() {
+        for code in ["aZ09", "00xK", "Zzzzzzzzzz", "0000"] {
+            let id = Id::from_base62(code).expect("long enough");
+            assert_eq!(id.to_base62().as_deref(), Ok(code));
+        }
+        assert_eq!(Id::from_base62("a-b"), Err(IdError::InvalidCharacter));
+    }
+
+    #[test]
+    fn generated_ids_may_not_convert_back() {
+        let id = Id::from_str("aybran").expect("valid ID");
+        assert_eq!(id.to_bytes(), Err(IdError::InvalidCharacter));
+    }
+}
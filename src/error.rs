@@ -1,9 +1,11 @@
 // src/error.rs
 //! Error types for user-friendly ID operations
 
+use std::{collections::HashMap, hash::BuildHasher};
+
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq, Clone)]
+#[derive(Error, Debug, PartialEq, Eq, Clone, Hash)]
 pub enum IdError {
     #[error("Invalid character in ID")]
     InvalidCharacter,
@@ -16,3 +18,149 @@ pub enum IdError {
     #[error("Invalid sequence in ID")]
     InvalidSequence,
 }
+
+impl IdError {
+    /// Every error, e.g. for checking a translation is complete
+    pub const ALL: [Self; 5] = [
+        Self::InvalidCharacter,
+        Self::InvalidCheckBit,
+        Self::TooShort,
+        Self::TooLong,
+        Self::InvalidSequence,
+    ];
+
+    /// Stable, machine readable code for the error, e.g. `invalid_check_bit`
+    ///
+    /// Codes never change between releases, unlike the English messages from `Display`, so use
+    /// them as keys when translating errors or matching on them outside of Rust.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidCharacter => "invalid_character",
+            Self::InvalidCheckBit => "invalid_check_bit",
+            Self::TooShort => "too_short",
+            Self::TooLong => "too_long",
+            Self::InvalidSequence => "invalid_sequence",
+        }
+    }
+
+    /// Render the error using a set of translated messages, falling back to English
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use human_friendly_ids::{Id, error::IdError};
+    ///
+    /// let german = HashMap::from([("invalid_check_bit", "Ungültige Prüfziffer")]);
+    /// let error = "wcfytxww4opin4jmjjes4ccfe".parse::<Id>().unwrap_err();
+    /// assert_eq!(error.localized(&german), "Ungültige Prüfziffer");
+    /// assert_eq!(IdError::TooLong.localized(&german), IdError::TooLong.to_string());
+    /// ```
+    #[must_use]
+    pub fn localized<M: ErrorMessages + ?Sized>(&self, messages: &M) -> String {
+        messages.message(self).unwrap_or_else(|| self.to_string())
+    }
+}
+
+/// Source of translated error messages, see [`IdError::localized`]
+///
+/// Implemented for maps from [`IdError::code`] to message, implement it directly to plug in a
+/// translation framework such as `fluent`.
+pub trait ErrorMessages {
+    /// Message for `error`, or `None` to fall back to the English message
+    fn message(&self, error: &IdError) -> Option<String>;
+}
+
+impl<K, V, S> ErrorMessages for HashMap<K, V, S>
+where
+    K: std::borrow::Borrow<str> + Eq + std::hash::Hash,
+    V: AsRef<str>,
+    S: BuildHasher,
+{
+    fn message(&self, error: &IdError) -> Option<String> {
+        self.get(error.code()).map(|m| m.as_ref().to_owned())
+    }
+}
+
+impl<F> ErrorMessages for F
+where
+    F: Fn(&IdError) -> Option<String>,
+{
+    fn message(&self, error: &IdError) -> Option<String> {
+        self(error)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize errors as their variant name, e.g. `"InvalidCheckBit"`, for structured API responses
+mod serde_impl {
+    use serde::{Serialize, Serializer};
+
+    use super::IdError;
+
+    impl Serialize for IdError {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let (index, name) = match self {
+                Self::InvalidCharacter => (0, "InvalidCharacter"),
+                Self::InvalidCheckBit => (1, "InvalidCheckBit"),
+                Self::TooShort => (2, "TooShort"),
+                Self::TooLong => (3, "TooLong"),
+                Self::InvalidSequence => (4, "InvalidSequence"),
+            };
+            serializer.serialize_unit_variant("IdError", index, name)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn serializes_variant_names() {
+            let errors = [
+                IdError::InvalidCharacter,
+                IdError::InvalidCheckBit,
+                IdError::TooShort,
+                IdError::TooLong,
+                IdError::InvalidSequence,
+            ];
+            insta::assert_json_snapshot!(errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut codes = IdError::ALL.map(|e| e.code()).to_vec();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), IdError::ALL.len());
+    }
+
+    #[test]
+    fn localized_messages() {
+        let french = |error: &IdError| match error {
+            IdError::TooShort => Some(String::from("Identifiant trop court")),
+            _ => None,
+        };
+        assert_eq!(
+            IdError::TooShort.localized(&french),
+            "Identifiant trop court"
+        );
+        assert_eq!(
+            IdError::InvalidSequence.localized(&french),
+            "Invalid sequence in ID"
+        );
+
+        let owned = HashMap::from([(String::from("too_long"), String::from("Zu lang"))]);
+        assert_eq!(IdError::TooLong.localized(&owned), "Zu lang");
+    }
+}
@@ -0,0 +1,105 @@
+// src/extract.rs
+//! Find IDs mentioned in free text, such as support tickets and log lines
+
+use std::ops::Range;
+
+use crate::{Id, checksum::FormatVersion};
+
+/// Shortest word [`Id::extract_all`] will consider
+///
+/// One in 23 words validates as an ID by chance, so short words would flood the results with
+/// ordinary English.
+pub const DEFAULT_MIN_LENGTH: usize = 8;
+
+impl Id {
+    /// Find every word in `text` that is a valid ID after normalization
+    ///
+    /// Words are maximal runs of ASCII letters and digits, so IDs must be delimited by whitespace,
+    /// punctuation or the ends of the text. Words shorter than [`DEFAULT_MIN_LENGTH`] are skipped,
+    /// see [`Id::extract_all_with_min_length`] to change this. Ranges are byte offsets into `text`.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let text = "Customer says order WCFYTXWW4OPIN4JMJJES4CCFD never arrived.";
+    /// let found = Id::extract_all(text);
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(&text[found[0].0.clone()], "WCFYTXWW4OPIN4JMJJES4CCFD");
+    /// assert_eq!(found[0].1.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    #[must_use]
+    pub fn extract_all(text: &str) -> Vec<(Range<usize>, Self)> {
+        Self::extract_all_with_min_length(text, DEFAULT_MIN_LENGTH)
+    }
+
+    /// Find every word of at least `min_len` characters in `text` that is a valid ID
+    ///
+    /// See [`Id::extract_all`].
+    #[must_use]
+    pub fn extract_all_with_min_length(text: &str, min_len: usize) -> Vec<(Range<usize>, Self)> {
+        words(text)
+            .filter(|range| range.len() >= min_len)
+            .filter_map(|range| {
+                let id = Self::parse_versioned(text.get(range.clone())?, FormatVersion::V1).ok()?;
+                Some((range, id))
+            })
+            .collect()
+    }
+}
+
+/// Byte ranges of the maximal runs of ASCII letters and digits in `text`
+fn words(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| {
+            if c.is_ascii_alphanumeric() {
+                start.get_or_insert(i);
+                None
+            } else {
+                start.take().map(|start| start..i)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn finds_ids_in_prose() {
+        let a = Id::new(25);
+        let b = Id::new(12);
+        let text = format!("ticket #{a}: see also ({}), thanks!\n{a}", b.to_uppercase());
+
+        let found = Id::extract_all(&text);
+        let ids = found.iter().map(|(_, id)| id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids, vec![a.clone(), b.clone(), a]);
+        for (range, id) in found {
+            assert_eq!(Id::from_str(&text[range]), Ok(id));
+        }
+    }
+
+    #[test]
+    fn ids_must_be_whole_words() {
+        let id = Id::new(12);
+        // The longer word may validate by chance, but never the ID inside it.
+        let ranges = |text: String| Id::extract_all(&text).into_iter().map(|(r, _)| r);
+        assert!(ranges(format!("x{id}")).all(|r| r == (0..13)));
+        assert!(ranges(format!("{id}9")).all(|r| r == (0..13)));
+        assert_eq!(ranges(format!("é{id}é")).collect::<Vec<_>>(), vec![2..14]);
+    }
+
+    #[test]
+    fn short_words_are_skipped() {
+        let id = Id::new(6);
+        assert!(Id::extract_all(&id).is_empty());
+        assert_eq!(
+            Id::extract_all_with_min_length(&format!("- {id} -"), 6),
+            vec![(2..8, id)]
+        );
+    }
+}
@@ -0,0 +1,1197 @@
+// src/generator.rs
+//! Configurable ID generation policies
+//!
+//! # Concurrency
+//!
+//! An [`IdGenerator`] is immutable once built and generates through `&self`, with random state
+//! kept per thread by the default RNG backend: `rand` uses its thread-local RNG, `fastrand` forks a
+//! thread-local seed and `getrandom` asks the operating system directly. A single generator can
+//! therefore be shared by every thread, e.g. as a `static`, without any lock for threads to
+//! contend on:
+//!
+//! ```
+//! use std::num::NonZeroUsize;
+//!
+//! use human_friendly_ids::generator::IdGenerator;
+//!
+//! static ORDER_IDS: IdGenerator = IdGenerator::new(16).with_max_run(NonZeroUsize::MIN);
+//!
+//! let handles = (0..4)
+//!     .map(|_| std::thread::spawn(|| ORDER_IDS.generate()))
+//!     .collect::<Vec<_>>();
+//! for handle in handles {
+//!     assert_eq!(handle.join().unwrap().len(), 16);
+//! }
+//! ```
+//!
+//! Avoid wrapping a single RNG in a `Mutex` and passing it to
+//! [`IdGenerator::generate_with_rng`]: every thread then queues on that lock. Run
+//! `cargo bench --bench contention` to compare the two at increasing thread counts on your
+//! hardware. Observers registered with [`IdGenerator::with_observer`] run on the generating thread
+//! and should avoid locks of their own.
+
+use std::{fmt, num::NonZeroUsize, sync::Arc};
+
+use rand_core::RngCore;
+
+use crate::{
+    Id,
+    alphabet::{self, GEN_ALPHABET},
+    checksum::FormatVersion,
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+    phonetic::{ConfusionMatrix, pronounceability},
+    telemetry::{self, RetryReason},
+};
+
+/// Number of distinct shard hints, one per character of the generation alphabet
+pub const SHARD_HINT_COUNT: usize = GEN_ALPHABET.len();
+
+/// Number of candidates tried before settling for the most pronounceable one seen
+const MAX_PRONOUNCEABILITY_ATTEMPTS: usize = 1_000;
+
+/// Number of candidates [`IdGenerator::generate_unique`] tries before giving up
+pub const MAX_COLLISION_ATTEMPTS: usize = 100;
+
+/// Something that happened while generating an ID, see [`IdGenerator::with_observer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationEvent<'a> {
+    /// An ID was generated and handed to the caller
+    Generated(&'a str),
+    /// A candidate fell short of the pronounceability policy, so another was drawn
+    Unpronounceable(&'a str),
+    /// A candidate was already taken, so another was drawn, see [`IdGenerator::generate_unique`]
+    Collision(&'a str),
+    /// IDs were lengthened after `issued` had been issued, see
+    /// [`AdaptiveGenerator`](crate::adaptive::AdaptiveGenerator)
+    Lengthened {
+        /// Previous length of IDs
+        from: usize,
+        /// New length of IDs
+        to: usize,
+        /// Number of IDs issued when the length changed
+        issued: u64,
+    },
+}
+
+/// Callback registered with [`IdGenerator::with_observer`]
+#[derive(Clone)]
+struct Observer(Arc<dyn Fn(&GenerationEvent<'_>) + Send + Sync>);
+
+#[cfg_attr(test, mutants::skip)]
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observer").finish_non_exhaustive()
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl PartialEq for Observer {
+    /// Generators are only equal when they share the same observer
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+// Generators are shared between threads without locking, see the module documentation.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<IdGenerator>();
+};
+
+/// Named ID lengths by the entropy they carry, so lengths aren't picked arbitrarily
+///
+/// Lengths below are for the default policies, stricter policies lengthen the IDs to keep the
+/// entropy. See [`IdGenerator::with_preset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPreset {
+    /// At least 32 bits, 9 characters: fine for IDs scoped to a user or looked up alongside other
+    /// data, where a collision is cheap to retry
+    Short,
+    /// At least 64 bits, 16 characters: suitable for most database keys
+    Standard,
+    /// At least 128 bits, 30 characters: as strong as a random UUID, for globally unique or
+    /// unguessable IDs
+    Long,
+}
+
+impl LengthPreset {
+    /// Minimum entropy of IDs generated with this preset, in bits
+    #[must_use]
+    pub const fn entropy_bits(self) -> f64 {
+        match self {
+            Self::Short => 32.0,
+            Self::Standard => 64.0,
+            Self::Long => 128.0,
+        }
+    }
+}
+
+/// Generator for IDs of a fixed length with configurable generation policies
+///
+/// [`Id::new`] and [`Id::new_with_rng`] use a generator with the default policies, construct one
+/// directly to tighten them.
+///
+/// # Example
+/// ```
+/// use std::num::NonZeroUsize;
+///
+/// use human_friendly_ids::generator::IdGenerator;
+///
+/// let generator = IdGenerator::new(12).with_max_run(NonZeroUsize::new(2).unwrap());
+/// let id = generator.generate();
+/// assert_eq!(id.len(), 12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdGenerator {
+    /// Total length of generated IDs, including the check character
+    len: usize,
+    /// Maximum number of times the same character may appear consecutively
+    max_run: Option<NonZeroUsize>,
+    /// Minimum [`pronounceability`] score of generated IDs
+    min_pronounceability: Option<f32>,
+    /// Shard index embedded as the first character, see [`Id::shard_hint`]
+    shard_hint: Option<u8>,
+    /// Format determining the check character algorithm
+    format: FormatVersion,
+    /// Characters that must not be placed next to each other
+    confusion: Option<ConfusionMatrix>,
+    /// Callback notified of every [`GenerationEvent`]
+    observer: Option<Observer>,
+}
+
+impl IdGenerator {
+    /// Create a generator for IDs of a given length with the default policies
+    ///
+    /// Lengths above [`MAX_PARSE_LENGTH`](crate::id::MAX_PARSE_LENGTH) are allowed, but such IDs
+    /// only parse back with [`Id::from_str_with_limit`].
+    #[must_use]
+    pub const fn new(len: usize) -> Self {
+        Self {
+            len,
+            max_run: None,
+            min_pronounceability: None,
+            shard_hint: None,
+            format: FormatVersion::V1,
+            confusion: None,
+            observer: None,
+        }
+    }
+
+    /// Prevent the same character appearing more than `max_run` times in a row, e.g. `bbbb`
+    ///
+    /// Long runs are hard to transcribe accurately, as readers lose count of the repetitions. The
+    /// check character counts towards the run.
+    #[must_use]
+    pub const fn with_max_run(mut self, max_run: NonZeroUsize) -> Self {
+        self.max_run = Some(max_run);
+        self
+    }
+
+    /// Regenerate IDs until their [`pronounceability`] score is at least `threshold`
+    ///
+    /// Useful for customer-facing codes that are read over the phone. Very high thresholds are
+    /// rarely met by long IDs, so after 1000 attempts the most pronounceable candidate is returned
+    /// instead.
+    #[must_use]
+    pub const fn with_min_pronounceability(mut self, threshold: f32) -> Self {
+        self.min_pronounceability = Some(threshold);
+        self
+    }
+
+    /// Embed a shard index as the first character of generated IDs, read back by [`Id::shard_hint`]
+    ///
+    /// Lets a routing tier pick a partition from the ID alone. The hint takes the place of a random
+    /// character, costing a little under 4.6 bits of entropy. IDs shorter than four
+    /// characters can't be parsed, so they never carry a hint.
+    ///
+    /// # Panics
+    ///
+    /// If `shard` is not below [`SHARD_HINT_COUNT`].
+    #[must_use]
+    pub const fn with_shard_hint(mut self, shard: u8) -> Self {
+        assert!(
+            (shard as usize) < SHARD_HINT_COUNT,
+            "shard hints must be below SHARD_HINT_COUNT"
+        );
+        self.shard_hint = Some(shard);
+        self
+    }
+
+    /// Generate IDs in a given format, [`FormatVersion::V1`] by default
+    ///
+    /// IDs in any other format must be parsed with [`Id::from_str_versioned`].
+    #[must_use]
+    pub const fn with_format(mut self, format: FormatVersion) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Never place two characters `matrix` considers confusable next to each other
+    ///
+    /// Useful for IDs read over the phone, see [`ConfusionMatrix`]. The check character is included,
+    /// and the entropy lost is accounted for by [`IdGenerator::entropy_bits`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{generator::IdGenerator, phonetic::ConfusionMatrix};
+    ///
+    /// let id = IdGenerator::new(25)
+    ///     .with_confusion_matrix(ConfusionMatrix::AUDIBLE)
+    ///     .generate();
+    /// assert!(!id.contains("bp") && !id.contains("nm"));
+    /// ```
+    #[must_use]
+    pub const fn with_confusion_matrix(mut self, matrix: ConfusionMatrix) -> Self {
+        self.confusion = Some(matrix);
+        self
+    }
+
+    /// Call `observer` for every [`GenerationEvent`], e.g. to audit issuance or publish events
+    ///
+    /// The observer runs synchronously on the generating thread, so keep it cheap. Clones of the
+    /// generator share the observer, and registering another one replaces it.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use human_friendly_ids::generator::{GenerationEvent, IdGenerator};
+    ///
+    /// let issued = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&issued);
+    /// let generator = IdGenerator::new(12).with_observer(move |event| {
+    ///     if let GenerationEvent::Generated(id) = event {
+    ///         log.lock().unwrap().push(id.to_string());
+    ///     }
+    /// });
+    ///
+    /// let id = generator.generate();
+    /// assert_eq!(*issued.lock().unwrap(), vec![id.to_string()]);
+    /// ```
+    #[must_use]
+    pub fn with_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&GenerationEvent<'_>) + Send + Sync + 'static,
+    {
+        self.observer = Some(Observer(Arc::new(observer)));
+        self
+    }
+
+    /// Lengthen generated IDs until they carry at least `bits` of entropy
+    ///
+    /// The length is only ever increased, and is computed against the policies configured so far,
+    /// so call this last.
+    ///
+    /// # Panics
+    ///
+    /// If `bits` is not finite, or reaching it would lengthen IDs beyond [`MAX_PARSE_LENGTH`].
+    #[must_use]
+    pub fn with_min_entropy(mut self, bits: f64) -> Self {
+        assert!(bits.is_finite(), "entropy must be finite, got {bits}");
+
+        // Every body character carries a little under log2(23) bits, so start from that lower
+        // bound and walk upwards.
+        #[allow(clippy::cast_precision_loss, reason = "the alphabet is tiny")]
+        let per_char = (GEN_ALPHABET.len() as f64).log2();
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "bits is finite, and saturating float casts clamp negative bits to zero"
+        )]
+        let estimate = (bits / per_char).ceil() as usize;
+        assert!(
+            estimate < MAX_PARSE_LENGTH,
+            "{bits} bits of entropy needs IDs longer than {MAX_PARSE_LENGTH} characters"
+        );
+        self.len = self.len.max(estimate.saturating_add(1));
+
+        while self.entropy_bits() < bits {
+            assert!(
+                self.len < MAX_PARSE_LENGTH,
+                "{bits} bits of entropy needs IDs longer than {MAX_PARSE_LENGTH} characters"
+            );
+            self.len = self.len.saturating_add(1);
+        }
+        self
+    }
+
+    /// Lengthen generated IDs to meet a [`LengthPreset`], see [`IdGenerator::with_min_entropy`]
+    ///
+    /// Like [`IdGenerator::with_min_entropy`], call this after configuring other policies.
+    ///
+    /// # Example
+    /// ```
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use human_friendly_ids::generator::{IdGenerator, LengthPreset};
+    ///
+    /// let generator = IdGenerator::new(0).with_preset(LengthPreset::Standard);
+    /// assert_eq!(generator.length(), 16);
+    ///
+    /// let strict = IdGenerator::new(0)
+    ///     .with_max_run(NonZeroUsize::new(1).unwrap())
+    ///     .with_preset(LengthPreset::Standard);
+    /// assert!(strict.entropy_bits() >= LengthPreset::Standard.entropy_bits());
+    /// ```
+    #[must_use]
+    pub fn with_preset(self, preset: LengthPreset) -> Self {
+        self.with_min_entropy(preset.entropy_bits())
+    }
+
+    /// Total length of generated IDs, including the check character
+    #[must_use]
+    pub const fn length(&self) -> usize {
+        self.len
+    }
+
+    /// Format of generated IDs, see [`IdGenerator::with_format`]
+    #[must_use]
+    pub const fn format(&self) -> FormatVersion {
+        self.format
+    }
+
+    /// Generate a new ID using the default RNG backend, see [`crate::rng`]
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn generate(&self) -> Id {
+        let mut rng = crate::rng::default_rng();
+        self.generate_with_rng(&mut rng)
+    }
+
+    /// Generate a new ID using the given RNG
+    ///
+    /// Any [`RngCore`] is accepted, see [`crate::compat`] for RNGs from older versions of `rand`.
+    #[must_use]
+    pub fn generate_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> Id {
+        let mut buf = String::with_capacity(self.len);
+        self.generate_into_with_rng(rng, &mut buf);
+        Id(buf.into())
+    }
+
+    /// Generate a new ID into `buf` using the default RNG backend, replacing its contents
+    ///
+    /// See [`IdGenerator::generate_into_with_rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    pub fn generate_into(&self, buf: &mut String) {
+        let mut rng = crate::rng::default_rng();
+        self.generate_into_with_rng(&mut rng, buf);
+    }
+
+    /// Generate a new ID into `buf` using the given RNG, replacing its contents
+    ///
+    /// Reusing a buffer avoids allocating for every ID, e.g. when writing a large batch of IDs
+    /// straight to a file. The buffer is only grown if it can't already hold the ID.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, generator::IdGenerator};
+    ///
+    /// let generator = IdGenerator::new(12);
+    /// let mut buf = String::new();
+    /// for _ in 0..10 {
+    ///     generator.generate_into_with_rng(&mut rand::rng(), &mut buf);
+    ///     assert!(buf.parse::<Id>().is_ok());
+    /// }
+    /// ```
+    pub fn generate_into_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R, buf: &mut String) {
+        let mut sampler = IndexSampler::new();
+        self.generate_pronounceable(rng, &mut sampler, buf);
+        self.notify(&GenerationEvent::Generated(buf));
+    }
+
+    /// Endless stream of IDs using the default RNG backend, for async pipelines
+    ///
+    /// Each ID is only generated when the stream is polled, so a slow consumer never causes IDs to
+    /// pile up in memory. The stream holds its own copy of the generator and is `Send`, so it can
+    /// be moved into a spawned task.
+    ///
+    /// # Example
+    /// ```
+    /// use futures_util::StreamExt;
+    /// use human_friendly_ids::generator::IdGenerator;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let ids = IdGenerator::new(12).stream().take(3).collect::<Vec<_>>().await;
+    /// assert_eq!(ids.len(), 3);
+    ///
+    /// let mut stream = IdGenerator::new(8).stream();
+    /// let id = stream.next().await.unwrap();
+    /// assert_eq!(id.len(), 8);
+    /// # });
+    /// ```
+    #[cfg(all(
+        feature = "async",
+        any(feature = "rand", feature = "getrandom", feature = "fastrand")
+    ))]
+    #[must_use]
+    pub fn stream(&self) -> IdStream {
+        IdStream {
+            generator: self.clone(),
+        }
+    }
+
+    /// Generate `count` IDs using the default RNG backend
+    ///
+    /// See [`IdGenerator::generate_batch_with_rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn generate_batch(&self, count: usize) -> Vec<Id> {
+        let mut rng = crate::rng::default_rng();
+        self.generate_batch_with_rng(&mut rng, count)
+    }
+
+    /// Generate `count` IDs using the given RNG
+    ///
+    /// Random bytes are shared across the whole batch, which is cheaper than generating the IDs
+    /// one at a time. IDs in a batch are independent, so may repeat, see
+    /// [`IdGenerator::generate_unique_with_rng`] to rule that out.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::generator::IdGenerator;
+    ///
+    /// let ids = IdGenerator::new(12).generate_batch(100);
+    /// assert_eq!(ids.len(), 100);
+    /// ```
+    #[must_use]
+    pub fn generate_batch_with_rng<R: RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        count: usize,
+    ) -> Vec<Id> {
+        let _span = telemetry::enter_batch(count, self.len);
+        let mut sampler = IndexSampler::new();
+        (0..count)
+            .map(|_| {
+                let mut buf = String::with_capacity(self.len);
+                self.generate_pronounceable(rng, &mut sampler, &mut buf);
+                self.notify(&GenerationEvent::Generated(&buf));
+                Id(buf.into())
+            })
+            .collect()
+    }
+
+    /// Generate an ID for which `is_taken` returns `false`, using the default RNG backend
+    ///
+    /// See [`IdGenerator::generate_unique_with_rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    pub fn generate_unique<F>(&self, is_taken: F) -> Option<Id>
+    where
+        F: FnMut(&Id) -> bool,
+    {
+        let mut rng = crate::rng::default_rng();
+        self.generate_unique_with_rng(&mut rng, is_taken)
+    }
+
+    /// Generate an ID for which `is_taken` returns `false` using the given RNG, redrawing on
+    /// collisions
+    ///
+    /// Each collision is reported to the observer as [`GenerationEvent::Collision`]. Returns `None`
+    /// if all [`MAX_COLLISION_ATTEMPTS`] candidates are taken, which means the IDs are too short for
+    /// the number already issued.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use human_friendly_ids::generator::IdGenerator;
+    ///
+    /// let mut issued = HashSet::new();
+    /// let generator = IdGenerator::new(8);
+    /// for _ in 0..100 {
+    ///     let id = generator.generate_unique(|id| issued.contains(id)).unwrap();
+    ///     issued.insert(id);
+    /// }
+    /// assert_eq!(issued.len(), 100);
+    /// ```
+    pub fn generate_unique_with_rng<R, F>(&self, rng: &mut R, mut is_taken: F) -> Option<Id>
+    where
+        R: RngCore + ?Sized,
+        F: FnMut(&Id) -> bool,
+    {
+        let _span = telemetry::enter_unique(self.len);
+        let mut sampler = IndexSampler::new();
+        let mut buf = String::with_capacity(self.len);
+        for _ in 0..MAX_COLLISION_ATTEMPTS {
+            self.generate_pronounceable(rng, &mut sampler, &mut buf);
+            let id = Id(std::mem::take(&mut buf).into());
+            if is_taken(&id) {
+                self.notify(&GenerationEvent::Collision(&id));
+                buf = id.0.into_owned();
+                continue;
+            }
+
+            self.notify(&GenerationEvent::Generated(&id));
+            return Some(id);
+        }
+        None
+    }
+
+    /// Report `event` to the observer, if any, and record it in the metrics
+    pub(crate) fn notify(&self, event: &GenerationEvent<'_>) {
+        match event {
+            GenerationEvent::Generated(_) => telemetry::record_generated(),
+            GenerationEvent::Unpronounceable(_) => {
+                telemetry::record_retry(RetryReason::Unpronounceable);
+            }
+            GenerationEvent::Collision(_) => telemetry::record_retry(RetryReason::Collision),
+            GenerationEvent::Lengthened { from, to, issued } => {
+                telemetry::record_length_change(*from, *to, *issued);
+            }
+        }
+        if let Some(observer) = &self.observer {
+            (observer.0)(event);
+        }
+    }
+
+    /// Generate an ID obeying every policy into `buf`, replacing its contents
+    fn generate_pronounceable<R: RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        sampler: &mut IndexSampler,
+        buf: &mut String,
+    ) {
+        self.generate_candidate(rng, sampler, buf);
+        let Some(threshold) = self.min_pronounceability else {
+            return;
+        };
+
+        let mut best_score = pronounceability(buf);
+        if best_score < threshold {
+            self.notify(&GenerationEvent::Unpronounceable(buf));
+        }
+        let mut candidate = String::with_capacity(self.len);
+        for _ in 1..MAX_PRONOUNCEABILITY_ATTEMPTS {
+            if best_score >= threshold {
+                break;
+            }
+
+            self.generate_candidate(rng, sampler, &mut candidate);
+            let score = pronounceability(&candidate);
+            if score < threshold {
+                self.notify(&GenerationEvent::Unpronounceable(&candidate));
+            }
+            if score > best_score {
+                std::mem::swap(buf, &mut candidate);
+                best_score = score;
+            }
+        }
+    }
+
+    /// Generate a single ID obeying the sequence policies into `body`, replacing its contents
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - won't generate a string that would panic."
+    )]
+    fn generate_candidate<R: RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        sampler: &mut IndexSampler,
+        body: &mut String,
+    ) {
+        let body_len = self.len.saturating_sub(1);
+        body.clear();
+        body.reserve(self.len);
+        let mut last_char = None;
+        let mut run = 0_usize;
+
+        if let Some(c) = self.shard_char() {
+            body.push(c);
+            last_char = Some(c);
+            run = 1;
+        }
+
+        while body.len() < body_len {
+            let is_last = body.len() == self.len.saturating_sub(2);
+
+            // The check character is derived from the body, so it can only be kept from extending a
+            // run by choosing the final body character carefully. Occasionally no final character
+            // works, in which case the one before it is redrawn.
+            if is_last
+                && self.constrains_check_char()
+                && !GEN_ALPHABET
+                    .iter()
+                    .any(|c| self.allows_final(body, last_char, run, *c))
+            {
+                body.pop();
+                (last_char, run) = trailing_run(body);
+                continue;
+            }
+
+            let idx = sampler.next_index(rng);
+            #[allow(clippy::indexing_slicing, reason = "index is generated within bounds")]
+            let c = GEN_ALPHABET[idx];
+
+            if !self.allows(last_char, run, c, is_last) {
+                continue;
+            }
+
+            if is_last
+                && self.constrains_check_char()
+                && !self.allows_final(body, last_char, run, c)
+            {
+                continue;
+            }
+
+            let run_after = if last_char == Some(c) {
+                run.saturating_add(1)
+            } else {
+                1
+            };
+
+            body.push(c);
+            last_char = Some(c);
+            run = run_after;
+        }
+
+        let check_char = self
+            .format
+            .algorithm()
+            .check_char(body)
+            .expect("Generated body should be valid for check calculation");
+
+        body.push(check_char);
+    }
+
+    /// Character holding the shard hint, if one is configured and the IDs are long enough to hold it
+    ///
+    /// The hint always has at least two random characters after it, so redrawing the character
+    /// before the final one never touches it.
+    fn shard_char(&self) -> Option<char> {
+        let shard = self.shard_hint?;
+        if self.len < MIN_LENGTH {
+            return None;
+        }
+        GEN_ALPHABET.get(usize::from(shard)).copied()
+    }
+
+    /// Whether a policy restricts which check character may follow the body
+    const fn constrains_check_char(&self) -> bool {
+        self.max_run.is_some() || self.confusion.is_some()
+    }
+
+    /// Whether `c` may follow `last_char`, which has been repeated `run` times
+    fn allows(&self, last_char: Option<char>, run: usize, c: char, is_last: bool) -> bool {
+        match (last_char, c) {
+            // Avoid ambiguous sequences
+            (Some(last), c) if alphabet::ambiguous_replacement(last, c).is_some() => false,
+            (Some(last), c)
+                if self
+                    .confusion
+                    .is_some_and(|matrix| matrix.confusable(last, c)) =>
+            {
+                false
+            }
+            // Don't end with 'r' or 'v', because the check-bit could create an ambiguous sequence
+            (_, c) if is_last && alphabet::starts_ambiguous_sequence(c) => false,
+            (Some(last), c) if last == c => self.max_run.is_none_or(|max| run < max.get()),
+            _ => true,
+        }
+    }
+
+    /// Whether `c` may end the body, including the check character it would produce
+    fn allows_final(&self, body: &str, last_char: Option<char>, run: usize, c: char) -> bool {
+        if !self.allows(last_char, run, c, true) {
+            return false;
+        }
+
+        let run_after = if last_char == Some(c) {
+            run.saturating_add(1)
+        } else {
+            1
+        };
+        let mut candidate = String::with_capacity(body.len().saturating_add(1));
+        candidate.push_str(body);
+        candidate.push(c);
+        let check_char = self
+            .format
+            .algorithm()
+            .check_char(&candidate)
+            .expect("Generated body should be valid for check calculation");
+        self.allows(Some(c), run_after, check_char, false)
+    }
+
+    /// Shannon entropy in bits of the IDs produced by this generator
+    ///
+    /// This accounts for every sequence the generator refuses to emit, so it is lower than
+    /// `log2(23) * (len - 1)`. The check character is derived from the body and contributes no
+    /// entropy, the rare redraws needed to stop it extending a run are ignored. Rejection filters
+    /// such as [`IdGenerator::with_min_pronounceability`] are not accounted for.
+    ///
+    /// # Example
+    /// ```
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use human_friendly_ids::generator::IdGenerator;
+    ///
+    /// let relaxed = IdGenerator::new(12).entropy_bits();
+    /// let strict = IdGenerator::new(12)
+    ///     .with_max_run(NonZeroUsize::new(1).unwrap())
+    ///     .entropy_bits();
+    /// assert!(strict < relaxed);
+    /// ```
+    #[must_use]
+    pub fn entropy_bits(&self) -> f64 {
+        let body_len = self.len.saturating_sub(1);
+        let max_run = self.max_run.unwrap_or(NonZeroUsize::MIN).get();
+
+        // Probability of the generator being in each (last char, run length) state, the empty body
+        // is tracked separately.
+        let mut start = 1.0_f64;
+        let mut states = vec![0.0_f64; GEN_ALPHABET.len().saturating_mul(max_run)];
+        let mut entropy = 0.0_f64;
+        let mut first_position = 0;
+
+        // A shard hint fixes the first character, so it contributes nothing.
+        if let Some(c) = self.shard_char() {
+            let idx = GEN_ALPHABET
+                .iter()
+                .position(|g| *g == c)
+                .unwrap_or_default();
+            if let Some(slot) = states.get_mut(idx * max_run) {
+                *slot = 1.0;
+            }
+            start = 0.0;
+            first_position = 1;
+        }
+
+        for position in first_position..body_len {
+            let is_last = position == self.len.saturating_sub(2);
+            let mut next = vec![0.0_f64; states.len()];
+
+            let from_states =
+                states
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| **p > 0.0)
+                    .map(|(state, p)| {
+                        let last = GEN_ALPHABET.get(state / max_run).copied();
+                        (last, state % max_run + 1, *p)
+                    });
+
+            for (last_char, run, p) in std::iter::once((None, 0, start)).chain(from_states) {
+                if p <= 0.0 {
+                    continue;
+                }
+
+                let allowed = GEN_ALPHABET
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| self.allows(last_char, run, **c, is_last))
+                    .collect::<Vec<_>>();
+
+                #[allow(
+                    clippy::cast_precision_loss,
+                    reason = "at most 23 choices, exactly representable"
+                )]
+                let choices = allowed.len() as f64;
+                entropy += p * choices.log2();
+
+                for (idx, c) in allowed {
+                    let run = match last_char {
+                        Some(last) if last == *c && self.max_run.is_some() => run + 1,
+                        _ => 1,
+                    };
+                    if let Some(slot) = next.get_mut(idx * max_run + run - 1) {
+                        *slot += p / choices;
+                    }
+                }
+            }
+
+            start = 0.0;
+            states = next;
+        }
+
+        entropy
+    }
+}
+
+/// Number of random bytes requested from the RNG at a time
+const RANDOM_BUFFER_LEN: usize = 64;
+
+/// Source of uniformly distributed indices into the generation alphabet
+///
+/// Random bytes are requested in batches, as the per-call overhead of most RNGs dominates when
+/// only a single byte is needed. Bytes from the top of the range that would bias the result are
+/// rejected, which wastes 3 in 256.
+struct IndexSampler {
+    /// Random bytes, those before `pos` have been consumed
+    buffer: [u8; RANDOM_BUFFER_LEN],
+    /// Position of the next unused byte in `buffer`
+    pos: usize,
+}
+
+impl IndexSampler {
+    /// Create a sampler with an empty buffer
+    const fn new() -> Self {
+        Self {
+            buffer: [0; RANDOM_BUFFER_LEN],
+            pos: RANDOM_BUFFER_LEN,
+        }
+    }
+
+    /// Pick a uniformly distributed index into the generation alphabet
+    fn next_index<R: RngCore + ?Sized>(&mut self, rng: &mut R) -> usize {
+        const LEN: usize = GEN_ALPHABET.len();
+        const ZONE: usize = 256 - 256 % LEN;
+
+        loop {
+            if self.pos >= RANDOM_BUFFER_LEN {
+                rng.fill_bytes(&mut self.buffer);
+                self.pos = 0;
+            }
+
+            let value = self.buffer.get(self.pos).copied().map(usize::from);
+            self.pos = self.pos.saturating_add(1);
+            if let Some(value) = value.filter(|v| *v < ZONE) {
+                return value % LEN;
+            }
+        }
+    }
+}
+
+/// Last character of `body` and how many times it is repeated at the end
+fn trailing_run(body: &str) -> (Option<char>, usize) {
+    let last_char = body.chars().next_back();
+    let run = body
+        .chars()
+        .rev()
+        .take_while(|c| Some(*c) == last_char)
+        .count();
+    (last_char, run)
+}
+
+/// Stream of IDs from an [`IdGenerator`], see [`IdGenerator::stream`]
+#[cfg(all(
+    feature = "async",
+    any(feature = "rand", feature = "getrandom", feature = "fastrand")
+))]
+#[derive(Debug, Clone)]
+pub struct IdStream {
+    /// Generator producing each ID
+    generator: IdGenerator,
+}
+
+#[cfg(all(
+    feature = "async",
+    any(feature = "rand", feature = "getrandom", feature = "fastrand")
+))]
+impl futures_util::Stream for IdStream {
+    type Item = Id;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        std::task::Poll::Ready(Some(self.generator.generate()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    /// Length of the longest run of a single repeated character
+    fn longest_run(s: &str) -> usize {
+        let mut longest = 0;
+        let mut run = 0;
+        let mut last = None;
+        for c in s.chars() {
+            run = if last == Some(c) { run + 1 } else { 1 };
+            last = Some(c);
+            longest = longest.max(run);
+        }
+        longest
+    }
+
+    #[test]
+    fn max_run_is_respected() {
+        for max_run in 1..=3 {
+            let generator = IdGenerator::new(40).with_max_run(NonZeroUsize::new(max_run).unwrap());
+            for _ in 0..2_000 {
+                let id = generator.generate();
+                assert!(longest_run(&id) <= max_run, "{id} has a run over {max_run}");
+                assert_eq!(Id::from_str(&id), Ok(id.clone()));
+            }
+        }
+    }
+
+    /// RNG repeating a fixed sequence of bytes
+    struct RepeatingRng(Vec<u8>);
+
+    impl RngCore for RepeatingRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            for (dst, src) in dst.iter_mut().zip(self.0.iter().cycle()) {
+                *dst = *src;
+            }
+        }
+    }
+
+    #[test]
+    fn sampler_rejects_biased_bytes() {
+        let mut rng = RepeatingRng(vec![253, 254, 255, 0, 22, 23, 252]);
+        let mut sampler = IndexSampler::new();
+        let indices = (0..8)
+            .map(|_| sampler.next_index(&mut rng))
+            .collect::<Vec<_>>();
+        assert_eq!(indices, vec![0, 22, 0, 22, 0, 22, 0, 22]);
+    }
+
+    #[test]
+    fn generate_into_replaces_contents() {
+        let generator = IdGenerator::new(10).with_min_pronounceability(0.8);
+        let mut buf = String::from("previous contents that are much longer than an ID");
+        for _ in 0..100 {
+            generator.generate_into(&mut buf);
+            assert_eq!(buf.len(), 10);
+            assert!(pronounceability(&buf) >= 0.8, "{buf} is not pronounceable");
+            assert!(Id::from_str(&buf).is_ok());
+        }
+    }
+
+    #[test]
+    fn observer_sees_every_event() {
+        use std::sync::Mutex;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&events);
+        // The threshold is unreachable, so every candidate is rejected.
+        let generator = IdGenerator::new(12)
+            .with_min_pronounceability(2.0)
+            .with_observer(move |event| log.lock().unwrap().push(format!("{event:?}")));
+
+        let mut taken = 2;
+        let id = generator
+            .generate_unique(|_| {
+                taken -= 1;
+                taken >= 0
+            })
+            .expect("only two collisions");
+
+        let events = events.lock().unwrap();
+        let count = |kind: &str| events.iter().filter(|e| e.starts_with(kind)).count();
+        assert_eq!(count("Collision"), 2);
+        assert_eq!(count("Unpronounceable"), 3 * MAX_PRONOUNCEABILITY_ATTEMPTS);
+        assert_eq!(
+            events.last(),
+            Some(&format!("Generated({:?})", id.as_str()))
+        );
+    }
+
+    #[test]
+    fn generate_batch_gives_valid_ids() {
+        let generator = IdGenerator::new(12).with_shard_hint(3);
+        let ids = generator.generate_batch(1_000);
+        assert_eq!(ids.len(), 1_000);
+        for id in ids {
+            assert_eq!(Id::from_str(&id), Ok(id.clone()));
+            assert_eq!(id.shard_hint(), Some(3));
+        }
+        assert!(generator.generate_batch(0).is_empty());
+    }
+
+    #[test]
+    fn generate_unique_gives_up() {
+        let generator = IdGenerator::new(12);
+        assert_eq!(generator.generate_unique(|_| true), None);
+    }
+
+    #[test]
+    fn shard_hint_is_embedded() {
+        for shard in 0..SHARD_HINT_COUNT {
+            let shard = u8::try_from(shard).unwrap();
+            let generator = IdGenerator::new(6)
+                .with_shard_hint(shard)
+                .with_max_run(NonZeroUsize::MIN);
+            for _ in 0..200 {
+                let id = generator.generate();
+                assert_eq!(id.shard_hint(), Some(shard), "{id} lost its hint");
+                assert_eq!(Id::from_str(&id), Ok(id.clone()));
+            }
+        }
+
+        let unhinted = IdGenerator::new(12).entropy_bits();
+        let hinted = IdGenerator::new(12).with_shard_hint(3).entropy_bits();
+        // The hinted character is fixed, everything after it is as random as before.
+        assert!((unhinted - hinted - (23_f64).log2()).abs() < 0.01);
+    }
+
+    #[test]
+    fn presets_meet_their_entropy() {
+        for (preset, len) in [
+            (LengthPreset::Short, 9),
+            (LengthPreset::Standard, 16),
+            (LengthPreset::Long, 30),
+        ] {
+            let generator = IdGenerator::new(MIN_LENGTH).with_preset(preset);
+            assert_eq!(generator.length(), len);
+            assert!(generator.entropy_bits() >= preset.entropy_bits());
+        }
+    }
+
+    #[test]
+    fn format_is_respected() {
+        let generator = IdGenerator::new(12)
+            .with_format(FormatVersion::V2)
+            .with_max_run(NonZeroUsize::MIN);
+        for _ in 0..1_000 {
+            let id = generator.generate();
+            assert_eq!(
+                Id::from_str_versioned(&id, FormatVersion::V2),
+                Ok(id.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn min_pronounceability_is_respected() {
+        let generator = IdGenerator::new(10).with_min_pronounceability(0.8);
+        for _ in 0..200 {
+            let id = generator.generate();
+            assert!(pronounceability(&id) >= 0.8, "{id} is not pronounceable");
+            assert_eq!(Id::from_str(&id), Ok(id.clone()));
+        }
+    }
+
+    #[test]
+    fn confusable_characters_are_never_adjacent() {
+        let matrix = ConfusionMatrix::AUDIBLE.with_pair('c', 'e');
+        for generator in [
+            IdGenerator::new(5).with_confusion_matrix(matrix),
+            IdGenerator::new(25)
+                .with_confusion_matrix(matrix)
+                .with_max_run(NonZeroUsize::MIN)
+                .with_format(FormatVersion::V2),
+        ] {
+            for _ in 0..2_000 {
+                let id = generator.generate();
+                let chars = id.chars().collect::<Vec<_>>();
+                for pair in chars.windows(2) {
+                    if let [a, b] = pair {
+                        assert!(!matrix.confusable(*a, *b), "{id}");
+                    }
+                }
+            }
+        }
+
+        let plain = IdGenerator::new(12);
+        let restricted = plain.clone().with_confusion_matrix(matrix);
+        let unrestricted = plain.clone().with_confusion_matrix(ConfusionMatrix::EMPTY);
+        assert!(restricted.entropy_bits() < plain.entropy_bits());
+        assert!((unrestricted.entropy_bits() - plain.entropy_bits()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn min_entropy_picks_shortest_length() {
+        for bits in [0.0, 1.0, 32.0, 64.0, 100.0, 128.0] {
+            for max_run in [None, NonZeroUsize::new(1)] {
+                let mut generator = IdGenerator::new(4);
+                if let Some(max_run) = max_run {
+                    generator = generator.with_max_run(max_run);
+                }
+                let generator = generator.with_min_entropy(bits);
+                assert!(generator.entropy_bits() >= bits);
+
+                let shorter = IdGenerator {
+                    len: generator.len - 1,
+                    ..generator.clone()
+                };
+                assert!(generator.len == 4 || shorter.entropy_bits() < bits);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic = "bits of entropy needs IDs longer than"]
+    fn min_entropy_rejects_huge_targets() {
+        let _ = IdGenerator::new(4).with_min_entropy(1e300);
+    }
+
+    #[test]
+    #[should_panic = "bits of entropy needs IDs longer than"]
+    fn min_entropy_rejects_targets_just_past_the_parse_limit() {
+        // The estimate fits, but the skipped sequences push the real length past the limit.
+        #[allow(clippy::cast_precision_loss, reason = "small integers")]
+        let bits = (MAX_PARSE_LENGTH - 2) as f64 * (GEN_ALPHABET.len() as f64).log2();
+        let _ = IdGenerator::new(4).with_min_entropy(bits);
+    }
+
+    #[test]
+    fn entropy_of_unrestricted_generator() {
+        // Only the final body character is restricted (no 'r' or 'v') in a two character ID, the
+        // remaining ambiguous sequences need at least two body characters.
+        let entropy = IdGenerator::new(2).entropy_bits();
+        assert!((entropy - 21_f64.log2()).abs() < 1e-9);
+
+        let entropy = IdGenerator::new(25).entropy_bits();
+        assert!(entropy < 24.0 * 23_f64.log2());
+        assert!(entropy > 24.0 * 22_f64.log2());
+    }
+
+    #[test]
+    fn entropy_decreases_with_tighter_runs() {
+        let entropy = |max_run| {
+            IdGenerator::new(25)
+                .with_max_run(NonZeroUsize::new(max_run).unwrap())
+                .entropy_bits()
+        };
+        assert!(entropy(1) < entropy(2));
+        assert!(entropy(2) < entropy(3));
+        assert!(entropy(3) < IdGenerator::new(25).entropy_bits());
+        assert!(IdGenerator::new(1).entropy_bits().abs() < f64::EPSILON);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn stream_yields_valid_ids() {
+        use futures_util::{Stream, StreamExt};
+
+        fn assert_send<T: Send>(_: &T) {}
+
+        let stream = IdGenerator::new(10)
+            .with_max_run(NonZeroUsize::MIN)
+            .stream();
+        assert_send(&stream);
+        assert_eq!(stream.size_hint(), (usize::MAX, None));
+
+        let ids = stream.take(100).collect::<Vec<_>>().await;
+        assert_eq!(ids.len(), 100);
+        for id in &ids {
+            assert_eq!(id.len(), 10);
+            assert_eq!(Id::from_str(id), Ok(id.clone()));
+        }
+    }
+
+    #[test]
+    fn shared_generator_is_usable_from_many_threads() {
+        static SHARED: IdGenerator = IdGenerator::new(12);
+
+        let handles = (0..8)
+            .map(|_| {
+                std::thread::spawn(|| (0..1_000).map(|_| SHARED.generate()).collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        let mut ids = std::collections::HashSet::new();
+        for handle in handles {
+            for id in handle.join().expect("no panics") {
+                assert_eq!(Id::from_str(&id), Ok(id.clone()));
+                ids.insert(id);
+            }
+        }
+        assert_eq!(ids.len(), 8_000);
+    }
+}
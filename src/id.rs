@@ -1,15 +1,45 @@
 // src/id.rs
 //! Core ID type and associated operations
 
-use std::{fmt, str::FromStr};
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    fmt,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use rand::Rng;
+use rand_core::RngCore;
 
 use crate::{
-    alphabet::{self, CHECK_ALPHABET},
+    alphabet::{self, CHECK_ALPHABET, CaseFolding},
+    checksum::{ChecksumInput, FormatVersion},
     error::IdError,
+    generator::IdGenerator,
+    telemetry,
 };
 
+/// FNV-1a offset basis for 64-bit hashes
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a prime for 64-bit hashes
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hash of `bytes`, see [`Id::fingerprint`]
+pub(crate) const fn fnv1a(mut bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    while let [byte, rest @ ..] = bytes {
+        #[allow(clippy::cast_lossless, reason = "`u64::from` is not const.")]
+        {
+            hash ^= *byte as u64;
+        }
+        hash = hash.wrapping_mul(FNV_PRIME);
+        bytes = rest;
+    }
+    hash
+}
+
 /// A user-friendly identifier with check bit validation
 ///
 /// # Example
@@ -21,15 +51,356 @@ use crate::{
 /// assert_eq!(id.as_str(), "abc-");
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Id(pub(crate) String);
+pub struct Id(pub(crate) Repr);
+
+/// Storage behind an [`Id`], always holding a valid ID in canonical form
+///
+/// Comparisons, hashing and formatting only consider the string, so equal IDs are equal whatever
+/// they are stored in.
+#[derive(Clone)]
+pub(crate) enum Repr {
+    /// A static or owned string
+    Cow(Cow<'static, str>),
+    /// A slice of a shared buffer, e.g. a network frame, see [`Id::parse_bytes`]
+    #[cfg(feature = "bytes")]
+    Bytes(bytes::Bytes),
+}
+
+impl Repr {
+    /// Take the string out, copying it if it is borrowed or shared
+    pub(crate) fn into_owned(self) -> String {
+        match self {
+            Self::Cow(s) => s.into_owned(),
+            #[cfg(feature = "bytes")]
+            Self::Bytes(_) => self.to_string(),
+        }
+    }
+}
+
+impl std::ops::Deref for Repr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Cow(s) => s,
+            // Only valid IDs, which are ASCII, are ever stored.
+            #[cfg(feature = "bytes")]
+            Self::Bytes(b) => std::str::from_utf8(b).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<String> for Repr {
+    fn from(s: String) -> Self {
+        Self::Cow(Cow::Owned(s))
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl fmt::Debug for Repr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl fmt::Display for Repr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl PartialEq for Repr {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Repr {}
+
+impl std::hash::Hash for Repr {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+/// Shortest ID, including the check character, that will be accepted when parsing
+pub const MIN_LENGTH: usize = 4;
+
+/// Longest input, in bytes, that parsing accepts by default, see [`Id::from_str_with_limit`]
+///
+/// Longer input is rejected before it is normalized, so oversized "IDs" from untrusted sources
+/// can't force large allocations.
+pub const MAX_PARSE_LENGTH: usize = 4096;
+
+/// Starts text laid out left to right, isolated from the direction of the text around it
+pub const LEFT_TO_RIGHT_ISOLATE: char = '\u{2066}';
+
+/// Ends text started by [`LEFT_TO_RIGHT_ISOLATE`]
+pub const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// An ID wrapped in Unicode directional isolates when displayed, see [`Id::bidi_isolated`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BidiIsolated<'a>(&'a str);
+
+impl fmt::Display for BidiIsolated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{LEFT_TO_RIGHT_ISOLATE}{}{POP_DIRECTIONAL_ISOLATE}",
+            self.0
+        )
+    }
+}
+
+/// How forgiving parsing is about input that was mangled on its way to the parser
+///
+/// [`Id::from_str`] is [`ParseProfile::STRICT`], see [`Id::from_str_with_profile`].
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{
+///     Id,
+///     alphabet::CaseFolding,
+///     id::ParseProfile,
+/// };
+///
+/// const PROFILE: ParseProfile =
+///     ParseProfile::LENIENT.with_folding(CaseFolding::with_foldings(&[('\u{12f}', 'i')]));
+/// let id = Id::from_str_with_profile("wcfytxww\u{200b}4op\u{12f}n4jmjjes4ccfd", &PROFILE);
+/// assert_eq!(id.unwrap().as_str(), "wcfytxww4opin4jmjjes4ccfd");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ParseProfile {
+    /// Whether [`INVISIBLE_CHARS`](alphabet::INVISIBLE_CHARS) are removed before normalizing
+    strip_invisible: bool,
+    /// Case foldings applied while normalizing
+    folding: CaseFolding,
+    /// Whether [`fold_compatibility`](alphabet::fold_compatibility) is applied before normalizing
+    #[cfg(feature = "unicode-normalization")]
+    compatibility: bool,
+}
+
+impl ParseProfile {
+    /// Only the usual normalization, invisible characters are rejected
+    pub const STRICT: Self = Self {
+        strip_invisible: false,
+        folding: CaseFolding::DEFAULT,
+        #[cfg(feature = "unicode-normalization")]
+        compatibility: false,
+    };
+
+    /// Also remove invisible characters, which arrive constantly in IDs copied from rich-text
+    /// email and web pages, see [`strip_invisible`](alphabet::strip_invisible)
+    pub const LENIENT: Self = Self {
+        strip_invisible: true,
+        folding: CaseFolding::DEFAULT,
+        #[cfg(feature = "unicode-normalization")]
+        compatibility: false,
+    };
+
+    /// Apply additional locale-specific case foldings, see [`CaseFolding`]
+    #[must_use]
+    pub const fn with_folding(mut self, folding: CaseFolding) -> Self {
+        self.folding = folding;
+        self
+    }
+
+    /// Fold styled text such as `𝗮`, `ａ` or `ⓐ` to plain ASCII before validating, see
+    /// [`fold_compatibility`](alphabet::fold_compatibility)
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, id::ParseProfile};
+    ///
+    /// const PROFILE: ParseProfile = ParseProfile::LENIENT.with_compatibility_folding();
+    /// // "wcfy" in mathematical sans-serif bold, fullwidth and circled letters
+    /// let styled = "\u{1d604}\u{ff43}\u{24d5}\u{1d606}txww4opin4jmjjes4ccfd";
+    /// let id = Id::from_str_with_profile(styled, &PROFILE).unwrap();
+    /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    #[cfg(feature = "unicode-normalization")]
+    #[must_use]
+    pub const fn with_compatibility_folding(mut self) -> Self {
+        self.compatibility = true;
+        self
+    }
+}
+
+/// Whether a partially typed string could still become a valid [`Id`]
+///
+/// See [`Id::validate_prefix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefixStatus {
+    /// Every character is acceptable, but there are too few of them to form an ID yet
+    TooShort,
+    /// Every character is acceptable, but the check character doesn't match yet
+    Incomplete,
+    /// The input is already a valid ID, although typing more could still form a longer one
+    Valid,
+    /// No amount of further typing can make this a valid ID
+    Invalid(IdError),
+}
+
+/// How user input compares to an [`Id`]
+///
+/// See [`Id::matches`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchResult {
+    /// The input is exactly the canonical form of the ID
+    Exact,
+    /// The input matches once normalized, e.g. it differs in case or uses confusable characters
+    Normalized,
+    /// The input is a valid ID, but a different one
+    Mismatch,
+    /// The input is not a valid ID, e.g. [`IdError::InvalidCheckBit`] for a failed checksum
+    Invalid(IdError),
+}
+
+impl MatchResult {
+    /// Whether the input refers to the ID, either exactly or after normalization
+    #[must_use]
+    pub const fn is_match(&self) -> bool {
+        matches!(self, Self::Exact | Self::Normalized)
+    }
+}
 
 impl Id {
+    /// Regular expression matching IDs in canonical form, for frontend validation and database
+    /// `CHECK` constraints
+    ///
+    /// The pattern only checks the alphabet and minimum length. It does not verify the check
+    /// character, reject ambiguous sequences such as `rn`, or accept input that would be valid
+    /// after normalization, so a match is necessary but not sufficient for a valid ID. It uses no
+    /// lookaround or escapes, so it works unchanged in JavaScript, Postgres and most other
+    /// regex dialects.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let check = format!("CHECK (order_id ~ '{}')", Id::PATTERN);
+    /// assert_eq!(check, "CHECK (order_id ~ '^[abcdefhijkmnoprstwxy34v]{4,}$')");
+    /// ```
+    pub const PATTERN: &'static str = "^[abcdefhijkmnoprstwxy34v]{4,}$";
+
     /// Get string slice representation
     #[must_use]
     pub fn as_str(&self) -> &str {
         &self.0
     }
 
+    /// The ID without its check character
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+    /// assert_eq!(id.body(), "wcfytxww4opin4jmjjes4ccf");
+    /// assert_eq!(Id::append_check_char(id.body()), Ok(id));
+    /// ```
+    #[must_use]
+    pub fn body(&self) -> &str {
+        self.0
+            .get(..self.0.len().saturating_sub(1))
+            .unwrap_or_default()
+    }
+
+    /// The check character ending the ID
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+    /// assert_eq!(id.check_char(), 'd');
+    /// ```
+    #[must_use]
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - IDs are never empty"
+    )]
+    pub fn check_char(&self) -> char {
+        self.0.chars().next_back().expect("IDs are never empty")
+    }
+
+    /// Display this ID for embedding in right-to-left text, such as Arabic or Hebrew UI strings
+    ///
+    /// The ID is wrapped in [`LEFT_TO_RIGHT_ISOLATE`] and [`POP_DIRECTIONAL_ISOLATE`], so it is
+    /// always laid out left to right and the surrounding text can't reorder its digits, symbols or
+    /// neighbouring punctuation. The isolates are invisible but are still characters, so parse text
+    /// copied from such a display with [`ParseProfile::LENIENT`], which removes them.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{
+    ///     Id,
+    ///     id::{LEFT_TO_RIGHT_ISOLATE, POP_DIRECTIONAL_ISOLATE},
+    /// };
+    ///
+    /// let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+    /// // "Order number" in Hebrew
+    /// let message = format!("\u{5de}\u{5e1}\u{5e4}\u{5e8} \u{5d4}\u{5d6}\u{5de}\u{5e0}\u{5d4}: {}", id.bidi_isolated());
+    /// assert!(message.ends_with(&format!("{LEFT_TO_RIGHT_ISOLATE}{id}{POP_DIRECTIONAL_ISOLATE}")));
+    /// ```
+    #[must_use]
+    pub fn bidi_isolated(&self) -> BidiIsolated<'_> {
+        BidiIsolated(self.as_str())
+    }
+
+    /// Get the ID as ASCII bytes, one per character
+    ///
+    /// Every character of every alphabet an ID can be made of is ASCII, which is checked at compile
+    /// time, so byte offsets are always character boundaries and byte-wise operations need no
+    /// UTF-8 handling.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+    /// assert_eq!(id.as_ascii_bytes()[0], b'w');
+    /// assert_eq!(id.as_ascii_bytes().len(), id.chars().count());
+    /// ```
+    #[must_use]
+    pub fn as_ascii_bytes(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+
+    /// Create an ID from a static string without allocating, validated in const contexts
+    ///
+    /// The string must already be in canonical form: lowercase, with every character in the
+    /// alphabet and no ambiguous sequences. Used in a `const` or `static`, an invalid ID fails to
+    /// compile, so well-known sentinel IDs need no parsing at startup. See also [`id!`](crate::id!).
+    ///
+    /// # Panics
+    ///
+    /// If `s` is not a valid ID in canonical form.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// static SYSTEM_USER: Id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+    /// assert_eq!(SYSTEM_USER.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    #[must_use]
+    pub const fn from_static(s: &'static str) -> Self {
+        match alphabet::validate_canonical(s) {
+            Ok(()) => Self(Repr::Cow(Cow::Borrowed(s))),
+            Err(IdError::TooShort) => panic!("ID is too short"),
+            Err(IdError::InvalidCharacter) => {
+                panic!("ID contains a character outside the alphabet, or is not lowercase")
+            }
+            Err(IdError::InvalidSequence) => {
+                panic!("ID contains an ambiguous sequence such as `rn`")
+            }
+            Err(IdError::InvalidCheckBit) => panic!("ID has the wrong check character"),
+            Err(IdError::TooLong) => panic!("ID is too long"),
+        }
+    }
+
     /// Calculate maximum valid ID length for current configuration
     #[allow(
         clippy::arithmetic_side_effects,
@@ -47,48 +418,839 @@ impl Id {
         (max_value + 1) as usize
     }
 
-    /// Generate a new ID with a given length
+    /// Generate a new ID with a given length
+    ///
+    /// Any [`RngCore`] is accepted, see [`crate::compat`] for RNGs from older versions of `rand`.
+    ///
+    /// See: [`Id::new`] if you want to use the default RNG, or [`IdGenerator`] to configure the
+    /// generation policies.
+    ///
+    /// IDs longer than [`MAX_PARSE_LENGTH`] can be generated, but only parse back with
+    /// [`Id::from_str_with_limit`].
+    #[must_use]
+    pub fn new_with_rng<R: RngCore + ?Sized>(len: usize, rng: &mut R) -> Self {
+        IdGenerator::new(len).generate_with_rng(rng)
+    }
+
+    /// Generate a new ID with a given length into `buf`, replacing its contents
+    ///
+    /// For callers generating many IDs that reuse a buffer instead of allocating for each one.
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    /// As with [`Id::new`], IDs longer than [`MAX_PARSE_LENGTH`] only parse back with
+    /// [`Id::from_str_with_limit`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let mut buf = String::new();
+    /// Id::new_into(12, &mut buf);
+    /// assert_eq!(buf.len(), 12);
+    /// assert!(buf.parse::<Id>().is_ok());
+    /// ```
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    pub fn new_into(len: usize, buf: &mut String) {
+        IdGenerator::new(len).generate_into(buf);
+    }
+
+    /// Generate a new ID with a given length
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    ///
+    /// IDs longer than [`MAX_PARSE_LENGTH`] can be generated, but only parse back with
+    /// [`Id::from_str_with_limit`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        let mut rng = crate::rng::default_rng();
+        Self::new_with_rng(len, &mut rng)
+    }
+
+    /// Generate a new ID with at least `bits` of entropy
+    ///
+    /// The length is chosen for you, accounting for the sequences the generator skips, and the
+    /// actual entropy of the returned ID is returned alongside it. See
+    /// [`IdGenerator::with_min_entropy`] to combine this with other generation policies.
+    ///
+    /// # Panics
+    ///
+    /// If `bits` is not finite, or reaching it would need an ID longer than [`MAX_PARSE_LENGTH`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let (id, entropy) = Id::new_with_entropy(64.0);
+    /// assert!(entropy >= 64.0);
+    /// assert_eq!(id.len(), 16);
+    /// ```
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn new_with_entropy(bits: f64) -> (Self, f64) {
+        let generator = IdGenerator::new(MIN_LENGTH).with_min_entropy(bits);
+        (generator.generate(), generator.entropy_bits())
+    }
+
+    /// Generate a new ID with at least 32 bits of entropy, see [`LengthPreset::Short`]
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn short() -> Self {
+        IdGenerator::new(MIN_LENGTH)
+            .with_preset(crate::generator::LengthPreset::Short)
+            .generate()
+    }
+
+    /// Generate a new ID with at least 64 bits of entropy, see [`LengthPreset::Standard`]
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// assert_eq!(Id::short().len(), 9);
+    /// assert_eq!(Id::standard().len(), 16);
+    /// assert_eq!(Id::long().len(), 30);
+    /// ```
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn standard() -> Self {
+        IdGenerator::new(MIN_LENGTH)
+            .with_preset(crate::generator::LengthPreset::Standard)
+            .generate()
+    }
+
+    /// Generate a new ID with at least 128 bits of entropy, see [`LengthPreset::Long`]
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn long() -> Self {
+        IdGenerator::new(MIN_LENGTH)
+            .with_preset(crate::generator::LengthPreset::Long)
+            .generate()
+    }
+
+    /// Shorten this ID to `new_len` characters, recomputing the check character
+    ///
+    /// Useful for displaying a short form of a long internal ID that can still be validated on its
+    /// own. If `new_len` is not shorter than this ID, the ID is returned unchanged.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if `new_len` is too short to form a valid ID
+    /// - [`IdError::TooLong`] if the ID would be longer than [`MAX_PARSE_LENGTH`]
+    /// - [`IdError::InvalidSequence`] if the new check character would form an ambiguous sequence
+    ///   with the end of the shortened body, e.g. `rn`
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+    /// let short = id.truncated(8).unwrap();
+    /// assert_eq!(short.as_str(), "wcfytxwc");
+    /// assert_eq!(short.as_str().parse::<Id>(), Ok(short));
+    /// ```
+    pub fn truncated(&self, new_len: usize) -> Result<Self, IdError> {
+        if new_len >= self.0.len() {
+            return Ok(self.clone());
+        }
+
+        let body = self
+            .0
+            .get(..new_len.saturating_sub(1))
+            .ok_or(IdError::InvalidCharacter)?;
+        Self::from_body(body)
+    }
+
+    /// Shortest prefix of this ID that no other ID in `ids` starts with, like short git hashes
+    ///
+    /// The prefix is at least `min_len` characters long, so abbreviations stay stable as more IDs
+    /// are added, and at most the whole ID, if another ID in `ids` starts with all of it. `ids`
+    /// may contain this ID itself.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let ids = ["wcfytxww4opin4jmjjes4ccf", "wcfa3k", "mpt4de"]
+    ///     .map(|body| Id::append_check_char(body).unwrap());
+    /// assert_eq!(ids[0].abbreviate(&ids, 1), "wcfy");
+    /// assert_eq!(ids[2].abbreviate(&ids, 1), "m");
+    /// assert_eq!(ids[2].abbreviate(&ids, 4), "mpt4");
+    /// ```
+    #[must_use]
+    pub fn abbreviate<'a>(&self, ids: impl IntoIterator<Item = &'a Self>, min_len: usize) -> &str {
+        let shared = ids
+            .into_iter()
+            .filter(|other| *other != self)
+            .map(|other| {
+                self.bytes()
+                    .zip(other.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            })
+            .max()
+            .unwrap_or_default();
+        let len = shared.saturating_add(1).max(min_len).min(self.len());
+        self.get(..len).unwrap_or(self.as_str())
+    }
+
+    /// Compare user input against this ID, normalizing it the same way as when parsing
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, id::MatchResult, error::IdError};
+    ///
+    /// let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+    /// assert_eq!(id.matches("wcfytxww4opin4jmjjes4ccfd"), MatchResult::Exact);
+    /// assert_eq!(id.matches("WCFYTXWVV4OP1N4JMJJES4CCFD"), MatchResult::Normalized);
+    /// assert_eq!(
+    ///     id.matches("wcfytxww4opin4jmjjes4ccfa"),
+    ///     MatchResult::Invalid(IdError::InvalidCheckBit)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn matches(&self, input: &str) -> MatchResult {
+        if self.eq_canonical(input) {
+            return MatchResult::Exact;
+        }
+
+        match Self::parse_versioned(input, FormatVersion::V1) {
+            Ok(parsed) if parsed == *self => MatchResult::Normalized,
+            Ok(_) => MatchResult::Mismatch,
+            Err(e) => MatchResult::Invalid(e),
+        }
+    }
+
+    /// Whether `s` is exactly the canonical form of this ID, compared byte for byte
+    ///
+    /// Nothing is normalized or allocated, so this is suitable for hot lookup paths where input
+    /// is known to be canonical, e.g. because it was checked with [`Id::is_canonical`] when it was
+    /// stored. Uppercase or confusable spellings of this ID compare unequal, use [`Id::matches`]
+    /// for input typed by users.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+    /// assert!(id.eq_canonical("wcfytxww4opin4jmjjes4ccfd"));
+    /// assert!(!id.eq_canonical("WCFYTXWW4OPIN4JMJJES4CCFD"));
+    /// assert!(id.matches("WCFYTXWW4OPIN4JMJJES4CCFD").is_match());
+    /// ```
+    #[must_use]
+    pub fn eq_canonical(&self, s: &str) -> bool {
+        self.as_bytes() == s.as_bytes()
+    }
+
+    /// Shard index embedded by [`IdGenerator::with_shard_hint`], read from the first character
+    ///
+    /// Every ID has a first character, so this returns a value whether or not a hint was embedded
+    /// when it was generated. It is up to the caller to only rely on it for IDs from a generator
+    /// configured with one.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::generator::IdGenerator;
+    ///
+    /// let id = IdGenerator::new(12).with_shard_hint(7).generate();
+    /// assert_eq!(id.shard_hint(), Some(7));
+    /// ```
+    #[must_use]
+    pub fn shard_hint(&self) -> Option<u8> {
+        let first = self.0.chars().next()?;
+        let idx = alphabet::GEN_ALPHABET.iter().position(|c| *c == first)?;
+        u8::try_from(idx).ok()
+    }
+
+    /// Stable 64-bit hash of the ID, for sharding and bloom filters shared between processes
+    ///
+    /// Unlike [`std::hash::Hash`], whose output depends on the hasher and is randomized per process
+    /// by `HashMap`, this is the 64-bit FNV-1a hash of the canonical form. It will not change
+    /// between releases, so it can be stored or computed independently in other languages.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+    /// assert_eq!(id.fingerprint(), 0xfc16_5ffd_e2d7_423f);
+    /// ```
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        fnv1a(self.as_bytes())
+    }
+
+    /// Shard in `0..num_shards` for this ID, by jump consistent hashing of [`Id::fingerprint`]
+    ///
+    /// IDs are spread evenly, and going from `n` to `n + 1` shards only moves the IDs that land on
+    /// the new shard. The jump is computed with integer division as
+    /// `(b + 1) * 2^31 / ((key >> 33) + 1)`, so results are identical on every platform.
+    ///
+    /// # Example
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::new(12);
+    /// let shards = NonZeroU32::new(16).unwrap();
+    /// assert!(id.shard(shards) < 16);
+    /// assert_eq!(id.shard(shards), id.shard(shards));
+    /// assert_eq!(id.shard(NonZeroU32::MIN), 0);
+    /// ```
+    #[must_use]
+    pub fn shard(&self, num_shards: NonZeroU32) -> u32 {
+        let mut key = self.fingerprint();
+        let mut bucket = 0_u64;
+        let mut next = 0_u64;
+        while next < u64::from(num_shards.get()) {
+            bucket = next;
+            key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+            next =
+                bucket.saturating_add(1).saturating_mul(1 << 31) / ((key >> 33).saturating_add(1));
+        }
+        u32::try_from(bucket).unwrap_or_default()
+    }
+
+    /// Path to store a file named after this ID in `depth` levels of nested directories
+    ///
+    /// Each directory is named after the next two characters of the ID, so files are spread
+    /// evenly and no directory grows too large. Depth is capped so the directories only use the
+    /// body of the ID, e.g. at one for a four character ID. [`Id::from_shard_path`] is the inverse.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+    /// assert_eq!(id.shard_path(2), Path::new("wc/fy/wcfytxww4opin4jmjjes4ccfd"));
+    /// assert_eq!(id.shard_path(0), Path::new("wcfytxww4opin4jmjjes4ccfd"));
+    /// ```
+    #[must_use]
+    pub fn shard_path(&self, depth: usize) -> PathBuf {
+        let mut path = PathBuf::new();
+        for dir in self.body().as_bytes().chunks_exact(2).take(depth) {
+            path.push(str::from_utf8(dir).unwrap_or_default());
+        }
+        path.push(self.as_str());
+        path
+    }
+
+    /// Parse the ID from a path built by [`Id::shard_path`] with the same `depth`
+    ///
+    /// The file name may have an extension, and anything above the shard directories is ignored.
+    /// Returns `None` if the file name isn't an ID or the directories don't match it, so a file
+    /// moved into the wrong directory is noticed rather than silently becoming unreachable.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+    /// let path = Path::new("/srv/blobs").join(id.shard_path(2));
+    /// assert_eq!(Id::from_shard_path(&path, 2), Some(id));
+    /// assert_eq!(Id::from_shard_path(Path::new("wc/xx/wcfytxww4opin4jmjjes4ccfd"), 2), None);
+    /// ```
+    #[must_use]
+    pub fn from_shard_path(path: &Path, depth: usize) -> Option<Self> {
+        let id = Self::from_file_stem(path).ok()?;
+        let expected = id.shard_path(depth);
+        let dirs = expected.parent()?.components().rev();
+        let mut actual = path.parent()?.components().rev();
+        for dir in dirs {
+            if actual.next()? != dir {
+                return None;
+            }
+        }
+        Some(id)
+    }
+
+    /// Parse an ID whose check character was calculated for a given [`FormatVersion`]
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooLong`] if the input is longer than [`MAX_PARSE_LENGTH`]
+    /// - [`IdError::TooShort`] if the normalized ID is shorter than four characters
+    /// - [`IdError::InvalidCharacter`] if a character is not in the alphabet
+    /// - [`IdError::InvalidCheckBit`] if the check character doesn't match
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, checksum::FormatVersion, generator::IdGenerator};
+    ///
+    /// let id = IdGenerator::new(12).with_format(FormatVersion::V2).generate();
+    /// assert_eq!(Id::from_str_versioned(&id, FormatVersion::V2), Ok(id));
+    /// ```
+    pub fn from_str_versioned(s: &str, version: FormatVersion) -> Result<Self, IdError> {
+        Self::parse_versioned(s, version).inspect_err(telemetry::record_parse_failure)
+    }
+
+    /// Parse an ID, applying locale-specific case foldings on top of the built-in ones
+    ///
+    /// Shorthand for [`Id::from_str_with_profile`] with [`ParseProfile::STRICT`] and `folding`.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::from_str_versioned`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, alphabet::CaseFolding};
+    ///
+    /// const FOLDING: CaseFolding = CaseFolding::with_foldings(&[('\u{12f}', 'i')]);
+    /// let id = Id::from_str_with_folding("wcfytxww4op\u{12f}n4jmjjes4ccfd", &FOLDING);
+    /// assert_eq!(id.unwrap().as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    pub fn from_str_with_folding(s: &str, folding: &CaseFolding) -> Result<Self, IdError> {
+        Self::from_str_with_profile(s, &ParseProfile::STRICT.with_folding(*folding))
+    }
+
+    /// Parse an ID with a given [`ParseProfile`], e.g. [`ParseProfile::LENIENT`] for input pasted
+    /// from rich text
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::from_str_versioned`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, error::IdError, id::ParseProfile};
+    ///
+    /// let pasted = "\u{feff}wcfytxww4opin4jmj\u{ad}jes4ccfd\u{200b}";
+    /// assert_eq!(pasted.parse::<Id>(), Err(IdError::InvalidCharacter));
+    /// let id = Id::from_str_with_profile(pasted, &ParseProfile::LENIENT).unwrap();
+    /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    pub fn from_str_with_profile(s: &str, profile: &ParseProfile) -> Result<Self, IdError> {
+        if s.len() > MAX_PARSE_LENGTH {
+            telemetry::record_parse_failure(&IdError::TooLong);
+            return Err(IdError::TooLong);
+        }
+        let s = if profile.strip_invisible {
+            alphabet::strip_invisible(s)
+        } else {
+            Cow::Borrowed(s)
+        };
+        #[cfg(feature = "unicode-normalization")]
+        let s = if profile.compatibility && !s.is_ascii() {
+            Cow::Owned(alphabet::fold_compatibility(&s).into_owned())
+        } else {
+            s
+        };
+        Self::from_str(&alphabet::normalize_string_with(&s, &profile.folding))
+    }
+
+    /// Parse an ID from an OS string, such as a command line argument or file name
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooLong`] if the input is longer than [`MAX_PARSE_LENGTH`]
+    /// - [`IdError::InvalidCharacter`] if the input is not valid Unicode
+    /// - Otherwise, see [`Id::from_str_versioned`]
+    ///
+    /// # Example
+    /// ```
+    /// use std::ffi::OsStr;
+    ///
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_os_str(OsStr::new("wcfytxww4opin4jmjjes4ccfd")).unwrap();
+    /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    pub fn from_os_str(s: &OsStr) -> Result<Self, IdError> {
+        let parsed = if s.len() > MAX_PARSE_LENGTH {
+            Err(IdError::TooLong)
+        } else {
+            s.to_str()
+                .ok_or(IdError::InvalidCharacter)
+                .and_then(|s| Self::parse_versioned(s, FormatVersion::V1))
+        };
+        parsed.inspect_err(telemetry::record_parse_failure)
+    }
+
+    /// Parse an ID stored as the name of a file, ignoring its directory and extension
+    ///
+    /// Only the last extension is removed, so `wcfytxww4opin.tar.gz` does not parse.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if the path has no file name, e.g. `/` or `..`
+    /// - Otherwise, see [`Id::from_os_str`]
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::from_file_stem(Path::new("blobs/wcfytxww4opin4jmjjes4ccfd.json")).unwrap();
+    /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    pub fn from_file_stem(path: &Path) -> Result<Self, IdError> {
+        let Some(stem) = path.file_stem() else {
+            telemetry::record_parse_failure(&IdError::TooShort);
+            return Err(IdError::TooShort);
+        };
+        Self::from_os_str(stem)
+    }
+
+    /// Parse an ID, accepting input up to `max_len` bytes long instead of [`MAX_PARSE_LENGTH`]
+    ///
+    /// Lower the limit for input from untrusted sources, such as request paths, or raise it to parse
+    /// unusually long IDs.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooLong`] if the input is longer than `max_len` bytes
+    /// - Otherwise, see [`Id::from_str_versioned`]
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, error::IdError};
+    ///
+    /// let input = "wcfytxww4opin4jmjjes4ccfd";
+    /// assert!(Id::from_str_with_limit(input, 32).is_ok());
+    /// assert_eq!(Id::from_str_with_limit(input, 16), Err(IdError::TooLong));
+    /// ```
+    pub fn from_str_with_limit(s: &str, max_len: usize) -> Result<Self, IdError> {
+        Self::parse_limited(s, FormatVersion::V1, max_len)
+            .inspect_err(telemetry::record_parse_failure)
+    }
+
+    /// Parse an ID in a given format without recording failures
+    ///
+    /// For probing candidates or bulk validation inside the crate, where a failure isn't a client
+    /// sending a bad ID and shouldn't show up in [`telemetry`].
+    pub(crate) fn parse_versioned(s: &str, version: FormatVersion) -> Result<Self, IdError> {
+        Self::parse_limited(s, version, MAX_PARSE_LENGTH)
+    }
+
+    /// Parse an ID of at most `max_len` bytes in a given format without recording failures
+    fn parse_limited(s: &str, version: FormatVersion, max_len: usize) -> Result<Self, IdError> {
+        if s.len() > max_len {
+            return Err(IdError::TooLong);
+        }
+        if version.checksum_input() == ChecksumInput::Raw {
+            return Self::parse_raw_checksum(s, version);
+        }
+        let normalized = alphabet::normalize_string(s);
+
+        if normalized.len() < MIN_LENGTH {
+            return Err(IdError::TooShort);
+        }
+
+        let (body, check_char) = normalized
+            .split_at_checked(normalized.len().saturating_sub(1))
+            .ok_or(IdError::InvalidCharacter)?;
+        let algorithm = version.algorithm();
+        if !check_char
+            .chars()
+            .all(|c| algorithm.check_alphabet().contains(&c))
+        {
+            return Err(IdError::InvalidCharacter);
+        }
+        let expected_check = algorithm.check_char(body)?;
+
+        if check_char != expected_check.to_string() {
+            return Err(IdError::InvalidCheckBit);
+        }
+
+        for c in body.chars() {
+            alphabet::validate_gen_char(c)?;
+        }
+
+        Ok(Self(normalized.into()))
+    }
+
+    /// Parse an ID whose check character was calculated over the body as typed
+    ///
+    /// The typed check character is validated against the raw body, the returned ID is the
+    /// normalized body followed by its check character.
+    fn parse_raw_checksum(s: &str, version: FormatVersion) -> Result<Self, IdError> {
+        let mut chars = s.chars();
+        let typed_check = chars.next_back().ok_or(IdError::TooShort)?;
+        let raw_body = chars.as_str().to_lowercase();
+
+        let body = alphabet::normalize_string(&raw_body);
+        if body.len() < MIN_LENGTH.saturating_sub(1) {
+            return Err(IdError::TooShort);
+        }
+        for c in body.chars() {
+            alphabet::validate_gen_char(c)?;
+        }
+
+        let algorithm = version.algorithm();
+        let typed_check = alphabet::normalize_char(typed_check.to_ascii_lowercase());
+        if !algorithm.check_alphabet().contains(&typed_check) {
+            return Err(IdError::InvalidCharacter);
+        }
+        if algorithm.check_char(&raw_body)? != typed_check {
+            return Err(IdError::InvalidCheckBit);
+        }
+
+        let check_char = algorithm.check_char(&body)?;
+        let candidate = format!("{body}{check_char}");
+        if alphabet::normalize_string(&candidate) != candidate {
+            return Err(IdError::InvalidSequence);
+        }
+        Ok(Self(candidate.into()))
+    }
+
+    /// Parse an ID, reusing the input when it is already in canonical form
+    ///
+    /// A lowercase, valid ID is stored as is, whether it is a `&'static str` or a `String` moved in,
+    /// so only input that needs normalizing is copied. [`Id::try_from`] with a `String` and
+    /// deserializing both go through this method.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::from_str_versioned`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let input = String::from("wcfytxww4opin4jmjjes4ccfd");
+    /// let ptr = input.as_ptr();
+    /// let id = Id::parse_cow(input.into()).unwrap();
+    /// assert_eq!(id.as_ptr(), ptr);
+    ///
+    /// let id = Id::parse_cow("WCFYTXWW4OPIN4JMJJES4CCFD".into()).unwrap();
+    /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    pub fn parse_cow(s: Cow<'static, str>) -> Result<Self, IdError> {
+        if s.len() <= MAX_PARSE_LENGTH && Self::is_canonical(&s) {
+            return Ok(Self(Repr::Cow(s)));
+        }
+        Self::from_str(&s)
+    }
+
+    /// Whether `s` is a valid ID already in canonical form, i.e. parsing it would not change it
+    ///
+    /// Storage layers can use this to make sure only canonical forms are persisted.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// assert!(Id::is_canonical("wcfytxww4opin4jmjjes4ccfd"));
+    /// assert!(!Id::is_canonical("WCFYTXWW4OPIN4JMJJES4CCFD"));
+    /// assert!(!Id::is_canonical("wcfytxww4opin4jmjjes4ccfa"));
+    /// ```
+    #[must_use]
+    pub const fn is_canonical(s: &str) -> bool {
+        alphabet::validate_canonical(s).is_ok()
+    }
+
+    /// Whether this ID is unchanged by [`normalize_string`](alphabet::normalize_string)
+    ///
+    /// Parsed and generated IDs always are, since generation never emits a character or sequence
+    /// that normalization would rewrite. This is for checking that invariant, e.g. from fuzzers,
+    /// see `testing::check_normalization_fixed_point` with the `test-util` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// assert!(Id::new(25).is_normalized());
+    /// ```
+    #[must_use]
+    pub fn is_normalized(&self) -> bool {
+        alphabet::normalize_string(self) == self.as_str()
+    }
+
+    /// Parse an ID in any of several formats, returning the first one that matches
+    ///
+    /// Intended for services part way through a migration. One in 23 IDs has a valid check
+    /// character in both formats, so list the format most IDs are expected to be in first.
+    ///
+    /// ## Errors
+    ///
+    /// - The error from the last format tried if none match
+    /// - [`IdError::InvalidCheckBit`] if `versions` is empty
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, checksum::FormatVersion, generator::IdGenerator};
+    ///
+    /// let accepted = [FormatVersion::V2, FormatVersion::V1];
+    /// let id = IdGenerator::new(12).with_format(FormatVersion::V2).generate();
+    /// assert_eq!(Id::from_str_any(&accepted, &id), Ok((id, FormatVersion::V2)));
+    /// ```
+    pub fn from_str_any(
+        versions: &[FormatVersion],
+        s: &str,
+    ) -> Result<(Self, FormatVersion), IdError> {
+        let mut error = IdError::InvalidCheckBit;
+        for version in versions {
+            match Self::parse_versioned(s, *version) {
+                Ok(id) => return Ok((id, *version)),
+                Err(e) => error = e,
+            }
+        }
+        telemetry::record_parse_failure(&error);
+        Err(error)
+    }
+
+    /// Check whether a partially typed string could still become a valid ID
     ///
-    /// See: [`Id::new`] if you want to use the default RNG.
+    /// Intended for live feedback in forms: the input is normalized the same way as when parsing,
+    /// so only characters that can never appear in an ID are rejected.
     ///
-    #[allow(
-        clippy::missing_panics_doc,
-        reason = "Internal invariant - won't generate a string that would panic."
-    )]
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, id::PrefixStatus, error::IdError};
+    ///
+    /// assert_eq!(Id::validate_prefix("wc"), PrefixStatus::TooShort);
+    /// assert_eq!(Id::validate_prefix("wcfyt"), PrefixStatus::Incomplete);
+    /// assert_eq!(Id::validate_prefix("WCFYTXWW4OPIN4JMJJES4CCFD"), PrefixStatus::Valid);
+    /// assert_eq!(
+    ///     Id::validate_prefix("wcf#"),
+    ///     PrefixStatus::Invalid(IdError::InvalidCharacter)
+    /// );
+    /// ```
     #[must_use]
-    pub fn new_with_rng<R: Rng>(len: usize, rng: &mut R) -> Self {
-        let mut body = String::with_capacity(len.saturating_sub(1));
-        let mut last_char = None;
-
-        while body.len() < len.saturating_sub(1) {
-            let idx = rng.random_range(0..alphabet::GEN_ALPHABET.len());
-            #[allow(clippy::indexing_slicing, reason = "index is generated within bounds")]
-            let c = alphabet::GEN_ALPHABET[idx];
-            // Avoid ambiguous sequences
-            match (last_char, c) {
-                (Some('r'), 'n') | (Some('v'), 'v') => {}
-                // Don't end with 'r' or 'v', because the check-bit could create an ambiguous sequence
-                (_, 'r' | 'v') if body.len() == len.saturating_sub(2) => {}
-                _ => {
-                    body.push(c);
-                    last_char = Some(c);
-                }
+    pub fn validate_prefix(s: &str) -> PrefixStatus {
+        let normalized = alphabet::normalize_string(s);
+
+        if let Some(err) = normalized
+            .chars()
+            .find_map(|c| alphabet::validate_char(c).err())
+        {
+            return PrefixStatus::Invalid(err);
+        }
+
+        if normalized.len() < MIN_LENGTH {
+            return PrefixStatus::TooShort;
+        }
+
+        match Self::parse_versioned(&normalized, FormatVersion::V1) {
+            Ok(_) => PrefixStatus::Valid,
+            Err(_) => PrefixStatus::Incomplete,
+        }
+    }
+
+    /// The check character that should follow a typed body
+    ///
+    /// Lets UI widgets display or auto-append the check character as the user finishes entering an
+    /// ID. The body is normalized the same way as when parsing.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if the body contains characters that can never appear in an ID
+    /// - [`IdError::InvalidSequence`] if appending the check character would form an ambiguous
+    ///   sequence, e.g. `rn`, in which case no ID can be formed from this body
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// assert_eq!(Id::expected_final_char("WCFYTXWW4OPIN4JMJJES4CCF"), Ok('d'));
+    /// ```
+    pub fn expected_final_char(body: &str) -> Result<char, IdError> {
+        let normalized = alphabet::normalize_string(body);
+        for c in normalized.chars() {
+            alphabet::validate_char(c)?;
+        }
+
+        let check_char = alphabet::calculate_check_char(&normalized)?;
+        match normalized.chars().next_back() {
+            Some(last) if alphabet::ambiguous_replacement(last, check_char).is_some() => {
+                Err(IdError::InvalidSequence)
             }
+            _ => Ok(check_char),
         }
+    }
 
-        let check_char = alphabet::calculate_check_char(&body)
-            .expect("Generated body should be valid for check calculation");
+    /// Build an ID from a chosen body, such as a vanity ID, by appending its check character
+    ///
+    /// The body is lowercased but otherwise kept as given, so it must already be made of alphabet
+    /// characters without ambiguous sequences.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if the body contains characters outside the alphabet
+    /// - [`IdError::TooShort`] if the ID would be shorter than four characters
+    /// - [`IdError::TooLong`] if the ID would be longer than [`MAX_PARSE_LENGTH`]
+    /// - [`IdError::InvalidSequence`] if the body contains an ambiguous sequence such as `rn`, or
+    ///   would form one with its check character
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, error::IdError};
+    ///
+    /// let id = Id::append_check_char("AcmeCorp").unwrap();
+    /// assert!(id.starts_with("acmecorp"));
+    /// assert_eq!(id.as_str().parse::<Id>(), Ok(id));
+    ///
+    /// assert_eq!(Id::append_check_char("barnyard"), Err(IdError::InvalidSequence));
+    /// ```
+    pub fn append_check_char(body: &str) -> Result<Self, IdError> {
+        let body = body.to_lowercase();
+        for c in body.chars() {
+            alphabet::validate_char(c)?;
+        }
+        if body.len() < MIN_LENGTH.saturating_sub(1) {
+            return Err(IdError::TooShort);
+        }
+        Self::from_body(&body)
+    }
 
-        Id(format!("{}{}", body, check_char))
+    /// Replace a wrong check character, e.g. for IDs written before validation was enforced
+    ///
+    /// The body is normalized and validated as when parsing, and the final character is replaced by
+    /// the right check character, whatever it was. Valid IDs are returned unchanged. If check
+    /// characters were left off rather than corrupted, use [`Id::append_check_char`] instead.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if the normalized input is shorter than four characters
+    /// - [`IdError::TooLong`] if the ID would be longer than [`MAX_PARSE_LENGTH`]
+    /// - [`IdError::InvalidCharacter`] if the body contains characters outside the alphabet
+    /// - [`IdError::InvalidSequence`] if the right check character would form an ambiguous sequence
+    ///   with the body, in which case no ID can be formed from it
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::Id;
+    ///
+    /// let id = Id::repair("wcfytxww4opin4jmjjes4ccfa").unwrap();
+    /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    /// ```
+    pub fn repair(s: &str) -> Result<Self, IdError> {
+        let mut body = alphabet::normalize_string(s);
+        if body.len() < MIN_LENGTH {
+            return Err(IdError::TooShort);
+        }
+        body.pop();
+        for c in body.chars() {
+            alphabet::validate_char(c)?;
+        }
+        Self::from_body(&body)
     }
 
-    /// Generate a new ID with a given length
+    /// Append the check character to a body of generation alphabet characters
     ///
-    /// This method uses the default RNG from the `rand` crate.
-    #[must_use]
-    pub fn new(len: usize) -> Self {
-        let mut rng = rand::rng();
-        Self::new_with_rng(len, &mut rng)
+    /// The result is only returned if it parses back to itself, so callers can't produce an ID
+    /// that changes under normalization.
+    pub(crate) fn from_body(body: &str) -> Result<Self, IdError> {
+        let check_char = alphabet::calculate_check_char(body)?;
+        let candidate = format!("{}{}", body, check_char);
+
+        if alphabet::normalize_string(&candidate) != candidate {
+            return Err(IdError::InvalidSequence);
+        }
+
+        Self::parse_versioned(&candidate, FormatVersion::V1)
     }
 }
 
@@ -111,41 +1273,23 @@ impl std::ops::Deref for Id {
 #[cfg_attr(test, mutants::skip)]
 impl From<Id> for String {
     fn from(id: Id) -> Self {
-        id.0
+        id.0.into_owned()
     }
 }
 
 #[cfg_attr(test, mutants::skip)]
 impl From<Id> for Box<str> {
     fn from(id: Id) -> Self {
-        id.0.into_boxed_str()
+        id.0.into_owned().into_boxed_str()
     }
 }
 
 impl FromStr for Id {
     type Err = IdError;
 
+    /// Parse an ID in [`FormatVersion::V1`], see [`Id::from_str_versioned`] for other formats
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let normalized = alphabet::normalize_string(s);
-
-        if normalized.len() <= 3 {
-            return Err(IdError::TooShort);
-        }
-
-        let (body, check_char) = normalized
-            .split_at_checked(normalized.len().checked_sub(1).expect("checked above"))
-            .ok_or(IdError::InvalidCharacter)?;
-        let expected_check = alphabet::calculate_check_char(body)?;
-
-        if check_char != expected_check.to_string() {
-            return Err(IdError::InvalidCheckBit);
-        }
-
-        for c in body.chars() {
-            alphabet::validate_char(c)?;
-        }
-
-        Ok(Self(normalized))
+        Self::from_str_versioned(s, FormatVersion::V1)
     }
 }
 
@@ -153,7 +1297,7 @@ impl TryFrom<String> for Id {
     type Error = IdError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        Self::from_str(&value)
+        Self::parse_cow(Cow::Owned(value))
     }
 }
 
@@ -163,6 +1307,133 @@ impl fmt::Display for Id {
     }
 }
 
+#[cfg(feature = "tracing")]
+/// Integration with `tracing` structured logging
+///
+/// `tracing::Value` is sealed, so IDs are recorded through their `Display` implementation. Use
+/// `%id` in `tracing` macros and `#[instrument(fields(id = %id))]`, or [`Id::as_display`] wherever a
+/// value is expected, both record the ID without allocating.
+///
+/// # Examples
+///
+/// ```
+/// use human_friendly_ids::Id;
+///
+/// let id = Id::new(12);
+/// tracing::info!(%id, "created upload");
+/// tracing::info!(id = id.as_display(), "created upload");
+/// ```
+mod tracing_impl {
+    use tracing::field::{DisplayValue, display};
+
+    use super::Id;
+
+    impl Id {
+        /// Record this ID as a `tracing` field value, equivalent to `%id`
+        #[must_use]
+        pub fn as_display(&self) -> DisplayValue<&str> {
+            display(self.as_str())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn records_plain_string() {
+            let id = Id::try_from("wcfytxww4opin4jmjjes4ccfd".to_string())
+                .expect("Failed to decode UploadId");
+            assert_eq!(
+                format!("{:?}", id.as_display()),
+                "wcfytxww4opin4jmjjes4ccfd"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+/// Zero-copy IDs backed by `bytes::Bytes` buffers
+mod bytes_impl {
+    use bytes::Bytes;
+
+    use super::{Id, MAX_PARSE_LENGTH, Repr};
+    use crate::error::IdError;
+
+    impl Id {
+        /// Parse an ID out of a shared buffer, e.g. a slice of a network frame
+        ///
+        /// If the buffer already holds an ID in canonical form, the ID keeps a reference to the
+        /// buffer instead of copying it into a new `String`. Otherwise the buffer is parsed as usual
+        /// with [`Id::from_str`](std::str::FromStr::from_str).
+        ///
+        /// ## Errors
+        ///
+        /// - [`IdError::InvalidCharacter`] if the buffer is not UTF-8
+        /// - Otherwise, see [`Id::from_str`](std::str::FromStr::from_str)
+        ///
+        /// # Example
+        /// ```
+        /// use bytes::Bytes;
+        /// use human_friendly_ids::Id;
+        ///
+        /// let frame = Bytes::from_static(b"id=wcfytxww4opin4jmjjes4ccfd");
+        /// let id = Id::parse_bytes(frame.slice(3..)).unwrap();
+        /// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+        /// ```
+        pub fn parse_bytes(bytes: Bytes) -> Result<Self, IdError> {
+            let s = std::str::from_utf8(&bytes).map_err(|_| IdError::InvalidCharacter)?;
+            if s.len() <= MAX_PARSE_LENGTH && Id::is_canonical(s) {
+                return Ok(Self(Repr::Bytes(bytes)));
+            }
+            s.parse()
+        }
+    }
+
+    impl From<Id> for Bytes {
+        /// Convert to a buffer, without copying if the ID came from [`Id::parse_bytes`]
+        fn from(id: Id) -> Self {
+            match id.0 {
+                Repr::Bytes(bytes) => bytes,
+                repr @ Repr::Cow(_) => Self::from(repr.into_owned()),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn canonical_buffers_are_shared() {
+            let frame = Bytes::from(b"wcfytxww4opin4jmjjes4ccfd;".to_vec());
+            let id = Id::parse_bytes(frame.slice(..25)).expect("valid ID");
+            assert!(matches!(id.0, Repr::Bytes(_)));
+            assert_eq!(id, "wcfytxww4opin4jmjjes4ccfd".parse().expect("valid ID"));
+
+            let bytes = Bytes::from(id);
+            assert_eq!(bytes.as_ptr(), frame.as_ptr());
+        }
+
+        #[test]
+        fn other_buffers_are_parsed() {
+            let id = Id::parse_bytes(Bytes::from_static(b"WCFYTXWW4OPIN4JMJJES4CCFD"))
+                .expect("valid ID");
+            assert!(matches!(id.0, Repr::Cow(_)));
+            assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+
+            assert_eq!(
+                Id::parse_bytes(Bytes::from_static(b"wcfytxww4opin4jmjjes4ccfa")),
+                Err(IdError::InvalidCheckBit)
+            );
+            assert_eq!(
+                Id::parse_bytes(Bytes::from_static(&[0xff, 0xfe, 0xfd, 0xfc])),
+                Err(IdError::InvalidCharacter)
+            );
+        }
+    }
+}
+
 #[cfg(feature = "serde")]
 /// This module provides custom implementations for the `Serialize` and `Deserialize` traits
 /// for the `UploadId` type. These implementations allow `UploadId` to be serialized as a string
@@ -180,9 +1451,15 @@ impl fmt::Display for Id {
 /// }
 /// ```
 mod serde_impl {
-    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+    use std::fmt;
 
-    use super::Id;
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer,
+        de::{Error, Visitor},
+    };
+
+    use super::{Id, MAX_PARSE_LENGTH};
+    use crate::error::IdError;
 
     impl Serialize for Id {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -194,12 +1471,78 @@ mod serde_impl {
     }
 
     impl<'de> Deserialize<'de> for Id {
+        /// Deserialize an ID of at most [`MAX_PARSE_LENGTH`] bytes
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
-            let s = String::deserialize(deserializer)?;
-            s.parse().map_err(D::Error::custom)
+            deserializer.deserialize_str(IdVisitor {
+                max_len: MAX_PARSE_LENGTH,
+            })
+        }
+    }
+
+    impl Id {
+        /// Deserialize an ID of at most `MAX_LEN` bytes, for `#[serde(deserialize_with = "...")]`
+        ///
+        /// Formats that can lend out strings, such as JSON, have the length checked before anything
+        /// is allocated, so oversized values from untrusted clients are cheap to reject.
+        ///
+        /// ## Errors
+        ///
+        /// - [`IdError::TooLong`] if the value is longer than `MAX_LEN` bytes
+        /// - Otherwise, see [`Id::from_str_versioned`]
+        ///
+        /// # Example
+        /// ```
+        /// use human_friendly_ids::Id;
+        /// use serde::Deserialize;
+        ///
+        /// #[derive(Deserialize)]
+        /// struct Request {
+        ///     #[serde(deserialize_with = "Id::deserialize_with_limit::<32, _>")]
+        ///     id: Id,
+        /// }
+        ///
+        /// assert!(serde_json::from_str::<Request>(r#"{"id":"wcfytxww4opin4jmjjes4ccfd"}"#).is_ok());
+        /// let oversized = format!(r#"{{"id":"{}"}}"#, "a".repeat(1_000));
+        /// assert!(serde_json::from_str::<Request>(&oversized).is_err());
+        /// ```
+        pub fn deserialize_with_limit<'de, const MAX_LEN: usize, D>(
+            deserializer: D,
+        ) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_str(IdVisitor { max_len: MAX_LEN })
+        }
+    }
+
+    /// Visitor parsing IDs of at most `max_len` bytes, without copying borrowed strings
+    struct IdVisitor {
+        /// Longest accepted string, in bytes
+        max_len: usize,
+    }
+
+    impl Visitor<'_> for IdVisitor {
+        type Value = Id;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "an ID of at most {} bytes", self.max_len)
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            Id::from_str_with_limit(v, self.max_len).map_err(E::custom)
+        }
+
+        fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+            if v.len() > self.max_len {
+                return Err(E::custom(IdError::TooLong));
+            }
+            if Id::is_canonical(&v) {
+                return Ok(Id(v.into()));
+            }
+            self.visit_str(&v)
         }
     }
 
@@ -221,5 +1564,258 @@ mod serde_impl {
 
             insta::assert_debug_snapshot!(deserialized);
         }
+
+        #[test]
+        fn oversized_values_are_rejected() {
+            #[derive(serde::Deserialize)]
+            struct Request {
+                #[serde(deserialize_with = "Id::deserialize_with_limit::<16, _>")]
+                id: Id,
+            }
+
+            let long = Id::new(MAX_PARSE_LENGTH + 1);
+            let serialized = serde_json::to_string(&long).expect("serializes");
+            let error = serde_json::from_str::<Id>(&serialized).expect_err("too long");
+            assert!(error.to_string().contains("exceeds maximum"));
+
+            let short = Id::new(16);
+            let request: Request = serde_json::from_value(serde_json::json!({ "id": short }))
+                .expect("within the limit");
+            assert_eq!(request.id, short);
+            assert!(
+                serde_json::from_value::<Request>(serde_json::json!({ "id": Id::new(17) }))
+                    .is_err()
+            );
+        }
+    }
+}
+
+#[cfg(feature = "ts-rs")]
+/// Export [`Id`] to TypeScript with `ts-rs` as a branded string
+///
+/// Fields of type [`Id`] become a distinct `Id` type rather than `string`, so the TypeScript
+/// compiler rejects arbitrary strings where an ID is expected. Values received from the API can be
+/// used as-is, while strings from elsewhere need an explicit `as Id` once validated.
+///
+/// # Examples
+///
+/// ```
+/// use human_friendly_ids::Id;
+/// use ts_rs::{Config, TS};
+///
+/// #[derive(TS)]
+/// struct Order {
+///     id: Id,
+/// }
+///
+/// let cfg = Config::default();
+/// assert_eq!(Order::decl(&cfg), "type Order = { id: Id, };");
+/// assert_eq!(Id::decl(&cfg), "type Id = string & { readonly __brand: \"Id\" };");
+/// ```
+mod ts_impl {
+    use std::path::PathBuf;
+
+    use ts_rs::{Config, TS};
+
+    use super::Id;
+
+    impl TS for Id {
+        type WithoutGenerics = Self;
+        type OptionInnerType = Self;
+
+        fn docs() -> Option<String> {
+            Some(String::from(
+                "A user-friendly identifier with check bit validation",
+            ))
+        }
+
+        fn name(_: &Config) -> String {
+            String::from("Id")
+        }
+
+        fn inline(_: &Config) -> String {
+            String::from("string & { readonly __brand: \"Id\" }")
+        }
+
+        fn decl(cfg: &Config) -> String {
+            format!("type {} = {};", Self::name(cfg), Self::inline(cfg))
+        }
+
+        fn decl_concrete(cfg: &Config) -> String {
+            Self::decl(cfg)
+        }
+
+        fn output_path() -> Option<PathBuf> {
+            Some(PathBuf::from("Id.ts"))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn exports_branded_string() {
+            let cfg = Config::default();
+            let exported = Id::export_to_string(&cfg).expect("Id can be exported");
+            assert!(exported.contains("export type Id = string & { readonly __brand: \"Id\" };"));
+            assert_eq!(<Option<Id>>::name(&cfg), "Id | null");
+        }
+    }
+}
+
+#[cfg(feature = "specta")]
+/// Export [`Id`] with `specta`, e.g. for Tauri commands through `tauri-specta`
+///
+/// IDs are exported as plain strings, matching their serialized form.
+///
+/// # Examples
+///
+/// ```
+/// use human_friendly_ids::Id;
+/// use specta::{Type, Types};
+///
+/// let mut types = Types::default();
+/// assert_eq!(Id::definition(&mut types), str::definition(&mut types));
+/// ```
+mod specta_impl {
+    use specta::{Type, Types, datatype::DataType};
+
+    use super::Id;
+
+    impl Type for Id {
+        fn definition(types: &mut Types) -> DataType {
+            str::definition(types)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn exports_as_string() {
+            let mut types = Types::default();
+            assert_eq!(
+                <Option<Id>>::definition(&mut types),
+                <Option<&str>>::definition(&mut types)
+            );
+        }
+    }
+}
+
+#[cfg(feature = "poem-openapi")]
+/// Use [`Id`] in `poem-openapi` operations and objects
+///
+/// Parameters and fields are parsed like [`Id::from_str`], so invalid IDs are rejected with a 400
+/// before reaching the handler, and the schema is a string with the ID length bounds.
+///
+/// # Examples
+///
+/// ```
+/// use human_friendly_ids::Id;
+/// use poem_openapi::{Object, types::ParseFromJSON};
+///
+/// #[derive(Debug, Object)]
+/// struct Order {
+///     id: Id,
+/// }
+///
+/// let order = Order::parse_from_json_string(r#"{"id":"WCFYTXWW4OPIN4JMJJES4CCFD"}"#).unwrap();
+/// assert_eq!(order.id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+/// assert!(Order::parse_from_json_string(r#"{"id":"wcfytxww4opin4jmjjes4ccfe"}"#).is_err());
+/// ```
+mod poem_openapi_impl {
+    use std::{borrow::Cow, str::FromStr};
+
+    use poem_openapi::{
+        registry::{MetaSchema, MetaSchemaRef},
+        types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+    };
+    use serde_json::Value;
+
+    use super::{Id, MAX_PARSE_LENGTH, MIN_LENGTH};
+
+    impl Type for Id {
+        const IS_REQUIRED: bool = true;
+
+        type RawValueType = Self;
+
+        type RawElementValueType = Self;
+
+        fn name() -> Cow<'static, str> {
+            "string_id".into()
+        }
+
+        fn schema_ref() -> MetaSchemaRef {
+            MetaSchemaRef::Inline(Box::new(MetaSchema {
+                description: Some("A user-friendly identifier with check bit validation"),
+                min_length: Some(MIN_LENGTH),
+                max_length: Some(MAX_PARSE_LENGTH),
+                ..MetaSchema::new("string")
+            }))
+        }
+
+        fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+            Some(self)
+        }
+
+        fn raw_element_iter<'a>(
+            &'a self,
+        ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+            Box::new(self.as_raw_value().into_iter())
+        }
+    }
+
+    impl ParseFromJSON for Id {
+        fn parse_from_json(value: Option<Value>) -> ParseResult<Self> {
+            match value.unwrap_or_default() {
+                Value::String(s) => Id::try_from(s).map_err(ParseError::custom),
+                value => Err(ParseError::expected_type(value)),
+            }
+        }
+    }
+
+    impl ParseFromParameter for Id {
+        fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+            Id::from_str(value).map_err(ParseError::custom)
+        }
+    }
+
+    impl ToJSON for Id {
+        fn to_json(&self) -> Option<Value> {
+            Some(Value::String(self.to_string()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::error::IdError;
+
+        #[test]
+        fn parses_and_validates() {
+            let id = Id::new(12);
+            assert_eq!(
+                Id::parse_from_parameter(&id.to_uppercase()).ok(),
+                Some(id.clone())
+            );
+            assert_eq!(Id::parse_from_json(id.to_json()).ok(), Some(id));
+
+            let error = Id::parse_from_parameter("ab").expect_err("too short");
+            assert!(error.message().ends_with(&IdError::TooShort.to_string()));
+            assert!(Id::parse_from_json(Some(Value::from(12))).is_err());
+            assert!(Id::parse_from_json(None).is_err());
+        }
+
+        #[test]
+        fn schema_is_bounded_string() {
+            let MetaSchemaRef::Inline(schema) = Id::schema_ref() else {
+                panic!("inline schema");
+            };
+            assert_eq!(schema.ty, "string");
+            assert_eq!(schema.min_length, Some(MIN_LENGTH));
+            assert_eq!(schema.max_length, Some(MAX_PARSE_LENGTH));
+        }
     }
 }
@@ -0,0 +1,196 @@
+// src/intern.rs
+//! Interning pool deduplicating equal IDs
+//!
+//! Services holding many references to a smaller set of distinct IDs, such as analytics pipelines
+//! keying events by user, otherwise keep a separate `String` for every reference. An
+//! [`IdInterner`] stores each distinct ID once and hands out either a 4-byte [`IdHandle`] or a shared
+//! [`Arc<Id>`], both cheap to copy and compare.
+
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use crate::{Id, error::IdError};
+
+/// Handle to an ID stored in an [`IdInterner`]
+///
+/// Handles are only meaningful for the interner that created them. Equal IDs interned into the
+/// same interner always get equal handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdHandle(u32);
+
+impl IdHandle {
+    /// Position of the ID in the interner, in order of first insertion
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Pool storing each distinct ID once
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, intern::IdInterner};
+///
+/// let mut interner = IdInterner::new();
+/// let id = Id::new(12);
+/// let a = interner.intern(id.clone());
+/// let b = interner.intern_str(&id.to_uppercase()).unwrap();
+/// assert_eq!(a, b);
+/// assert_eq!(interner.len(), 1);
+/// assert_eq!(interner.resolve(a), Some(&id));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdInterner {
+    /// Distinct IDs, indexed by handle
+    ids: Vec<Arc<Id>>,
+    /// Handle of each distinct ID, sharing its allocation with `ids`
+    handles: HashMap<Arc<Id>, IdHandle>,
+}
+
+impl IdInterner {
+    /// Create an empty interner
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty interner with room for `capacity` distinct IDs
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: Vec::with_capacity(capacity),
+            handles: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Intern `id`, returning the handle of the stored copy
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interner already holds `u32::MAX` distinct IDs.
+    pub fn intern(&mut self, id: Id) -> IdHandle {
+        self.intern_shared(id).0
+    }
+
+    /// Parse and intern `s`, see [`IdInterner::intern`]
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::from_str`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interner already holds `u32::MAX` distinct IDs.
+    pub fn intern_str(&mut self, s: &str) -> Result<IdHandle, IdError> {
+        Ok(self.intern(Id::from_str(s)?))
+    }
+
+    /// Intern `id`, returning a shared reference to the stored copy
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interner already holds `u32::MAX` distinct IDs.
+    pub fn intern_arc(&mut self, id: Id) -> Arc<Id> {
+        self.intern_shared(id).1
+    }
+
+    /// Handle of `id`, if it has been interned
+    #[must_use]
+    pub fn get(&self, id: &Id) -> Option<IdHandle> {
+        self.handles.get(id).copied()
+    }
+
+    /// The ID behind `handle`, or `None` if it came from a different, smaller interner
+    #[must_use]
+    pub fn resolve(&self, handle: IdHandle) -> Option<&Id> {
+        self.ids.get(handle.index()).map(AsRef::as_ref)
+    }
+
+    /// Shared reference to the ID behind `handle`, see [`IdInterner::resolve`]
+    #[must_use]
+    pub fn resolve_arc(&self, handle: IdHandle) -> Option<Arc<Id>> {
+        self.ids.get(handle.index()).cloned()
+    }
+
+    /// Number of distinct IDs interned
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether no IDs have been interned
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Distinct IDs in order of first insertion, along with their handles
+    pub fn iter(&self) -> impl Iterator<Item = (IdHandle, &Id)> {
+        self.ids
+            .iter()
+            .enumerate()
+            .filter_map(|(i, id)| Some((IdHandle(u32::try_from(i).ok()?), id.as_ref())))
+    }
+
+    /// Look up or insert `id`, returning both forms of reference to the stored copy
+    fn intern_shared(&mut self, id: Id) -> (IdHandle, Arc<Id>) {
+        if let Some((id, handle)) = self.handles.get_key_value(&id) {
+            return (*handle, Arc::clone(id));
+        }
+
+        let handle = IdHandle(u32::try_from(self.ids.len()).expect("too many distinct IDs"));
+        let id = Arc::new(id);
+        self.ids.push(Arc::clone(&id));
+        self.handles.insert(Arc::clone(&id), handle);
+        (handle, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_ids_share_storage() {
+        let mut interner = IdInterner::new();
+        let ids = (0..100).map(|_| Id::new(12)).collect::<Vec<_>>();
+
+        let handles = ids
+            .iter()
+            .cycle()
+            .take(1_000)
+            .map(|id| interner.intern(id.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(interner.len(), ids.len());
+        for (handle, id) in handles.iter().zip(ids.iter().cycle()) {
+            assert_eq!(interner.resolve(*handle), Some(id));
+            assert_eq!(interner.get(id), Some(*handle));
+        }
+
+        let a = interner.intern_arc(ids[0].clone());
+        let b = interner.intern_arc(ids[0].clone());
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(Arc::ptr_eq(&a, &interner.resolve_arc(handles[0]).unwrap()));
+    }
+
+    #[test]
+    fn iter_follows_insertion_order() {
+        let mut interner = IdInterner::with_capacity(2);
+        assert!(interner.is_empty());
+        let first = interner.intern_str("wcfytxww4opin4jmjjes4ccfd").unwrap();
+        let second = interner.intern(Id::new(8));
+        interner.intern_str("WCFYTXWW4OPIN4JMJJES4CCFD").unwrap();
+
+        let handles = interner.iter().map(|(h, _)| h).collect::<Vec<_>>();
+        assert_eq!(handles, vec![first, second]);
+        assert_eq!(second.index(), 1);
+    }
+
+    #[test]
+    fn invalid_ids_are_not_interned() {
+        let mut interner = IdInterner::new();
+        assert_eq!(interner.intern_str("ab"), Err(IdError::TooShort));
+        assert!(interner.is_empty());
+        assert_eq!(interner.resolve(IdHandle(0)), None);
+    }
+}
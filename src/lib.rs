@@ -2,9 +2,57 @@
 #![deny(clippy::all, clippy::pedantic)]
 #![allow(clippy::uninlined_format_args)]
 
+pub mod adaptive;
 pub mod alphabet;
+pub mod audit;
+pub mod builder;
+pub mod checksum;
+#[cfg(feature = "rand-compat")]
+pub mod compat;
+pub mod composite;
+#[cfg(any(feature = "blake3", feature = "sha2"))]
+pub mod content;
+pub mod coupon;
+pub mod cursor;
+pub mod custom;
+pub mod encoding;
 pub mod error;
+pub mod extract;
+pub mod generator;
 pub mod id;
+pub mod intern;
+pub mod license;
+#[doc(hidden)]
+pub mod macros;
+pub mod migrate;
+pub mod phonetic;
+#[cfg(all(
+    feature = "tokio",
+    any(feature = "rand", feature = "getrandom", feature = "fastrand")
+))]
+pub mod prefetch;
+pub mod registry;
+pub mod rng;
+#[cfg(any(feature = "blake3", feature = "sha2"))]
+pub mod secret;
+pub mod segmented;
+pub mod set;
+pub mod slug;
+#[cfg(feature = "spec")]
+pub mod spec;
+pub mod spell;
+pub mod state;
+pub mod suggest;
+pub mod tagged;
+pub mod telemetry;
+#[cfg(feature = "test-util")]
+pub mod testing;
+#[cfg(any(feature = "blake3", feature = "sha2"))]
+pub mod token;
+#[cfg(any(feature = "validator", feature = "garde"))]
+pub mod validation;
+#[cfg(any(feature = "warp", feature = "salvo"))]
+pub mod web;
 
 pub use crate::id::Id;
 
@@ -35,13 +83,72 @@ mod tests {
         let id = Id::new(TEST_SIZE);
         assert_eq!(id.as_str().len(), TEST_SIZE);
 
-        // Decode and re-encode the id.
+        // Decode and re-encode the id, which is far over the default parse limit.
         let id_str = id.to_string();
-        let id_decoded: Id = id_str.parse().expect("Failed to decode UploadId");
+        assert_eq!(id_str.parse::<Id>(), Err(crate::error::IdError::TooLong));
+        let id_decoded =
+            Id::from_str_with_limit(&id_str, TEST_SIZE).expect("Failed to decode UploadId");
 
         assert_eq!(id_decoded.to_string(), id_str);
     }
 
+    #[test]
+    fn test_parse_length_limit() {
+        use crate::{error::IdError, id::MAX_PARSE_LENGTH};
+
+        let id = Id::new(MAX_PARSE_LENGTH);
+        assert_eq!(id.as_str().parse::<Id>(), Ok(id.clone()));
+        assert_eq!(Id::try_from(id.to_string()), Ok(id.clone()));
+
+        let long = Id::new(MAX_PARSE_LENGTH + 1);
+        assert_eq!(long.as_str().parse::<Id>(), Err(IdError::TooLong));
+        assert_eq!(Id::try_from(long.to_string()), Err(IdError::TooLong));
+        assert_eq!(
+            Id::from_str_with_limit(&"a".repeat(1 << 20), 64),
+            Err(IdError::TooLong)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // Reference values of 64-bit FNV-1a, which fingerprints must never drift from.
+        assert_eq!(crate::id::fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(crate::id::fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+        assert_eq!(id.fingerprint(), 0xfc16_5ffd_e2d7_423f);
+        let parsed = Id::try_from(String::from("WCFYTXWW4OPIN4JMJJES4CCFD")).unwrap();
+        assert_eq!(parsed.fingerprint(), id.fingerprint());
+        assert_ne!(Id::new(25).fingerprint(), id.fingerprint());
+    }
+
+    #[test]
+    fn test_shard_is_consistent() {
+        use std::num::NonZeroU32;
+
+        let ids = (0..10_000).map(|_| Id::new(12)).collect::<Vec<_>>();
+        let ten = NonZeroU32::new(10).unwrap();
+        let eleven = NonZeroU32::new(11).unwrap();
+
+        let mut counts = [0; 10];
+        for id in &ids {
+            let shard = id.shard(ten);
+            counts[shard as usize] += 1;
+
+            // Growing by one shard only moves IDs onto the new shard.
+            let grown = id.shard(eleven);
+            assert!(grown == shard || grown == 10);
+        }
+        assert!(
+            counts.iter().all(|c| (800..1_200).contains(c)),
+            "{counts:?}"
+        );
+
+        // Pinned so shard assignments never change between releases.
+        let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+        assert_eq!(id.shard(NonZeroU32::new(1_000).unwrap()), 659);
+    }
+
     #[test]
     fn test_decode() {
         let test_string = String::from("wcfytxww4opin4jmjjes4ccfd");
@@ -95,6 +202,430 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_truncated() {
+        let id = Id::try_from(String::from("wcfytxww4opin4jmjjes4ccfd")).unwrap();
+        assert_eq!(id.truncated(100), Ok(id.clone()));
+        assert_eq!(id.truncated(3), Err(crate::error::IdError::TooShort));
+
+        for len in 4..25 {
+            let short = id
+                .truncated(len)
+                .expect("no ambiguous sequences in this ID");
+            assert_eq!(short.len(), len);
+            assert!(id.starts_with(&short[..len - 1]));
+            assert_eq!(Id::try_from(short.to_string()), Ok(short));
+        }
+
+        // The body ends in 'r' and the recomputed check character is 'n', which would read as 'm'.
+        let id = Id::try_from(String::from("aybran")).unwrap();
+        assert_eq!(id.truncated(5), Err(crate::error::IdError::InvalidSequence));
+    }
+
+    #[test]
+    fn test_validate_prefix() {
+        use crate::{error::IdError, id::PrefixStatus};
+
+        let id = "wcfytxww4opin4jmjjes4ccfd";
+        for len in 0..id.len() {
+            let status = Id::validate_prefix(&id[..len]);
+            match len {
+                0..=3 => assert_eq!(status, PrefixStatus::TooShort),
+                _ if Id::try_from(id[..len].to_string()).is_ok() => {
+                    assert_eq!(status, PrefixStatus::Valid)
+                }
+                _ => assert_eq!(status, PrefixStatus::Incomplete),
+            }
+        }
+        assert_eq!(Id::validate_prefix(id), PrefixStatus::Valid);
+        assert_eq!(
+            Id::validate_prefix("wc🦀"),
+            PrefixStatus::Invalid(IdError::InvalidCharacter)
+        );
+        assert_eq!(
+            Id::validate_prefix(&format!("{id}-")),
+            PrefixStatus::Invalid(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn test_expected_final_char() {
+        use crate::error::IdError;
+
+        for _ in 0..1_000 {
+            let id = Id::new(12);
+            let (body, check) = id.split_at(11);
+            assert_eq!(
+                Id::expected_final_char(&body.to_uppercase()),
+                Ok(check.chars().next().unwrap())
+            );
+        }
+        assert_eq!(
+            Id::expected_final_char("aybr"),
+            Err(IdError::InvalidSequence)
+        );
+        assert_eq!(
+            Id::expected_final_char("ab#"),
+            Err(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn test_append_check_char() {
+        use crate::error::IdError;
+
+        for _ in 0..1_000 {
+            let id = Id::new(12);
+            assert_eq!(Id::append_check_char(&id[..11].to_uppercase()), Ok(id));
+        }
+        assert_eq!(Id::append_check_char("ab"), Err(IdError::TooShort));
+        assert_eq!(
+            Id::append_check_char("acme-corp"),
+            Err(IdError::InvalidCharacter)
+        );
+        assert_eq!(Id::append_check_char("wvvw"), Err(IdError::InvalidSequence));
+        assert_eq!(Id::append_check_char("aybr"), Err(IdError::InvalidSequence));
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        for _ in 0..1_000 {
+            let id = Id::new(12);
+            assert!(Id::is_canonical(&id));
+            assert!(!Id::is_canonical(&id.to_uppercase()));
+            assert!(!Id::is_canonical(&format!(" {id}")));
+        }
+        assert!(!Id::is_canonical("wcfytxww4opin4jmjjes4ccfd0"));
+        assert!(!Id::is_canonical("abc"));
+        assert!(!Id::is_canonical("wcfytxwvv4opin4jmjjes4ccfd"));
+    }
+
+    #[test]
+    fn test_repair() {
+        use crate::error::IdError;
+
+        for _ in 0..1_000 {
+            let id = Id::new(12);
+            assert_eq!(Id::repair(&id), Ok(id.clone()));
+            let corrupted = format!("{}#", &id[..11].to_uppercase());
+            assert_eq!(Id::repair(&corrupted), Ok(id));
+        }
+        assert_eq!(Id::repair("abc"), Err(IdError::TooShort));
+        assert_eq!(Id::repair("ab#cd"), Err(IdError::InvalidCharacter));
+        assert_eq!(Id::repair("aybra"), Err(IdError::InvalidSequence));
+    }
+
+    #[test]
+    fn test_matches() {
+        use crate::{error::IdError, id::MatchResult};
+
+        let id = Id::try_from(String::from("wcfytxww4opin4jmjjes4ccfd")).unwrap();
+        assert_eq!(id.matches("wcfytxww4opin4jmjjes4ccfd"), MatchResult::Exact);
+        assert_eq!(id.matches(" wcfytxww4opin4jmjjes4ccfd").is_match(), false);
+        assert_eq!(
+            id.matches("WcfytxwW4oP1n4jmjjes4ccfd"),
+            MatchResult::Normalized
+        );
+        assert!(id.matches("wcfytxww4opin4jmjjes4ccfd").is_match());
+        assert_eq!(id.matches(&Id::new(25).to_string()), MatchResult::Mismatch);
+        assert_eq!(id.matches("ab"), MatchResult::Invalid(IdError::TooShort));
+        assert_eq!(
+            id.matches("wcfytxww4opin4jmjjes4ccfe"),
+            MatchResult::Invalid(IdError::InvalidCheckBit)
+        );
+    }
+
+    #[test]
+    fn test_from_str_any() {
+        use crate::{checksum::FormatVersion, error::IdError, generator::IdGenerator};
+
+        let legacy = Id::new(25);
+        let current = IdGenerator::new(25)
+            .with_format(FormatVersion::V2)
+            .generate();
+        let both = [FormatVersion::V2, FormatVersion::V1];
+
+        assert_eq!(
+            Id::from_str_any(&[FormatVersion::V1], &legacy.to_uppercase()),
+            Ok((legacy.clone(), FormatVersion::V1))
+        );
+        let (parsed, _) = Id::from_str_any(&both, &legacy).unwrap();
+        assert_eq!(parsed, legacy);
+        assert_eq!(
+            Id::from_str_any(&both, &current),
+            Ok((current, FormatVersion::V2))
+        );
+        assert_eq!(Id::from_str_any(&both, "ab"), Err(IdError::TooShort));
+        assert_eq!(
+            Id::from_str_any(&[], &legacy),
+            Err(IdError::InvalidCheckBit)
+        );
+    }
+
+    #[test]
+    fn test_eq_canonical_agrees_with_exact_matches() {
+        use crate::id::MatchResult;
+
+        let id = Id::new(12);
+        let inputs = [
+            id.to_string(),
+            id.to_uppercase(),
+            id.truncated(8).unwrap().to_string(),
+            format!("{id} "),
+            String::new(),
+        ];
+        for input in inputs {
+            assert_eq!(
+                id.eq_canonical(&input),
+                id.matches(&input) == MatchResult::Exact,
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_body_and_check_char() {
+        for len in [4, 5, 12, 25, 64] {
+            let id = Id::new(len);
+            assert_eq!(id.body().len(), len - 1);
+            assert_eq!(format!("{}{}", id.body(), id.check_char()), id.as_str());
+            assert_eq!(
+                crate::alphabet::calculate_check_char(id.body()),
+                Ok(id.check_char())
+            );
+        }
+    }
+
+    #[test]
+    fn test_bidi_isolated() {
+        use crate::id::{LEFT_TO_RIGHT_ISOLATE, POP_DIRECTIONAL_ISOLATE};
+
+        let id = Id::new(12);
+        let isolated = id.bidi_isolated().to_string();
+        assert_eq!(isolated.chars().count(), id.len() + 2);
+        assert_eq!(
+            isolated
+                .strip_prefix(LEFT_TO_RIGHT_ISOLATE)
+                .and_then(|s| s.strip_suffix(POP_DIRECTIONAL_ISOLATE)),
+            Some(id.as_str())
+        );
+    }
+
+    #[test]
+    fn test_turkish_i_is_folded() {
+        let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+        for typed in [
+            "WCFYTXWW4OP\u{130}N4JMJJES4CCFD",
+            "wcfytxww4op\u{131}n4jmjjes4ccfd",
+        ] {
+            assert_eq!(typed.parse::<Id>(), Ok(id.clone()), "{typed}");
+        }
+    }
+
+    #[test]
+    fn test_lenient_profile_strips_invisible_characters() {
+        use crate::{alphabet::INVISIBLE_CHARS, id::ParseProfile};
+
+        let id = Id::new(16);
+        for c in INVISIBLE_CHARS {
+            let pasted = format!("{c}{}{c}{}{c}", &id[..5], &id[5..]);
+            assert_eq!(
+                Id::from_str_with_profile(&pasted, &ParseProfile::LENIENT),
+                Ok(id.clone())
+            );
+            assert!(Id::from_str_with_profile(&pasted, &ParseProfile::STRICT).is_err());
+        }
+        assert_eq!(
+            Id::from_str_with_profile(&id.bidi_isolated().to_string(), &ParseProfile::LENIENT),
+            Ok(id)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-normalization")]
+    fn test_compatibility_folding() {
+        use crate::id::ParseProfile;
+
+        let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+        // Bold capitals, fullwidth digits and a circled letter
+        let styled = "\u{1d416}\u{1d402}FYTXWW\u{ff14}OPIN\u{ff14}JMJJES4CC\u{24d5}D";
+        assert!(Id::from_str_with_profile(styled, &ParseProfile::LENIENT).is_err());
+        assert_eq!(
+            Id::from_str_with_profile(styled, &ParseProfile::STRICT.with_compatibility_folding()),
+            Ok(id)
+        );
+    }
+
+    #[test]
+    fn test_from_file_stem() {
+        use std::path::Path;
+
+        use crate::error::IdError;
+
+        let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+        for path in [
+            "wcfytxww4opin4jmjjes4ccfd",
+            "wcfytxww4opin4jmjjes4ccfd.json",
+            "/var/blobs/WCFYTXWW4OPIN4JMJJES4CCFD.bin",
+        ] {
+            assert_eq!(
+                Id::from_file_stem(Path::new(path)),
+                Ok(id.clone()),
+                "{path}"
+            );
+        }
+        assert_eq!(Id::from_file_stem(Path::new("/")), Err(IdError::TooShort));
+        assert!(Id::from_file_stem(Path::new("wcfytxww4opin4jmjjes4ccfd.tar.gz")).is_err());
+    }
+
+    #[test]
+    fn test_abbreviate() {
+        let ids = (0..1_000).map(|_| Id::new(12)).collect::<Vec<_>>();
+        for id in &ids {
+            let prefix = id.abbreviate(&ids, 1);
+            let matching = ids.iter().filter(|other| other.starts_with(prefix));
+            assert_eq!(matching.collect::<Vec<_>>(), vec![id]);
+            // Any shorter prefix is ambiguous
+            let shorter = &prefix[..prefix.len() - 1];
+            assert!(shorter.is_empty() || ids.iter().any(|o| o != id && o.starts_with(shorter)));
+            assert!(id.abbreviate(&ids, 6).len() >= 6);
+        }
+
+        let id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+        assert_eq!(id.abbreviate([], 4), "wcfy");
+        assert_eq!(id.abbreviate([], 100), id.as_str());
+        let longer = Id::append_check_char(&id).unwrap();
+        assert_eq!(id.abbreviate([&longer], 1), id.as_str());
+    }
+
+    #[test]
+    fn test_shard_path_round_trips() {
+        use std::path::Path;
+
+        let id: Id = "wcfytxww4opin4jmjjes4ccfd".parse().unwrap();
+        for depth in 0..4 {
+            assert_eq!(
+                Id::from_shard_path(&id.shard_path(depth), depth),
+                Some(id.clone())
+            );
+        }
+        assert_eq!(
+            id.shard_path(3),
+            Path::new("wc/fy/tx/wcfytxww4opin4jmjjes4ccfd")
+        );
+        assert_eq!(
+            Id::from_shard_path(Path::new("wc/fy/WCFYTXWW4OPIN4JMJJES4CCFD.json"), 2),
+            Some(id.clone())
+        );
+        assert_eq!(Id::from_shard_path(&id.shard_path(1), 2), None);
+        assert_eq!(Id::from_shard_path(Path::new("wc/fy/wcfy"), 2), None);
+
+        let short = Id::append_check_char("wcf").unwrap();
+        assert_eq!(short.shard_path(4), Path::new("wc").join(short.as_str()));
+        assert_eq!(Id::from_shard_path(&short.shard_path(4), 4), Some(short));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_from_os_str_rejects_invalid_unicode() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        use crate::error::IdError;
+
+        let input = OsStr::from_bytes(b"wcfytxww4op\xffn4jmjjes4ccfd");
+        assert_eq!(Id::from_os_str(input), Err(IdError::InvalidCharacter));
+    }
+
+    #[test]
+    fn test_raw_checksum() {
+        use crate::{alphabet::calculate_check_char, checksum::FormatVersion, error::IdError};
+
+        // A partner typed `0` for `o` and calculated the check character before normalizing.
+        let raw_body = "WCFYTXWW40PIN4JMJJES4CCF";
+        let raw_check = calculate_check_char(&raw_body.to_lowercase()).unwrap();
+        let typed = format!("{raw_body}{raw_check}");
+
+        let id = Id::from_str_versioned(&typed, FormatVersion::V1Raw).unwrap();
+        assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+        assert_eq!(Id::from_str_versioned(&id, FormatVersion::V1Raw), Ok(id));
+        assert_eq!(
+            Id::from_str_versioned(&typed, FormatVersion::V1),
+            Err(IdError::InvalidCheckBit)
+        );
+        assert_eq!(
+            Id::from_str_versioned("wcfytxww40pin4jmjjes4ccfd", FormatVersion::V1Raw),
+            Err(IdError::InvalidCheckBit)
+        );
+        assert_eq!(
+            Id::from_str_versioned("ab", FormatVersion::V1Raw),
+            Err(IdError::TooShort)
+        );
+    }
+
+    #[test]
+    fn test_id_macro() {
+        assert_eq!(
+            crate::id!("wcfytxww4opin4jmjjes4ccfd"),
+            Id::try_from(String::from("wcfytxww4opin4jmjjes4ccfd")).unwrap()
+        );
+        assert_eq!(crate::id!("abcd").as_str(), "abcd");
+    }
+
+    #[test]
+    fn test_from_static() {
+        static SENTINEL: Id = Id::from_static("wcfytxww4opin4jmjjes4ccfd");
+        assert_eq!(SENTINEL, "WCFYTXWW4OPIN4JMJJES4CCFD".parse::<Id>().unwrap());
+        assert_eq!(String::from(SENTINEL.clone()), "wcfytxww4opin4jmjjes4ccfd");
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong check character")]
+    fn test_from_static_rejects_invalid_ids() {
+        let _ = Id::from_static("wcfytxww4opin4jmjjes4ccfe");
+    }
+
+    #[test]
+    fn test_parse_cow_reuses_canonical_input() {
+        use std::borrow::Cow;
+
+        for _ in 0..1_000 {
+            let input = Id::new(12).to_string();
+            let ptr = input.as_ptr();
+            let id = Id::try_from(input).unwrap();
+            assert_eq!(id.as_ptr(), ptr);
+
+            let upper = id.to_uppercase();
+            assert_eq!(Id::parse_cow(Cow::Owned(upper)), Ok(id));
+        }
+
+        static INPUT: &str = "wcfytxww4opin4jmjjes4ccfd";
+        let id = Id::parse_cow(Cow::Borrowed(INPUT)).unwrap();
+        assert_eq!(id.as_ptr(), INPUT.as_ptr());
+        assert_eq!(
+            Id::parse_cow(Cow::Borrowed("wcfytxww4opin4jmjjes4ccfa")),
+            Err(crate::error::IdError::InvalidCheckBit)
+        );
+    }
+
+    #[test]
+    fn test_ids_are_ascii() {
+        for _ in 0..1_000 {
+            let id = Id::new(25);
+            assert!(id.as_ascii_bytes().is_ascii());
+            let parsed = id.to_uppercase().replace('o', "0").parse::<Id>().unwrap();
+            assert_eq!(parsed.as_ascii_bytes(), id.as_ascii_bytes());
+        }
+    }
+
+    #[test]
+    fn test_pattern_matches_alphabet() {
+        let chars = crate::alphabet::CHECK_ALPHABET.iter().collect::<String>();
+        assert_eq!(
+            Id::PATTERN,
+            format!("^[{chars}]{{{},}}$", crate::id::MIN_LENGTH)
+        );
+    }
+
     #[test]
     fn test_invalid_chars_error() {
         let id = "abc123".to_string();
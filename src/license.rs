@@ -0,0 +1,342 @@
+// src/license.rs
+//! License keys in dash-separated groups, e.g. `BCDFG-HJKMP-QRTVW-XY23C`
+//!
+//! Keys are made of the [`LicenseKey`] alphabet, uppercase with no confusable characters, and
+//! carry either a single check character covering the whole key or one per group. Per-group check
+//! characters let support staff tell a customer which group they mistyped.
+//!
+//! Parsing is lenient about formatting: dashes and spaces may be missing or misplaced, and case and
+//! the alphabet's confusions are normalized, as keys are usually copied from emails or typed off
+//! packaging.
+
+use std::{fmt, str::FromStr};
+
+use rand_core::RngCore;
+
+use crate::{
+    custom::{self, Alphabet, LicenseKey},
+    error::IdError,
+    id::MIN_LENGTH,
+};
+
+/// Separator between groups
+pub const GROUP_SEPARATOR: char = '-';
+
+/// Which characters of a key are check characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CheckMode {
+    /// The last character of the key covers every other character
+    #[default]
+    WholeKey,
+    /// The last character of each group covers the rest of that group
+    PerGroup,
+}
+
+/// Shape of a license key: how many groups, how long, and where the check characters go
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::license::{CheckMode, LicenseFormat};
+///
+/// let format = LicenseFormat::new(3, 4, CheckMode::PerGroup).unwrap();
+/// let key = format.generate();
+/// assert_eq!(key.as_str().len(), 14);
+///
+/// // Dashes are optional and case doesn't matter when parsing.
+/// let typed = key.as_str().replace('-', "").to_lowercase();
+/// assert_eq!(format.parse(&typed), Ok(key));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LicenseFormat {
+    /// Number of groups
+    groups: usize,
+    /// Characters per group, including any check character
+    group_len: usize,
+    /// Where the check characters go
+    check: CheckMode,
+}
+
+impl LicenseFormat {
+    /// Describe keys of `groups` groups of `group_len` characters
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if the key would be shorter than the minimum ID length, or a group
+    ///   would have no room for anything but its check character
+    pub fn new(groups: usize, group_len: usize, check: CheckMode) -> Result<Self, IdError> {
+        if groups == 0
+            || group_len == 0
+            || groups.saturating_mul(group_len) < MIN_LENGTH
+            || (check == CheckMode::PerGroup && group_len < 2)
+        {
+            return Err(IdError::TooShort);
+        }
+        Ok(Self {
+            groups,
+            group_len,
+            check,
+        })
+    }
+
+    /// Number of characters in a key, excluding separators
+    const fn key_len(&self) -> usize {
+        self.groups.saturating_mul(self.group_len)
+    }
+
+    /// Generate a new key
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn generate(&self) -> ProductKey {
+        let mut rng = crate::rng::default_rng();
+        self.generate_with_rng(&mut rng)
+    }
+
+    /// Generate a new key using the given RNG
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - every generated character is in the alphabet."
+    )]
+    #[must_use]
+    pub fn generate_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> ProductKey {
+        let chars = LicenseKey::CHARS.as_bytes();
+        let mut key = (0..self.key_len())
+            .filter_map(|_| chars.get(custom::random_index(rng, chars.len())))
+            .map(|b| char::from(*b))
+            .collect::<String>();
+        self.fill_check_chars(&mut key)
+            .expect("generated from the alphabet");
+        self.format(&key)
+    }
+
+    /// Parse a key in this format
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] or [`IdError::TooLong`] if the key has the wrong number of
+    ///   characters, ignoring separators
+    /// - [`IdError::InvalidCharacter`] if a character is not in the [`LicenseKey`] alphabet
+    /// - [`IdError::InvalidCheckBit`] if a check character doesn't match
+    pub fn parse(&self, s: &str) -> Result<ProductKey, IdError> {
+        let key = custom::normalize::<LicenseKey>(s)
+            .chars()
+            .filter(|c| *c != GROUP_SEPARATOR && !c.is_whitespace())
+            .collect::<String>();
+        match key.len().cmp(&self.key_len()) {
+            std::cmp::Ordering::Less => return Err(IdError::TooShort),
+            std::cmp::Ordering::Greater => return Err(IdError::TooLong),
+            std::cmp::Ordering::Equal => {}
+        }
+
+        if !key.chars().all(|c| LicenseKey::CHARS.contains(c)) {
+            return Err(IdError::InvalidCharacter);
+        }
+
+        let mut expected = key.clone();
+        self.fill_check_chars(&mut expected)?;
+        if expected != key {
+            return Err(IdError::InvalidCheckBit);
+        }
+        Ok(self.format(&key))
+    }
+
+    /// Overwrite the check characters of a lowercase key with the ones its other characters need
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if a character is not in the [`LicenseKey`] alphabet
+    fn fill_check_chars(&self, key: &mut String) -> Result<(), IdError> {
+        let step = match self.check {
+            CheckMode::WholeKey => self.key_len(),
+            CheckMode::PerGroup => self.group_len,
+        };
+        let mut filled = String::with_capacity(key.len());
+        for chunk in key.as_bytes().chunks(step.max(1)) {
+            let body = std::str::from_utf8(
+                chunk
+                    .get(..chunk.len().saturating_sub(1))
+                    .unwrap_or_default(),
+            )
+            .map_err(|_| IdError::InvalidCharacter)?;
+            filled.push_str(body);
+            filled.push(custom::check_char::<LicenseKey>(body)?);
+        }
+        *key = filled;
+        Ok(())
+    }
+
+    /// Uppercase a lowercase key and split it into groups
+    fn format(&self, key: &str) -> ProductKey {
+        let mut formatted = String::with_capacity(key.len().saturating_add(self.groups));
+        for (i, c) in key.chars().enumerate() {
+            if i > 0 && i.checked_rem(self.group_len) == Some(0) {
+                formatted.push(GROUP_SEPARATOR);
+            }
+            formatted.push(c.to_ascii_uppercase());
+        }
+        ProductKey {
+            key: formatted,
+            group_len: self.group_len,
+        }
+    }
+}
+
+impl Default for LicenseFormat {
+    /// Four groups of five characters with a single check character, `XXXXX-XXXXX-XXXXX-XXXXC`
+    fn default() -> Self {
+        Self {
+            groups: 4,
+            group_len: 5,
+            check: CheckMode::WholeKey,
+        }
+    }
+}
+
+/// A license key in canonical form, uppercase with dashes between groups
+///
+/// Parsing with [`FromStr`] expects the [`LicenseFormat::default`] format, use
+/// [`LicenseFormat::parse`] for others.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProductKey {
+    /// Uppercase key with separators
+    key: String,
+    /// Characters per group
+    group_len: usize,
+}
+
+impl ProductKey {
+    /// Get string slice representation
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.key
+    }
+
+    /// The groups of the key, without separators
+    pub fn groups(&self) -> impl Iterator<Item = &str> {
+        self.key.split(GROUP_SEPARATOR)
+    }
+
+    /// Characters per group, including any check character
+    #[must_use]
+    pub const fn group_len(&self) -> usize {
+        self.group_len
+    }
+}
+
+impl FromStr for ProductKey {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LicenseFormat::default().parse(s)
+    }
+}
+
+impl TryFrom<String> for ProductKey {
+    type Error = IdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl AsRef<str> for ProductKey {
+    fn as_ref(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for ProductKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_format_roundtrip() {
+        for _ in 0..1_000 {
+            let key = LicenseFormat::default().generate();
+            assert_eq!(key.as_str().len(), 23);
+            assert_eq!(key.groups().count(), 4);
+            assert!(key.groups().all(|g| g.len() == 5));
+            assert_eq!(ProductKey::from_str(key.as_str()), Ok(key.clone()));
+            assert_eq!(
+                ProductKey::from_str(&key.as_str().replace('-', " ")),
+                Ok(key)
+            );
+        }
+    }
+
+    #[test]
+    fn per_group_checks_localize_errors() {
+        let format = LicenseFormat::new(4, 5, CheckMode::PerGroup).expect("valid format");
+        for _ in 0..1_000 {
+            let key = format.generate();
+            for group in key.groups() {
+                let (body, check) = group.split_at(4);
+                let expected = custom::check_char::<LicenseKey>(&body.to_lowercase())
+                    .expect("alphabet characters");
+                assert_eq!(check, expected.to_uppercase().to_string());
+            }
+            assert_eq!(format.parse(key.as_str()), Ok(key));
+        }
+    }
+
+    #[test]
+    fn single_substitutions_are_caught() {
+        for check in [CheckMode::WholeKey, CheckMode::PerGroup] {
+            let format = LicenseFormat::new(4, 5, check).expect("valid format");
+            let key = format.generate();
+            let (first, rest) = key.as_str().split_at(1);
+            let replacement = if first == "B" { "C" } else { "B" };
+            assert_eq!(
+                format.parse(&format!("{replacement}{rest}")),
+                Err(IdError::InvalidCheckBit)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_keys_are_rejected() {
+        let key = LicenseFormat::default().generate();
+        assert_eq!(
+            ProductKey::from_str(&key.as_str()[1..]),
+            Err(IdError::TooShort)
+        );
+        assert_eq!(
+            ProductKey::from_str(&format!("{key}B")),
+            Err(IdError::TooLong)
+        );
+        assert_eq!(
+            ProductKey::from_str(&key.as_str().replacen(|c: char| c != '-', "0", 1)),
+            Err(IdError::InvalidCharacter)
+        );
+        let (rest, _) = key.as_str().split_at(key.as_str().len() - 1);
+        assert_eq!(
+            ProductKey::from_str(&format!("{rest}I")),
+            Err(IdError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn formats_are_validated() {
+        assert_eq!(
+            LicenseFormat::new(0, 5, CheckMode::WholeKey),
+            Err(IdError::TooShort)
+        );
+        assert_eq!(
+            LicenseFormat::new(1, 3, CheckMode::WholeKey),
+            Err(IdError::TooShort)
+        );
+        assert_eq!(
+            LicenseFormat::new(4, 1, CheckMode::PerGroup),
+            Err(IdError::TooShort)
+        );
+        assert!(LicenseFormat::new(4, 1, CheckMode::WholeKey).is_ok());
+    }
+}
@@ -0,0 +1,258 @@
+// src/macros.rs
+//! Macros for declaring IDs and ID types in source code
+
+/// Create an [`Id`](crate::Id) from a string literal, validated at compile time
+///
+/// The literal must already be in canonical form: lowercase, with every character in the alphabet
+/// and no ambiguous sequences. Anything else, including a wrong check character, fails to compile,
+/// so hard-coded IDs in tests and fixtures can't silently rot.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, id};
+///
+/// let id: Id = id!("wcfytxww4opin4jmjjes4ccfd");
+/// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+/// ```
+///
+/// A wrong check character is a compile error:
+/// ```compile_fail
+/// let id = human_friendly_ids::id!("wcfytxww4opin4jmjjes4ccfe");
+/// ```
+#[macro_export]
+macro_rules! id {
+    ($id:literal) => {
+        const { $crate::Id::from_static($id) }
+    };
+}
+
+/// Declare a newtype around [`Id`](crate::Id) for a single kind of entity, e.g. `UserId`
+///
+/// Separate types stop an order ID being passed where a user ID is expected, without the
+/// boilerplate of forwarding every trait by hand. The generated type has:
+///
+/// - `new` (when an RNG backend is enabled), `new_with_rng`, `as_id`, `into_id` and `as_str`
+/// - `Debug`, `Clone`, `PartialEq`, `Eq`, `Hash`, plus any attributes given to the macro
+/// - `Display`, `FromStr` and `TryFrom<String>`, parsing exactly like [`Id`](crate::Id)
+/// - `From` conversions to and from [`Id`](crate::Id), `AsRef<str>`, `AsRef<Id>` and `Deref` to
+///   [`Id`](crate::Id)
+/// - `Serialize` and `Deserialize` when the `serde` feature of this crate is enabled
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, define_id_type};
+///
+/// define_id_type! {
+///     /// Identifies a user
+///     pub UserId
+/// }
+///
+/// let user = UserId::new(12);
+/// let parsed: UserId = user.to_string().parse().unwrap();
+/// assert_eq!(parsed, user);
+/// assert_eq!(Id::from(user).len(), 12);
+/// ```
+#[macro_export]
+macro_rules! define_id_type {
+    ($(#[$meta:meta])* $vis:vis $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        $vis struct $name($crate::Id);
+
+        impl $name {
+            /// Generate a new ID with a given length using the given RNG
+            #[must_use]
+            pub fn new_with_rng<R: $crate::macros::__private::RngCore + ?Sized>(
+                len: usize,
+                rng: &mut R,
+            ) -> Self {
+                Self($crate::Id::new_with_rng(len, rng))
+            }
+
+            /// The underlying ID
+            #[must_use]
+            pub const fn as_id(&self) -> &$crate::Id {
+                &self.0
+            }
+
+            /// Unwrap the underlying ID
+            #[must_use]
+            pub fn into_id(self) -> $crate::Id {
+                self.0
+            }
+
+            /// Get string slice representation
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        $crate::__define_id_type_new!($name);
+        $crate::__define_id_type_serde!($name);
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::core::str::FromStr for $name {
+            type Err = $crate::error::IdError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                s.parse().map(Self)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::std::string::String> for $name {
+            type Error = $crate::error::IdError;
+
+            fn try_from(
+                value: ::std::string::String,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                $crate::Id::try_from(value).map(Self)
+            }
+        }
+
+        impl ::core::convert::From<$crate::Id> for $name {
+            fn from(id: $crate::Id) -> Self {
+                Self(id)
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::Id {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl ::core::convert::AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::core::convert::AsRef<$crate::Id> for $name {
+            fn as_ref(&self) -> &$crate::Id {
+                &self.0
+            }
+        }
+
+        impl ::core::ops::Deref for $name {
+            type Target = $crate::Id;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    };
+}
+
+/// `new` for [`define_id_type!`], only available with an RNG backend
+#[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_id_type_new {
+    ($name:ident) => {
+        impl $name {
+            /// Generate a new ID with a given length using the default RNG backend
+            #[must_use]
+            pub fn new(len: usize) -> Self {
+                Self($crate::Id::new(len))
+            }
+        }
+    };
+}
+
+/// `new` for [`define_id_type!`], only available with an RNG backend
+#[cfg(not(any(feature = "rand", feature = "getrandom", feature = "fastrand")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_id_type_new {
+    ($name:ident) => {};
+}
+
+/// Serde implementations for [`define_id_type!`], only available with the `serde` feature
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_id_type_serde {
+    ($name:ident) => {
+        impl $crate::macros::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::macros::__private::serde::Serializer,
+            {
+                $crate::macros::__private::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> $crate::macros::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: $crate::macros::__private::serde::Deserializer<'de>,
+            {
+                <$crate::Id as $crate::macros::__private::serde::Deserialize<'de>>::deserialize(
+                    deserializer,
+                )
+                .map(Self)
+            }
+        }
+    };
+}
+
+/// Serde implementations for [`define_id_type!`], only available with the `serde` feature
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_id_type_serde {
+    ($name:ident) => {};
+}
+
+/// Implementation details of the macros, not part of the public API
+#[doc(hidden)]
+pub mod __private {
+    pub use rand_core::RngCore;
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{Id, error::IdError};
+
+    crate::define_id_type!(
+        /// A test entity
+        OrderId
+    );
+
+    #[test]
+    fn newtype_forwards_to_id() {
+        let order = OrderId::new(12);
+        assert_eq!(order.len(), 12);
+        assert_eq!(order.as_str(), order.as_id().as_str());
+        assert_eq!(OrderId::from_str(&order.to_uppercase()), Ok(order.clone()));
+        assert_eq!(
+            OrderId::try_from(String::from("ab")),
+            Err(IdError::TooShort)
+        );
+
+        let id: Id = order.clone().into();
+        assert_eq!(OrderId::from(id.clone()), order);
+        assert_eq!(order.into_id(), id);
+        assert_eq!(OrderId::new_with_rng(8, &mut rand::rng()).len(), 8);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn newtype_serde_roundtrip() {
+        let order = OrderId::new(12);
+        let serialized = serde_json::to_string(&order).expect("serializes");
+        assert_eq!(serialized, format!("\"{order}\""));
+        let deserialized: OrderId = serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, order);
+    }
+}
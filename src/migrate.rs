@@ -0,0 +1,270 @@
+// src/migrate.rs
+//! Re-issue IDs under a newer [`FormatVersion`]
+//!
+//! Migrating keeps the body of an ID and only replaces its check character, so the old and new
+//! forms differ in their last character and can be stored side by side during a bulk re-issue.
+//! [`migrate_lines`] runs a whole export through [`migrate`] at once, and the `migrate` example
+//! wraps it in a command line tool:
+//!
+//! ```sh
+//! cargo run --example migrate -- --from v1 --to v2 --in ids.txt --out ids_new.csv
+//! ```
+
+use std::io::{self, BufRead, Write};
+
+use crate::{Id, alphabet, checksum::FormatVersion, error::IdError};
+
+/// An ID in both its original and migrated form
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Migration {
+    /// The ID as parsed, in the format it was migrated from
+    pub legacy: Id,
+    /// The same body with the check character of the target format
+    pub migrated: Id,
+}
+
+/// Parse an ID in format `from` and re-emit it in format `to`
+///
+/// ## Errors
+///
+/// - Any error from [`Id::from_str_versioned`] if `s` is not a valid ID in format `from`
+/// - [`IdError::InvalidSequence`] if the new check character would form an ambiguous sequence
+///   with the last character of the body, e.g. `rn`
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, checksum::FormatVersion, migrate};
+///
+/// let legacy = Id::new(12);
+/// let migration = migrate::migrate(&legacy, FormatVersion::V1, FormatVersion::V2).unwrap();
+/// assert_eq!(migration.legacy, legacy);
+/// assert_eq!(
+///     Id::from_str_versioned(&migration.migrated, FormatVersion::V2),
+///     Ok(migration.migrated.clone())
+/// );
+/// ```
+pub fn migrate(s: &str, from: FormatVersion, to: FormatVersion) -> Result<Migration, IdError> {
+    let legacy = Id::from_str_versioned(s, from)?;
+
+    let mut migrated = legacy.as_str().to_owned();
+    migrated.pop();
+    let check_char = to.algorithm().check_char(&migrated)?;
+    migrated.push(check_char);
+
+    // The old check character may have been the only thing keeping the body unambiguous.
+    if alphabet::normalize_string(&migrated) != migrated {
+        return Err(IdError::InvalidSequence);
+    }
+
+    Ok(Migration {
+        legacy,
+        migrated: Id(migrated.into()),
+    })
+}
+
+/// Parse an ID in [`FormatVersion::V1`] and re-emit it in [`FormatVersion::V2`], the format
+/// recommended for new deployments
+///
+/// ## Errors
+///
+/// See [`migrate`].
+pub fn migrate_legacy(s: &str) -> Result<Migration, IdError> {
+    migrate(s, FormatVersion::V1, FormatVersion::V2)
+}
+
+/// An ID that could not be migrated by [`migrate_lines`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MigrationFailure {
+    /// Line number in the input, starting at 1
+    pub line: usize,
+    /// The line as read, without surrounding whitespace
+    pub input: String,
+    /// Why the ID could not be migrated
+    pub error: IdError,
+}
+
+/// Outcome of [`migrate_lines`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MigrationReport {
+    /// Number of IDs migrated and written
+    pub migrated: usize,
+    /// IDs that failed to migrate, in input order
+    pub failures: Vec<MigrationFailure>,
+}
+
+impl MigrationReport {
+    /// Whether every ID was migrated
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Migrate one ID per line of `input` from format `from` to `to`, writing CSV to `output`
+///
+/// The CSV has a header row and a `legacy,migrated` row per ID. Blank lines are skipped, and IDs
+/// that fail to migrate are left out of the CSV and listed in the report instead, so a single bad
+/// row doesn't stop a bulk migration.
+///
+/// ## Errors
+///
+/// Any error from reading `input` or writing `output`.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{checksum::FormatVersion, migrate::migrate_lines};
+///
+/// let input = "wcfytxww4opin4jmjjes4ccfd\nnot-an-id\n\nWCFYTXWW4OPIN4JMJJES4CCFD\n";
+/// let mut csv = Vec::new();
+/// let report = migrate_lines(input.as_bytes(), &mut csv, FormatVersion::V1, FormatVersion::V2)
+///     .unwrap();
+///
+/// assert_eq!(report.migrated, 2);
+/// assert_eq!(report.failures[0].line, 2);
+/// assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 3);
+/// ```
+pub fn migrate_lines<R: BufRead, W: Write>(
+    input: R,
+    mut output: W,
+    from: FormatVersion,
+    to: FormatVersion,
+) -> io::Result<MigrationReport> {
+    let mut report = MigrationReport::default();
+    writeln!(output, "legacy,migrated")?;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match migrate(trimmed, from, to) {
+            Ok(migration) => {
+                // IDs are letters and digits, so nothing needs quoting.
+                writeln!(output, "{},{}", migration.legacy, migration.migrated)?;
+                report.migrated = report.migrated.saturating_add(1);
+            }
+            Err(error) => report.failures.push(MigrationFailure {
+                line: i.saturating_add(1),
+                input: trimmed.to_owned(),
+                error,
+            }),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrated_ids_parse_in_new_format() {
+        for _ in 0..1_000 {
+            let legacy = Id::new(25);
+            let migration = migrate_legacy(&legacy.to_uppercase()).expect("valid legacy ID");
+            assert_eq!(migration.legacy, legacy);
+            assert_eq!(
+                migration.migrated.get(..24),
+                legacy.get(..24),
+                "the body must not change"
+            );
+            assert_eq!(
+                Id::from_str_versioned(&migration.migrated, FormatVersion::V2),
+                Ok(migration.migrated.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn migrating_to_the_same_format_is_a_no_op() {
+        let id = Id::new(12);
+        let migration = migrate(&id, FormatVersion::V1, FormatVersion::V1).expect("valid ID");
+        assert_eq!(migration.legacy, migration.migrated);
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        let mut broken = Id::new(12).to_string();
+        let last = broken.pop();
+        broken.push(if last == Some('a') { 'b' } else { 'a' });
+        assert_eq!(migrate_legacy(&broken), Err(IdError::InvalidCheckBit));
+    }
+
+    #[test]
+    fn ambiguous_results_are_rejected() {
+        // A V1 body ending in 'r' whose V2 check character is 'n'.
+        let body = (0..100_000)
+            .map(|_: usize| format!("{}r", Id::new(7).get(..6).unwrap_or_default()))
+            .find(|body| {
+                FormatVersion::V2.algorithm().check_char(body) == Ok('n')
+                    && FormatVersion::V1.algorithm().check_char(body) != Ok('n')
+            })
+            .expect("a random body eventually matches");
+        let legacy = format!(
+            "{body}{}",
+            FormatVersion::V1.algorithm().check_char(&body).unwrap()
+        );
+        assert_eq!(migrate_legacy(&legacy), Err(IdError::InvalidSequence));
+    }
+
+    #[test]
+    fn bulk_migration_reports_failures() {
+        // Bodies without `r` or `v` can't become ambiguous, unlike random IDs.
+        let ids = (0..100_u64)
+            .map(|i| Id::from_bytes(&(i * 0x0123_4567_89ab).to_be_bytes()).expect("eight bytes"))
+            .collect::<Vec<_>>();
+        let mut input = ids
+            .iter()
+            .map(|id| format!("  {}  ", id.to_uppercase()))
+            .collect::<Vec<_>>();
+        input.insert(10, String::from("ab"));
+        input.insert(20, String::new());
+        let input = input.join("\n");
+
+        let mut output = Vec::new();
+        let report = migrate_lines(
+            input.as_bytes(),
+            &mut output,
+            FormatVersion::V1,
+            FormatVersion::V2,
+        )
+        .expect("in-memory IO");
+        assert!(!report.is_complete());
+        assert_eq!(
+            report.failures,
+            vec![MigrationFailure {
+                line: 11,
+                input: String::from("ab"),
+                error: IdError::TooShort,
+            }]
+        );
+
+        let output = String::from_utf8(output).expect("CSV is UTF-8");
+        let mut rows = output.lines();
+        assert_eq!(rows.next(), Some("legacy,migrated"));
+        let mut migrated = 0;
+        for (row, id) in rows.zip(&ids) {
+            let (legacy, new) = row.split_once(',').expect("two columns");
+            assert_eq!(legacy, id.as_str());
+            assert_eq!(
+                Id::from_str_versioned(new, FormatVersion::V2).map(|id| id.to_string()),
+                Ok(new.to_owned())
+            );
+            migrated += 1;
+        }
+        assert_eq!(migrated, report.migrated);
+    }
+
+    #[test]
+    fn format_versions_parse_from_names() {
+        for version in FormatVersion::ALL {
+            assert_eq!(version.to_string().parse(), Ok(*version));
+        }
+        assert_eq!("V2".parse(), Ok(FormatVersion::V2));
+        assert_eq!(
+            "v4".parse::<FormatVersion>(),
+            Err(crate::checksum::UnknownFormatVersion)
+        );
+    }
+}
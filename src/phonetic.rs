@@ -0,0 +1,212 @@
+// src/phonetic.rs
+//! Heuristics for how easily an ID can be read aloud
+
+use crate::alphabet::GEN_ALPHABET;
+
+/// Characters treated as vowels, `y` is included as it usually reads as one mid-word
+const VOWELS: [char; 6] = ['a', 'e', 'i', 'o', 'u', 'y'];
+
+/// Shortest run of consonants considered an awkward cluster
+const CLUSTER_LEN: usize = 3;
+
+/// Score how pronounceable an ID is, from `0.0` (e.g. `xwxtx`) to `1.0`
+///
+/// The score is the fraction of letters that are not part of a cluster of three or more
+/// consecutive consonants. Such clusters force the reader to spell the ID letter by letter, which
+/// is where mistakes creep in over the phone. Digits are read as words and break up clusters.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::phonetic::pronounceability;
+///
+/// assert!(pronounceability("bakeso") > pronounceability("xwxtxa"));
+/// assert!((pronounceability("xwxtx") - 0.0).abs() < f32::EPSILON);
+/// ```
+#[must_use]
+pub fn pronounceability(id: &str) -> f32 {
+    let mut letters = 0_usize;
+    let mut clustered = 0_usize;
+    let mut run = 0_usize;
+
+    for c in id.chars().map(|c| c.to_ascii_lowercase()) {
+        if !c.is_ascii_alphabetic() {
+            run = 0;
+            continue;
+        }
+
+        letters = letters.saturating_add(1);
+        if VOWELS.contains(&c) {
+            run = 0;
+            continue;
+        }
+
+        run = run.saturating_add(1);
+        if run == CLUSTER_LEN {
+            clustered = clustered.saturating_add(CLUSTER_LEN);
+        } else if run > CLUSTER_LEN {
+            clustered = clustered.saturating_add(1);
+        }
+    }
+
+    if letters == 0 {
+        return 1.0;
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "the score is a heuristic, precision loss on enormous IDs is irrelevant"
+    )]
+    let (clustered, letters) = (clustered as f32, letters as f32);
+    1.0 - clustered / letters
+}
+
+/// Pairs of characters that sound alike when read aloud, see [`ConfusionMatrix::AUDIBLE`]
+const AUDIBLE_PAIRS: [(char, char); 4] = [('b', 'p'), ('d', 't'), ('m', 'n'), ('f', 's')];
+
+/// Characters of the generation alphabet that are easily confused with each other when heard
+///
+/// Passed to [`IdGenerator::with_confusion_matrix`](crate::generator::IdGenerator::with_confusion_matrix),
+/// which never places two confusable characters next to each other, as `b` followed by `p` is
+/// easily heard as `bb`, `pp` or `pb`. This generalises the avoidance of `rn` and `vv`, which are
+/// rewritten by normalization, to pairs that only sound alike.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::phonetic::ConfusionMatrix;
+///
+/// const MATRIX: ConfusionMatrix = ConfusionMatrix::AUDIBLE.with_pair('c', 'e');
+/// assert!(MATRIX.confusable('p', 'b'));
+/// assert!(MATRIX.confusable('e', 'c'));
+/// assert!(!MATRIX.confusable('a', 'b'));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConfusionMatrix {
+    /// For each generation alphabet character, a bit set of the characters confusable with it
+    rows: [u32; GEN_ALPHABET.len()],
+}
+
+const _: () = assert!(
+    GEN_ALPHABET.len() <= u32::BITS as usize,
+    "rows of the confusion matrix are u32 bit sets"
+);
+
+impl ConfusionMatrix {
+    /// A matrix in which no characters are confusable
+    pub const EMPTY: Self = Self {
+        rows: [0; GEN_ALPHABET.len()],
+    };
+
+    /// Letters commonly misheard over the phone: `b`/`p`, `d`/`t`, `m`/`n` and `f`/`s`
+    pub const AUDIBLE: Self = {
+        let mut matrix = Self::EMPTY;
+        let mut i = 0;
+        while i < AUDIBLE_PAIRS.len() {
+            matrix = matrix.with_pair(AUDIBLE_PAIRS[i].0, AUDIBLE_PAIRS[i].1);
+            i += 1;
+        }
+        matrix
+    };
+
+    /// Mark `a` and `b` as confusable with each other
+    ///
+    /// # Panics
+    ///
+    /// If either character is not in the generation alphabet, or they are the same character.
+    /// Repeated characters are limited by
+    /// [`IdGenerator::with_max_run`](crate::generator::IdGenerator::with_max_run) instead.
+    #[must_use]
+    #[allow(
+        clippy::indexing_slicing,
+        reason = "indices come from positions in the alphabet"
+    )]
+    pub const fn with_pair(mut self, a: char, b: char) -> Self {
+        let (Some(a), Some(b)) = (position(a), position(b)) else {
+            panic!("confusable characters must be in the generation alphabet");
+        };
+        assert!(a != b, "a character can't be confused with itself");
+        self.rows[a] |= 1 << b;
+        self.rows[b] |= 1 << a;
+        self
+    }
+
+    /// Whether `a` and `b` are confusable, in either order
+    #[must_use]
+    #[allow(
+        clippy::indexing_slicing,
+        reason = "indices come from positions in the alphabet"
+    )]
+    pub const fn confusable(&self, a: char, b: char) -> bool {
+        match (position(a), position(b)) {
+            (Some(a), Some(b)) => self.rows[a] & (1 << b) != 0,
+            _ => false,
+        }
+    }
+}
+
+impl Default for ConfusionMatrix {
+    #[cfg_attr(test, mutants::skip)]
+    fn default() -> Self {
+        Self::AUDIBLE
+    }
+}
+
+/// Position of `c` in the generation alphabet
+const fn position(c: char) -> Option<usize> {
+    let mut i = 0;
+    while i < GEN_ALPHABET.len() {
+        if GEN_ALPHABET[i] == c {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        let cases = [
+            "xwxtx", "wcfytd", "bakeso", "abc", "3434", "", "sktch4ma", "WCFYTD",
+        ]
+        .map(|id| (id, pronounceability(id)));
+        insta::assert_debug_snapshot!(cases);
+    }
+
+    #[test]
+    fn score_is_bounded() {
+        for _ in 0..1_000 {
+            let id = crate::Id::new(25);
+            let score = pronounceability(&id);
+            assert!((0.0..=1.0).contains(&score), "{id} scored {score}");
+        }
+    }
+
+    #[test]
+    fn confusion_matrix_is_symmetric() {
+        for a in GEN_ALPHABET {
+            for b in GEN_ALPHABET {
+                assert_eq!(
+                    ConfusionMatrix::AUDIBLE.confusable(a, b),
+                    ConfusionMatrix::AUDIBLE.confusable(b, a)
+                );
+                assert!(!ConfusionMatrix::EMPTY.confusable(a, b));
+            }
+        }
+        let pairs = GEN_ALPHABET
+            .iter()
+            .flat_map(|a| GEN_ALPHABET.iter().map(move |b| (*a, *b)))
+            .filter(|(a, b)| ConfusionMatrix::AUDIBLE.confusable(*a, *b))
+            .count();
+        assert_eq!(pairs, AUDIBLE_PAIRS.len() * 2);
+        assert!(!ConfusionMatrix::AUDIBLE.confusable('b', '*'));
+    }
+
+    #[test]
+    #[should_panic = "confusable characters must be in the generation alphabet"]
+    fn confusion_matrix_rejects_unknown_characters() {
+        let _ = ConfusionMatrix::EMPTY.with_pair('b', 'g');
+    }
+}
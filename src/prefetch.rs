@@ -0,0 +1,137 @@
+// src/prefetch.rs
+//! IDs generated ahead of time by a background tokio task
+//!
+//! Generating an ID is cheap, but generators with policies such as
+//! [`IdGenerator::with_min_pronounceability`] may retry many times, which shows up in the tail
+//! latency of request handlers. A [`PrefetchedGenerator`] keeps a bounded buffer topped up in the
+//! background, so handlers take an ID without running the generator inline.
+
+use std::sync::Mutex;
+
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{Id, generator::IdGenerator};
+
+/// Generator handing out IDs prefetched by a background task
+///
+/// [`PrefetchedGenerator::pop`] never waits: if the buffer has run dry, e.g. during a burst, the ID
+/// is generated inline instead. Dropping the generator stops the background task.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+///
+/// use human_friendly_ids::{generator::IdGenerator, prefetch::PrefetchedGenerator};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let ids = Arc::new(PrefetchedGenerator::spawn(IdGenerator::new(12), 1_024));
+///
+/// // e.g. in a request handler
+/// let id = ids.pop();
+/// assert_eq!(id.len(), 12);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct PrefetchedGenerator {
+    /// Prefetched IDs, locked only for a non-blocking receive
+    receiver: Mutex<mpsc::Receiver<Id>>,
+    /// Copy of the generator, for when the buffer is empty
+    generator: IdGenerator,
+    /// The background task filling the buffer
+    task: JoinHandle<()>,
+}
+
+impl PrefetchedGenerator {
+    /// Start filling a buffer of up to `capacity` IDs from `generator` on the current tokio runtime
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a tokio runtime, or if `capacity` is zero.
+    #[must_use]
+    pub fn spawn(generator: IdGenerator, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let background = generator.clone();
+        let task = tokio::spawn(async move {
+            // Waits while the buffer is full, and ends once the receiver is dropped.
+            while sender.send(background.generate()).await.is_ok() {}
+        });
+
+        Self {
+            receiver: Mutex::new(receiver),
+            generator,
+            task,
+        }
+    }
+
+    /// Take a prefetched ID, or generate one inline if none are ready
+    #[must_use]
+    pub fn pop(&self) -> Id {
+        self.try_pop().unwrap_or_else(|| self.generator.generate())
+    }
+
+    /// Take a prefetched ID, or `None` if none are ready
+    #[must_use]
+    pub fn try_pop(&self) -> Option<Id> {
+        // A panic while holding the lock can't leave the receiver in a broken state.
+        let mut receiver = self
+            .receiver
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        receiver.try_recv().ok()
+    }
+
+    /// Number of IDs ready to be taken
+    #[must_use]
+    pub fn prefetched(&self) -> usize {
+        self.receiver
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .len()
+    }
+}
+
+impl Drop for PrefetchedGenerator {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn buffer_is_filled_in_background() {
+        let ids = PrefetchedGenerator::spawn(IdGenerator::new(12), 64);
+        while ids.prefetched() < 64 {
+            tokio::task::yield_now().await;
+        }
+
+        let popped = (0..64).filter_map(|_| ids.try_pop()).collect::<Vec<_>>();
+        assert_eq!(popped.len(), 64);
+        for id in &popped {
+            assert_eq!(Id::from_str(id), Ok(id.clone()));
+        }
+
+        // Without yielding, the task can't refill the buffer, so this is generated inline.
+        assert_eq!(ids.try_pop(), None);
+        assert_eq!(ids.pop().len(), 12);
+    }
+
+    #[tokio::test]
+    async fn dropping_stops_the_task() {
+        let ids = PrefetchedGenerator::spawn(IdGenerator::new(12), 4);
+        tokio::task::yield_now().await;
+        let task = ids.task.abort_handle();
+        drop(ids);
+        for _ in 0..100 {
+            if task.is_finished() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(task.is_finished());
+    }
+}
@@ -0,0 +1,180 @@
+// src/registry.rs
+//! Independently configured generators for each kind of ID an application issues
+//!
+//! Invoices, users and API keys rarely want the same length, policies or format. A
+//! [`GeneratorRegistry`] keeps one [`IdGenerator`] per namespace, such as `inv` or `usr`, so the
+//! rules for each kind live in one place and call sites only name the kind they need.
+
+use std::collections::HashMap;
+
+use rand_core::RngCore;
+
+use crate::{Id, error::IdError, generator::IdGenerator};
+
+/// Generators keyed by namespace
+///
+/// # Example
+/// ```
+/// use std::num::NonZeroUsize;
+///
+/// use human_friendly_ids::{
+///     checksum::FormatVersion, generator::IdGenerator, registry::GeneratorRegistry,
+/// };
+///
+/// let registry = GeneratorRegistry::new()
+///     .with("inv", IdGenerator::new(12).with_format(FormatVersion::V2))
+///     .with("usr", IdGenerator::new(8).with_max_run(NonZeroUsize::MIN));
+///
+/// let invoice = registry.generate("inv").unwrap();
+/// assert_eq!(invoice.len(), 12);
+/// assert_eq!(registry.parse("inv", &invoice), Some(Ok(invoice)));
+/// assert_eq!(registry.generate("usr").unwrap().len(), 8);
+/// assert_eq!(registry.generate("ord"), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorRegistry {
+    /// Generator for each namespace
+    generators: HashMap<String, IdGenerator>,
+}
+
+impl GeneratorRegistry {
+    /// Create an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `generator` for `namespace`, replacing any generator already registered for it
+    #[must_use]
+    pub fn with(mut self, namespace: impl Into<String>, generator: IdGenerator) -> Self {
+        self.register(namespace, generator);
+        self
+    }
+
+    /// Register `generator` for `namespace`, returning the generator it replaced, if any
+    pub fn register(
+        &mut self,
+        namespace: impl Into<String>,
+        generator: IdGenerator,
+    ) -> Option<IdGenerator> {
+        self.generators.insert(namespace.into(), generator)
+    }
+
+    /// Remove and return the generator for `namespace`
+    pub fn unregister(&mut self, namespace: &str) -> Option<IdGenerator> {
+        self.generators.remove(namespace)
+    }
+
+    /// Generator registered for `namespace`
+    #[must_use]
+    pub fn get(&self, namespace: &str) -> Option<&IdGenerator> {
+        self.generators.get(namespace)
+    }
+
+    /// Registered namespaces, in no particular order
+    pub fn namespaces(&self) -> impl Iterator<Item = &str> {
+        self.generators.keys().map(String::as_str)
+    }
+
+    /// Generate an ID for `namespace` using the default RNG backend, see [`crate::rng`]
+    ///
+    /// Returns `None` if no generator is registered for `namespace`.
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    #[must_use]
+    pub fn generate(&self, namespace: &str) -> Option<Id> {
+        Some(self.get(namespace)?.generate())
+    }
+
+    /// Generate an ID for `namespace` using the given RNG
+    ///
+    /// Returns `None` if no generator is registered for `namespace`.
+    #[must_use]
+    pub fn generate_with_rng<R: RngCore + ?Sized>(
+        &self,
+        namespace: &str,
+        rng: &mut R,
+    ) -> Option<Id> {
+        Some(self.get(namespace)?.generate_with_rng(rng))
+    }
+
+    /// Parse an ID issued for `namespace`, in the format of its generator
+    ///
+    /// Returns `None` if no generator is registered for `namespace`. The length isn't checked, so
+    /// IDs issued before the generator was lengthened still parse.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::from_str_versioned`].
+    #[must_use]
+    pub fn parse(&self, namespace: &str, s: &str) -> Option<Result<Id, IdError>> {
+        let generator = self.get(namespace)?;
+        Some(Id::from_str_versioned(s, generator.format()))
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, IdGenerator)> for GeneratorRegistry {
+    fn from_iter<T: IntoIterator<Item = (S, IdGenerator)>>(iter: T) -> Self {
+        Self {
+            generators: iter
+                .into_iter()
+                .map(|(namespace, generator)| (namespace.into(), generator))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{checksum::FormatVersion, rng::default_rng};
+
+    #[test]
+    fn namespaces_use_their_own_generators() {
+        let mut registry = [
+            ("inv", IdGenerator::new(12).with_format(FormatVersion::V3)),
+            ("usr", IdGenerator::new(6)),
+        ]
+        .into_iter()
+        .collect::<GeneratorRegistry>();
+
+        let mut rng = default_rng();
+        for _ in 0..100 {
+            let invoice = registry.generate_with_rng("inv", &mut rng).unwrap();
+            assert_eq!(invoice.len(), 12);
+            assert_eq!(registry.parse("inv", &invoice), Some(Ok(invoice.clone())));
+            assert_eq!(
+                Id::from_str_versioned(&invoice, FormatVersion::V3),
+                Ok(invoice)
+            );
+            assert_eq!(
+                registry.generate_with_rng("usr", &mut rng).unwrap().len(),
+                6
+            );
+        }
+
+        let mut namespaces = registry.namespaces().collect::<Vec<_>>();
+        namespaces.sort_unstable();
+        assert_eq!(namespaces, ["inv", "usr"]);
+
+        let previous = registry.register("usr", IdGenerator::new(9));
+        assert_eq!(previous.map(|g| g.length()), Some(6));
+        assert_eq!(
+            registry.generate_with_rng("usr", &mut rng).unwrap().len(),
+            9
+        );
+    }
+
+    #[test]
+    fn unknown_namespaces_are_reported() {
+        let mut registry = GeneratorRegistry::new().with("usr", IdGenerator::new(6));
+        assert_eq!(registry.parse("ord", "wcfytxww4opin4jmjjes4ccfd"), None);
+        assert_eq!(
+            registry.parse("usr", "wcfytxww4opin4jmjjes4ccfa"),
+            Some(Err(IdError::InvalidCheckBit))
+        );
+
+        assert!(registry.unregister("usr").is_some());
+        assert_eq!(registry.generate_with_rng("usr", &mut default_rng()), None);
+        assert!(registry.get("usr").is_none());
+    }
+}
@@ -0,0 +1,135 @@
+// src/rng.rs
+//! Random number backends used by [`Id::new`](crate::Id::new)
+//!
+//! The backend is selected at compile time, in order of preference:
+//!
+//! - `rand` (enabled by default) uses the thread-local RNG from `rand`.
+//! - `getrandom` pulls bytes straight from the operating system, without the thread-local state or
+//!   the rest of the `rand` stack. On `wasm32-unknown-unknown` the final binary must also enable the
+//!   `wasm_js` feature of `getrandom`.
+//! - `fastrand` is a lighter-weight, non-cryptographic alternative for tools where dependency
+//!   weight matters more than unpredictability.
+//!
+//! Any [`RngCore`] can still be passed to [`Id::new_with_rng`](crate::Id::new_with_rng).
+
+#[cfg(any(feature = "fastrand", feature = "getrandom"))]
+use rand_core::RngCore;
+
+/// The RNG used when none is supplied
+#[cfg(feature = "rand")]
+pub(crate) fn default_rng() -> rand::rngs::ThreadRng {
+    rand::rng()
+}
+
+/// The RNG used when none is supplied
+#[cfg(all(not(feature = "rand"), feature = "getrandom"))]
+pub(crate) const fn default_rng() -> GetrandomRng {
+    GetrandomRng
+}
+
+/// The RNG used when none is supplied
+#[cfg(all(
+    not(feature = "rand"),
+    not(feature = "getrandom"),
+    feature = "fastrand"
+))]
+pub(crate) fn default_rng() -> FastRng {
+    FastRng::new()
+}
+
+/// [`RngCore`] reading directly from the operating system's random source via [`getrandom`]
+///
+/// # Panics
+///
+/// The [`RngCore`] methods panic if the operating system fails to provide random bytes, which on
+/// supported platforms only happens when it is fundamentally broken.
+#[cfg(feature = "getrandom")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GetrandomRng;
+
+#[cfg(feature = "getrandom")]
+impl RngCore for GetrandomRng {
+    fn next_u32(&mut self) -> u32 {
+        getrandom::u32().expect("operating system random source failed")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        getrandom::u64().expect("operating system random source failed")
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        getrandom::fill(dst).expect("operating system random source failed");
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl rand_core::CryptoRng for GetrandomRng {}
+
+/// [`RngCore`] adapter over [`fastrand`], a small non-cryptographic RNG
+///
+/// **Not** suitable for IDs which must be unguessable.
+#[cfg(feature = "fastrand")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastRng(fastrand::Rng);
+
+#[cfg(feature = "fastrand")]
+impl FastRng {
+    /// Create an RNG with a random seed
+    #[must_use]
+    pub fn new() -> Self {
+        Self(fastrand::Rng::new())
+    }
+
+    /// Create an RNG with a fixed seed, producing a reproducible sequence
+    #[must_use]
+    pub fn with_seed(seed: u64) -> Self {
+        Self(fastrand::Rng::with_seed(seed))
+    }
+}
+
+#[cfg(feature = "fastrand")]
+#[cfg_attr(test, mutants::skip)]
+impl Default for FastRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "fastrand")]
+impl RngCore for FastRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.u32(..)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.u64(..)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0.fill(dst);
+    }
+}
+
+#[cfg(all(test, any(feature = "fastrand", feature = "getrandom")))]
+mod tests {
+    use super::*;
+    use crate::Id;
+
+    #[cfg(feature = "getrandom")]
+    #[test]
+    fn getrandom_drives_generation() {
+        let a = Id::new_with_rng(25, &mut GetrandomRng);
+        let b = Id::new_with_rng(25, &mut GetrandomRng);
+        assert_ne!(a, b);
+        assert_eq!(a.as_str().parse::<Id>(), Ok(a));
+    }
+
+    #[cfg(feature = "fastrand")]
+    #[test]
+    fn fastrand_drives_generation() {
+        let a = Id::new_with_rng(25, &mut FastRng::with_seed(7));
+        let b = Id::new_with_rng(25, &mut FastRng::with_seed(7));
+        assert_eq!(a, b);
+        assert_eq!(a.as_str().parse::<Id>(), Ok(a));
+    }
+}
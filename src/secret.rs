@@ -0,0 +1,481 @@
+// src/secret.rs
+//! High-entropy secret codes, such as gift card or password reset codes
+//!
+//! A [`SecretCode`] is a long random part drawn from a cryptographically secure RNG, followed by a
+//! short MAC under a server-side key and the usual check character. The MAC lets a code be rejected
+//! offline, e.g. at a till, before any lookup, and guessing a code that passes it takes around
+//! 2^35 attempts. Codes are compared in constant time and never show up in `Debug` output.
+//!
+//! Sign codes with a [`KeyRing`] to rotate keys without invalidating codes already handed out.
+
+use std::fmt;
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{Id, content::ContentHash, custom, encoding::BYTES_ALPHABET, error::IdError};
+
+/// Number of random characters in a code, giving over 110 bits of entropy
+pub const RANDOM_LENGTH: usize = 26;
+
+/// Number of MAC characters in a code
+pub const MAC_LENGTH: usize = 8;
+
+/// Total length of a code, including the check character
+pub const SECRET_LENGTH: usize = RANDOM_LENGTH + MAC_LENGTH + 1;
+
+/// Domain separator, so MACs can't be confused with IDs from [`Id::derive_with`] under the same key
+const MAC_CONTEXT: &[u8] = b"human-friendly-ids SecretCode\0";
+
+/// A secret code, e.g. for a gift card, see the [module documentation](crate::secret)
+///
+/// Both parts are written with [`BYTES_ALPHABET`], so codes are also valid [`Id`]s. The hash
+/// function is always passed explicitly, so enabling a feature elsewhere in the dependency graph
+/// can't invalidate codes already handed out.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{content::ContentHash, secret::SecretCode};
+///
+/// let hash = ContentHash::ALL[0];
+/// let key = b"server-side secret";
+/// let code = SecretCode::generate_with(hash, key, &mut rand::rng());
+/// let sent = code.expose_secret().to_owned();
+///
+/// assert_eq!(
+///     SecretCode::verify_with(hash, key, &sent.to_uppercase()),
+///     Ok(code.clone())
+/// );
+/// assert!(SecretCode::verify_with(hash, b"another secret", &sent).is_err());
+/// assert_eq!(format!("{code:?}"), "SecretCode(<redacted>)");
+/// ```
+#[derive(Clone)]
+pub struct SecretCode(Id);
+
+impl SecretCode {
+    /// Generate a code using the given cryptographically secure RNG and hash function
+    #[must_use]
+    pub fn generate_with<R: RngCore + CryptoRng + ?Sized>(
+        hash: ContentHash,
+        key: &[u8],
+        rng: &mut R,
+    ) -> Self {
+        Self::sign(hash, key, random_chars(rng, RANDOM_LENGTH))
+    }
+
+    /// Parse a code and check its MAC under `key` using a specific hash function
+    ///
+    /// The MAC is compared in constant time.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] or [`IdError::TooLong`] if the code is not [`SECRET_LENGTH`] long
+    /// - [`IdError::InvalidCharacter`] if a character is not in the alphabet
+    /// - [`IdError::InvalidCheckBit`] if the check character or the MAC doesn't match
+    pub fn verify_with(hash: ContentHash, key: &[u8], s: &str) -> Result<Self, IdError> {
+        Self::verify_by(hash, s, |_| Some(key))
+    }
+
+    /// Key ID of a code generated by a [`KeyRing`], i.e. its first character
+    ///
+    /// Codes generated with a single key also start with a key ID character, which means nothing.
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - codes are never empty."
+    )]
+    #[must_use]
+    pub fn key_id(&self) -> char {
+        self.0.chars().next().expect("codes are never empty")
+    }
+
+    /// The code itself, for sending to its recipient
+    ///
+    /// Named so that every place a code leaves the program is easy to audit.
+    #[must_use]
+    pub fn expose_secret(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Append the MAC of `random` under `key` and a check character
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - codes are written with an alphabet free of ambiguous sequences."
+    )]
+    fn sign(hash: ContentHash, key: &[u8], mut random: String) -> Self {
+        let mac = mac(hash, key, &random);
+        random.push_str(&mac);
+        Self(Id::from_body(&random).expect("bytes alphabet bodies are unambiguous"))
+    }
+
+    /// Parse a code and check its MAC under the key `key_for` picks for its random part
+    fn verify_by<'k, F>(hash: ContentHash, s: &str, key_for: F) -> Result<Self, IdError>
+    where
+        F: FnOnce(&str) -> Option<&'k [u8]>,
+    {
+        let id = s.parse::<Id>()?;
+        match id.len().cmp(&SECRET_LENGTH) {
+            std::cmp::Ordering::Less => return Err(IdError::TooShort),
+            std::cmp::Ordering::Greater => return Err(IdError::TooLong),
+            std::cmp::Ordering::Equal => {}
+        }
+
+        let (random, rest) = id.split_at(RANDOM_LENGTH);
+        let mac_chars = rest.get(..MAC_LENGTH).unwrap_or_default();
+        if !random.chars().all(|c| BYTES_ALPHABET.contains(&c)) {
+            return Err(IdError::InvalidCharacter);
+        }
+        let key = key_for(random).ok_or(IdError::InvalidCheckBit)?;
+        if !constant_time_eq(mac(hash, key, random).as_bytes(), mac_chars.as_bytes()) {
+            return Err(IdError::InvalidCheckBit);
+        }
+        Ok(Self(id))
+    }
+}
+
+/// Signing keys for [`SecretCode`]s, each identified by a character embedded in the codes it signs
+///
+/// New codes are signed with the current key, and start with its key ID. Codes are verified with
+/// whichever key their first character names, so rotating to a new key leaves codes signed with
+/// older keys valid until those keys are [retired](KeyRing::retire). Key IDs are characters of
+/// [`BYTES_ALPHABET`], allowing 21 keys at once, and take the place of one random character.
+///
+/// The hash function is fixed when the ring is created and used for every code it signs or
+/// verifies.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{content::ContentHash, secret::KeyRing};
+///
+/// let mut keys = KeyRing::new(ContentHash::ALL[0], 'a', b"2024 secret".to_vec()).unwrap();
+/// let old = keys.generate();
+///
+/// keys.rotate('b', b"2025 secret".to_vec()).unwrap();
+/// let new = keys.generate();
+/// assert_eq!((old.key_id(), new.key_id()), ('a', 'b'));
+/// assert_eq!(keys.verify(old.expose_secret()), Ok(old.clone()));
+///
+/// keys.retire('a');
+/// assert!(keys.verify(old.expose_secret()).is_err());
+/// assert_eq!(keys.verify(new.expose_secret()), Ok(new));
+/// ```
+#[derive(Clone)]
+pub struct KeyRing {
+    /// Hash function every code is signed and verified with
+    hash: ContentHash,
+    /// Key ID and key of every key accepted for verification
+    keys: Vec<(char, Vec<u8>)>,
+    /// Key ID of the key new codes are signed with
+    current: char,
+}
+
+impl KeyRing {
+    /// Create a ring signing new codes with `key`, identified by `key_id`, using `hash`
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if `key_id` is not in [`BYTES_ALPHABET`]
+    pub fn new(hash: ContentHash, key_id: char, key: Vec<u8>) -> Result<Self, IdError> {
+        validate_key_id(key_id)?;
+        Ok(Self {
+            hash,
+            keys: vec![(key_id, key)],
+            current: key_id,
+        })
+    }
+
+    /// Also accept codes signed with `key`, identified by `key_id`, without signing new ones with it
+    ///
+    /// Replaces any key already identified by `key_id`.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if `key_id` is not in [`BYTES_ALPHABET`]
+    pub fn with_key(mut self, key_id: char, key: Vec<u8>) -> Result<Self, IdError> {
+        self.insert(key_id, key)?;
+        Ok(self)
+    }
+
+    /// Sign new codes with `key`, identified by `key_id`, while still accepting codes signed with
+    /// the previous keys
+    ///
+    /// Replaces any key already identified by `key_id`, which invalidates codes signed with it.
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCharacter`] if `key_id` is not in [`BYTES_ALPHABET`]
+    pub fn rotate(&mut self, key_id: char, key: Vec<u8>) -> Result<(), IdError> {
+        self.insert(key_id, key)?;
+        self.current = key_id;
+        Ok(())
+    }
+
+    /// Stop accepting codes signed with the key identified by `key_id`
+    ///
+    /// Returns whether the key was removed. The current key can't be retired, rotate away from it
+    /// first.
+    pub fn retire(&mut self, key_id: char) -> bool {
+        if key_id == self.current {
+            return false;
+        }
+        let before = self.keys.len();
+        self.keys.retain(|(id, _)| *id != key_id);
+        self.keys.len() < before
+    }
+
+    /// Key ID of the key new codes are signed with
+    #[must_use]
+    pub const fn current_key_id(&self) -> char {
+        self.current
+    }
+
+    /// Hash function every code is signed and verified with
+    #[must_use]
+    pub const fn hash(&self) -> ContentHash {
+        self.hash
+    }
+
+    /// Key IDs of every key accepted for verification
+    pub fn key_ids(&self) -> impl Iterator<Item = char> {
+        self.keys.iter().map(|(id, _)| *id)
+    }
+
+    /// Generate a code signed with the current key
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`], which is only available when
+    /// it is cryptographically secure.
+    #[cfg(any(feature = "rand", feature = "getrandom"))]
+    #[must_use]
+    pub fn generate(&self) -> SecretCode {
+        let mut rng = crate::rng::default_rng();
+        self.generate_with(&mut rng)
+    }
+
+    /// Generate a code signed with the current key using the given cryptographically secure RNG
+    #[must_use]
+    pub fn generate_with<R: RngCore + CryptoRng + ?Sized>(&self, rng: &mut R) -> SecretCode {
+        let mut random = String::with_capacity(SECRET_LENGTH);
+        random.push(self.current);
+        random.push_str(&random_chars(rng, RANDOM_LENGTH.saturating_sub(1)));
+        SecretCode::sign(
+            self.hash,
+            self.key(self.current).unwrap_or_default(),
+            random,
+        )
+    }
+
+    /// Parse a code and check its MAC under the key its key ID names
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::InvalidCheckBit`] if no key in the ring has the code's key ID
+    /// - Otherwise, see [`SecretCode::verify_with`]
+    pub fn verify(&self, s: &str) -> Result<SecretCode, IdError> {
+        SecretCode::verify_by(self.hash, s, |random| self.key(random.chars().next()?))
+    }
+
+    /// Key identified by `key_id`
+    fn key(&self, key_id: char) -> Option<&[u8]> {
+        self.keys
+            .iter()
+            .find(|(id, _)| *id == key_id)
+            .map(|(_, key)| key.as_slice())
+    }
+
+    /// Add or replace the key identified by `key_id`
+    fn insert(&mut self, key_id: char, key: Vec<u8>) -> Result<(), IdError> {
+        validate_key_id(key_id)?;
+        match self.keys.iter_mut().find(|(id, _)| *id == key_id) {
+            Some((_, existing)) => *existing = key,
+            None => self.keys.push((key_id, key)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(test, mutants::skip)]
+impl fmt::Debug for KeyRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyRing")
+            .field("hash", &self.hash)
+            .field("key_ids", &self.key_ids().collect::<String>())
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Check a key ID can be embedded in a code
+fn validate_key_id(key_id: char) -> Result<(), IdError> {
+    if BYTES_ALPHABET.contains(&key_id) {
+        Ok(())
+    } else {
+        Err(IdError::InvalidCharacter)
+    }
+}
+
+/// `len` random characters of [`BYTES_ALPHABET`]
+fn random_chars<R: RngCore + ?Sized>(rng: &mut R, len: usize) -> String {
+    (0..len)
+        .filter_map(|_| BYTES_ALPHABET.get(custom::random_index(rng, BYTES_ALPHABET.len())))
+        .collect()
+}
+
+/// MAC of a code's random part, as [`MAC_LENGTH`] characters of [`BYTES_ALPHABET`]
+fn mac(hash: ContentHash, key: &[u8], random: &str) -> String {
+    let mut input = Vec::with_capacity(MAC_CONTEXT.len().saturating_add(random.len()));
+    input.extend_from_slice(MAC_CONTEXT);
+    input.extend_from_slice(random.as_bytes());
+    let digest = hash.keyed_digest(key, &input);
+
+    // 21^8 is far below 2^64, so reducing the first eight bytes leaves a negligible bias.
+    let mut value = digest
+        .first_chunk::<8>()
+        .copied()
+        .map(u64::from_le_bytes)
+        .unwrap_or_default();
+    let base = BYTES_ALPHABET.len() as u64;
+    (0..MAC_LENGTH)
+        .filter_map(|_| {
+            let digit = usize::try_from(value % base).ok()?;
+            value /= base;
+            BYTES_ALPHABET.get(digit)
+        })
+        .collect()
+}
+
+/// Compare two byte strings in time depending only on their lengths
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0_u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl PartialEq for SecretCode {
+    /// Compare codes in constant time
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(self.0.as_bytes(), other.0.as_bytes())
+    }
+}
+
+impl Eq for SecretCode {}
+
+#[cfg_attr(test, mutants::skip)]
+impl fmt::Debug for SecretCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretCode(<redacted>)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    /// Hash function used throughout these tests
+    const HASH: ContentHash = ContentHash::ALL[0];
+
+    /// Generate a code under `key`
+    fn generate(key: &[u8]) -> SecretCode {
+        SecretCode::generate_with(HASH, key, &mut rand::rng())
+    }
+
+    /// Verify a code under `key`
+    fn verify(key: &[u8], s: &str) -> Result<SecretCode, IdError> {
+        SecretCode::verify_with(HASH, key, s)
+    }
+
+    #[test]
+    fn roundtrip() {
+        for _ in 0..1_000 {
+            let code = generate(b"key");
+            assert_eq!(code.expose_secret().len(), SECRET_LENGTH);
+            assert!(Id::from_str(code.expose_secret()).is_ok());
+            assert_eq!(verify(b"key", code.expose_secret()), Ok(code));
+        }
+    }
+
+    #[test]
+    fn forged_codes_are_rejected() {
+        let code = generate(b"key");
+        assert_eq!(
+            verify(b"other key", code.expose_secret()),
+            Err(IdError::InvalidCheckBit)
+        );
+
+        // A code with a valid check character but no valid MAC.
+        let body = "a".repeat(SECRET_LENGTH - 1);
+        let forged = Id::from_body(&body).expect("valid body");
+        assert_eq!(verify(b"key", &forged), Err(IdError::InvalidCheckBit));
+
+        let short = Id::new(SECRET_LENGTH - 1);
+        assert_eq!(verify(b"key", &short), Err(IdError::TooShort));
+    }
+
+    #[cfg(all(feature = "blake3", feature = "sha2"))]
+    #[test]
+    fn hash_functions_give_different_macs() {
+        let mut rng = rand::rng();
+        let code = SecretCode::generate_with(ContentHash::Blake3, b"key", &mut rng);
+        let sent = code.expose_secret();
+        assert!(SecretCode::verify_with(ContentHash::Blake3, b"key", sent).is_ok());
+        assert!(SecretCode::verify_with(ContentHash::Sha256, b"key", sent).is_err());
+    }
+
+    #[test]
+    fn rotated_keys_keep_old_codes_valid() {
+        let mut keys = KeyRing::new(HASH, 'a', b"first".to_vec()).unwrap();
+        let first = keys.generate();
+        keys.rotate('b', b"second".to_vec()).unwrap();
+        let second = keys.generate();
+        assert_eq!(keys.current_key_id(), 'b');
+        assert_eq!(keys.key_ids().collect::<String>(), "ab");
+
+        for code in [&first, &second] {
+            assert_eq!(code.expose_secret().len(), SECRET_LENGTH);
+            assert_eq!(keys.verify(code.expose_secret()), Ok(code.clone()));
+        }
+        // Each code verifies with the key it names, and only that key.
+        assert!(verify(b"first", first.expose_secret()).is_ok());
+        assert!(verify(b"second", first.expose_secret()).is_err());
+
+        assert!(!keys.retire('b'));
+        assert!(keys.retire('a'));
+        assert!(!keys.retire('a'));
+        assert_eq!(
+            keys.verify(first.expose_secret()),
+            Err(IdError::InvalidCheckBit)
+        );
+
+        // Reusing a key ID for a new key invalidates codes signed with the old one.
+        keys.rotate('b', b"third".to_vec()).unwrap();
+        assert_eq!(
+            keys.verify(second.expose_secret()),
+            Err(IdError::InvalidCheckBit)
+        );
+    }
+
+    #[test]
+    fn key_ids_must_be_in_the_alphabet() {
+        assert_eq!(
+            KeyRing::new(HASH, 'z', b"key".to_vec()).map(|_| ()),
+            Err(IdError::InvalidCharacter)
+        );
+        let keys = KeyRing::new(HASH, 'a', b"key".to_vec())
+            .unwrap()
+            .with_key('c', b"old".to_vec())
+            .unwrap();
+        assert_eq!(keys.current_key_id(), 'a');
+        assert!(keys.clone().with_key('A', b"old".to_vec()).is_err());
+        assert_eq!(
+            format!("{keys:?}"),
+            format!("KeyRing {{ hash: {HASH:?}, key_ids: \"ac\", current: 'a', .. }}")
+        );
+    }
+
+    #[test]
+    fn comparison_is_by_value() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+        let code = generate(b"key");
+        assert_eq!(code, code.clone());
+        assert_ne!(code, generate(b"key"));
+    }
+}
@@ -0,0 +1,274 @@
+// src/segmented.rs
+//! Multiple IDs joined by a separator, e.g. `wcfytd-kmop3s`
+//!
+//! The joined forms of [`SegmentedId`] and [`CompositeId`](crate::composite::CompositeId) are
+//! split, displayed and serialized the same way, and rejected with the same [`JoinError`].
+
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{Id, error::IdError};
+
+/// Most parts a joined ID may have, so untrusted input can't force unbounded allocation
+pub const MAX_PARTS: usize = 32;
+
+/// Why a joined ID was rejected
+#[derive(Error, Debug, PartialEq, Eq, Clone, Hash)]
+pub enum JoinError {
+    /// One of the joined IDs is invalid
+    #[error(transparent)]
+    Id(#[from] IdError),
+    /// There are no parts
+    #[error("Joined ID has no parts")]
+    Empty,
+    /// There are more than [`MAX_PARTS`] parts
+    #[error("Joined ID has more than {MAX_PARTS} parts")]
+    TooManyParts,
+    /// A segment has no kind separator
+    #[error("Segment is missing its kind")]
+    MissingKind,
+    /// A segment kind is empty, too long or contains anything but ASCII letters and digits
+    #[error("Invalid segment kind")]
+    InvalidKind,
+}
+
+impl JoinError {
+    /// Stable, machine readable code for the error, see [`IdError::code`]
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Id(e) => e.code(),
+            Self::Empty => "empty",
+            Self::TooManyParts => "too_many_parts",
+            Self::MissingKind => "missing_kind",
+            Self::InvalidKind => "invalid_kind",
+        }
+    }
+}
+
+/// Several [`Id`]s joined by `SEPARATOR`, each validated independently
+///
+/// The separator is part of the type so the joined form can be parsed and deserialized without
+/// extra configuration. It must not be an ASCII letter or digit, as those are all either part of
+/// the alphabet or normalized into it.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, segmented::SegmentedId};
+///
+/// let id = SegmentedId::<'_'>::new(vec![Id::new(6), Id::new(6)]).unwrap();
+/// let parsed: SegmentedId<'_'> = id.to_string().parse().unwrap();
+/// assert_eq!(parsed.parts(), id.parts());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SegmentedId<const SEPARATOR: char = '-'> {
+    /// The joined IDs, never empty
+    parts: Vec<Id>,
+}
+
+impl<const SEPARATOR: char> SegmentedId<SEPARATOR> {
+    /// Separator between the parts
+    pub const SEPARATOR: char = {
+        assert!(
+            !SEPARATOR.is_ascii_alphanumeric(),
+            "separator must not be an ASCII letter or digit"
+        );
+        SEPARATOR
+    };
+
+    /// Join IDs into a segmented ID
+    ///
+    /// ## Errors
+    ///
+    /// - [`JoinError::Empty`] if `parts` is empty
+    /// - [`JoinError::TooManyParts`] if there are more than [`MAX_PARTS`] parts
+    pub fn new(parts: Vec<Id>) -> Result<Self, JoinError> {
+        // Evaluating the constant rejects invalid separators at compile time.
+        let _ = Self::SEPARATOR;
+        if parts.is_empty() {
+            return Err(JoinError::Empty);
+        }
+        if parts.len() > MAX_PARTS {
+            return Err(JoinError::TooManyParts);
+        }
+        Ok(Self { parts })
+    }
+
+    /// The joined IDs, in order
+    #[must_use]
+    pub fn parts(&self) -> &[Id] {
+        &self.parts
+    }
+
+    /// Split into the joined IDs
+    #[must_use]
+    pub fn into_parts(self) -> Vec<Id> {
+        self.parts
+    }
+}
+
+/// Split `s` on `separator` and parse each part, rejecting more than [`MAX_PARTS`] before parsing
+pub(crate) fn parse_joined<T, E>(
+    s: &str,
+    separator: char,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Vec<T>, JoinError>
+where
+    JoinError: From<E>,
+{
+    if s.split(separator).nth(MAX_PARTS).is_some() {
+        return Err(JoinError::TooManyParts);
+    }
+    s.split(separator)
+        .map(|part| parse(part).map_err(JoinError::from))
+        .collect()
+}
+
+/// Write `parts` separated by `separator`
+pub(crate) fn fmt_joined<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    parts: &[T],
+    separator: char,
+) -> fmt::Result {
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "{}", part)?;
+    }
+    Ok(())
+}
+
+impl<const SEPARATOR: char> FromStr for SegmentedId<SEPARATOR> {
+    type Err = JoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(parse_joined(s, Self::SEPARATOR, Id::from_str)?)
+    }
+}
+
+impl<const SEPARATOR: char> TryFrom<String> for SegmentedId<SEPARATOR> {
+    type Error = JoinError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl<const SEPARATOR: char> fmt::Display for SegmentedId<SEPARATOR> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_joined(f, &self.parts, Self::SEPARATOR)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize joined IDs in their string form
+pub(crate) mod serde_impl {
+    use std::{fmt::Display, str::FromStr};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::{JoinError, SegmentedId};
+
+    /// Serialize a joined ID as its [`Display`] form
+    pub(crate) fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    /// Deserialize a joined ID from a string through its [`FromStr`] implementation
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr<Err = JoinError>,
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+
+    impl<const SEPARATOR: char> Serialize for SegmentedId<SEPARATOR> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serialize(self, serializer)
+        }
+    }
+
+    impl<'de, const SEPARATOR: char> Deserialize<'de> for SegmentedId<SEPARATOR> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_default_separator() {
+        let parts = vec![Id::new(6), Id::new(8), Id::new(10)];
+        let segmented = SegmentedId::<'-'>::new(parts.clone()).expect("non-empty");
+        let joined = segmented.to_string();
+        assert_eq!(joined.matches('-').count(), 2);
+
+        let parsed: SegmentedId = joined.to_uppercase().parse().expect("valid parts");
+        assert_eq!(parsed.into_parts(), parts);
+    }
+
+    #[test]
+    fn each_part_is_validated() {
+        let valid = Id::new(6);
+        assert_eq!(
+            SegmentedId::<'/'>::from_str(&format!("{valid}/abc")),
+            Err(JoinError::Id(IdError::TooShort))
+        );
+        assert!(SegmentedId::<'/'>::from_str(&format!("{valid}-{valid}")).is_err());
+        assert_eq!(SegmentedId::<'/'>::new(Vec::new()), Err(JoinError::Empty));
+        assert_eq!(
+            SegmentedId::<'/'>::from_str("").map_err(|e| e.code()),
+            Err("too_short")
+        );
+    }
+
+    #[test]
+    fn part_count_is_bounded() {
+        let valid = Id::new(6).to_string();
+        let max = vec![valid.as_str(); MAX_PARTS].join("/");
+        assert_eq!(
+            SegmentedId::<'/'>::from_str(&max).map(|id| id.parts().len()),
+            Ok(MAX_PARTS)
+        );
+        assert_eq!(
+            SegmentedId::<'/'>::from_str(&format!("{max}/{valid}")),
+            Err(JoinError::TooManyParts)
+        );
+        // Rejected before any part is parsed.
+        assert_eq!(
+            SegmentedId::<'/'>::from_str(&"/".repeat(100_000)),
+            Err(JoinError::TooManyParts)
+        );
+        assert_eq!(
+            SegmentedId::<'/'>::new(vec![Id::new(6); MAX_PARTS + 1]),
+            Err(JoinError::TooManyParts)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let segmented = SegmentedId::<':'>::new(vec![Id::new(6), Id::new(6)]).expect("non-empty");
+        let serialized = serde_json::to_string(&segmented).expect("serializes");
+        assert_eq!(serialized, format!("\"{segmented}\""));
+        let deserialized: SegmentedId<':'> =
+            serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, segmented);
+    }
+}
@@ -0,0 +1,219 @@
+// src/set.rs
+//! Sorted set of IDs, resolving the shortened IDs users type back to full ones
+//!
+//! CLI tools can display [`IdSet::abbreviate`]d IDs in tables and accept them back as arguments
+//! with [`IdSet::resolve_prefix`], the way git handles short commit hashes.
+
+use crate::{Id, alphabet};
+
+/// Outcome of looking up a shortened ID with [`IdSet::resolve_prefix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution<'a> {
+    /// Exactly one ID starts with the prefix
+    Unique(&'a Id),
+    /// This many IDs start with the prefix, so it must be made longer
+    Ambiguous(usize),
+    /// No ID starts with the prefix
+    NotFound,
+}
+
+/// Set of distinct IDs kept in sorted order
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{
+///     Id,
+///     set::{IdSet, Resolution},
+/// };
+///
+/// let ids = ["wcfytxww4opin4jmjjes4ccf", "wcfa3k", "mpt4de"]
+///     .map(|body| Id::append_check_char(body).unwrap());
+/// let set = IdSet::from_iter(ids.clone());
+/// assert_eq!(set.resolve_prefix("WCFY"), Resolution::Unique(&ids[0]));
+/// assert_eq!(set.resolve_prefix("wcf"), Resolution::Ambiguous(2));
+/// assert_eq!(set.resolve_prefix("x"), Resolution::NotFound);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdSet {
+    /// IDs sorted by their canonical form
+    ids: Vec<Id>,
+}
+
+impl IdSet {
+    /// Create an empty set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert `id`, returning whether it was not already in the set
+    pub fn insert(&mut self, id: Id) -> bool {
+        match self.position(&id) {
+            Ok(_) => false,
+            Err(i) => {
+                self.ids.insert(i, id);
+                true
+            }
+        }
+    }
+
+    /// Remove `id`, returning whether it was in the set
+    pub fn remove(&mut self, id: &Id) -> bool {
+        let Ok(i) = self.position(id) else {
+            return false;
+        };
+        self.ids.remove(i);
+        true
+    }
+
+    /// Whether `id` is in the set
+    #[must_use]
+    pub fn contains(&self, id: &Id) -> bool {
+        self.position(id).is_ok()
+    }
+
+    /// Number of IDs in the set
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the set is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// IDs in the set, in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = &Id> {
+        self.ids.iter()
+    }
+
+    /// Find the ID a user meant by typing the start of it
+    ///
+    /// The prefix is normalized the same way as when parsing, so it can be typed in any case and
+    /// with confusable characters.
+    #[must_use]
+    pub fn resolve_prefix(&self, prefix: &str) -> Resolution<'_> {
+        let prefix = alphabet::normalize_string(prefix);
+        let start = self.ids.partition_point(|id| id.as_str() < prefix.as_str());
+        let matching = self.ids.get(start..).unwrap_or_default();
+        let count = matching.partition_point(|id| id.starts_with(prefix.as_str()));
+        match matching.first() {
+            Some(id) if count == 1 => Resolution::Unique(id),
+            _ if count == 0 => Resolution::NotFound,
+            _ => Resolution::Ambiguous(count),
+        }
+    }
+
+    /// Shortest prefix of `id` that no other ID in the set starts with, see [`Id::abbreviate`]
+    ///
+    /// Only the IDs either side of `id` in sorted order can share a longer prefix with it, so this
+    /// is much faster than [`Id::abbreviate`] over the whole set.
+    #[must_use]
+    pub fn abbreviate<'a>(&self, id: &'a Id, min_len: usize) -> &'a str {
+        let (before, after) = match self.position(id) {
+            Ok(i) => (i.checked_sub(1), i.saturating_add(1)),
+            Err(i) => (i.checked_sub(1), i),
+        };
+        let neighbours = before
+            .and_then(|i| self.ids.get(i))
+            .into_iter()
+            .chain(self.ids.get(after));
+        id.abbreviate(neighbours, min_len)
+    }
+
+    /// Index of `id`, or where it would be inserted
+    fn position(&self, id: &Id) -> Result<usize, usize> {
+        self.ids
+            .binary_search_by(|other| other.as_str().cmp(id.as_str()))
+    }
+}
+
+impl FromIterator<Id> for IdSet {
+    fn from_iter<T: IntoIterator<Item = Id>>(iter: T) -> Self {
+        let mut ids = iter.into_iter().collect::<Vec<_>>();
+        ids.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+        ids.dedup();
+        Self { ids }
+    }
+}
+
+impl Extend<Id> for IdSet {
+    fn extend<T: IntoIterator<Item = Id>>(&mut self, iter: T) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IdSet {
+    type Item = &'a Id;
+    type IntoIter = std::slice::Iter<'a, Id>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_every_abbreviation_uniquely() {
+        let set = (0..1_000).map(|_| Id::new(10)).collect::<IdSet>();
+        for id in &set {
+            let prefix = set.abbreviate(id, 1);
+            assert_eq!(prefix, id.abbreviate(&set, 1));
+            assert_eq!(set.resolve_prefix(prefix), Resolution::Unique(id));
+            assert_eq!(
+                set.resolve_prefix(&prefix.to_uppercase()),
+                Resolution::Unique(id)
+            );
+            assert_eq!(set.resolve_prefix(id), Resolution::Unique(id));
+            if prefix.len() > 1 {
+                let shorter = &prefix[..prefix.len() - 1];
+                assert!(matches!(
+                    set.resolve_prefix(shorter),
+                    Resolution::Ambiguous(_)
+                ));
+            }
+        }
+        assert_eq!(set.resolve_prefix(""), Resolution::Ambiguous(set.len()));
+    }
+
+    #[test]
+    fn abbreviates_ids_outside_the_set() {
+        let set = ["wcfytxww4opin4jmjjes4ccf", "wcfa3k"]
+            .map(|body| Id::append_check_char(body).unwrap())
+            .into_iter()
+            .collect::<IdSet>();
+        let id = Id::append_check_char("wcfyab").unwrap();
+        assert!(!set.contains(&id));
+        assert_eq!(set.abbreviate(&id, 1), "wcfya");
+    }
+
+    #[test]
+    fn insert_and_remove_keep_ids_distinct_and_sorted() {
+        let mut set = IdSet::new();
+        assert!(set.is_empty());
+        assert_eq!(set.resolve_prefix("w"), Resolution::NotFound);
+
+        let ids = (0..100).map(|_| Id::new(8)).collect::<Vec<_>>();
+        set.extend(ids.iter().cloned());
+        set.extend(ids.iter().cloned());
+        assert_eq!(set.len(), ids.len());
+        assert!(
+            set.iter()
+                .zip(set.iter().skip(1))
+                .all(|(a, b)| a.as_str() < b.as_str())
+        );
+
+        assert!(!set.insert(ids[0].clone()));
+        assert!(set.remove(&ids[0]));
+        assert!(!set.remove(&ids[0]));
+        assert!(!set.contains(&ids[0]));
+        assert_eq!(set, ids.into_iter().skip(1).collect());
+    }
+}
@@ -0,0 +1,270 @@
+// src/slug.rs
+//! Readable slugs derived from titles, e.g. `my-blog-post-k3fa`
+//!
+//! The words keep their usual spelling so URLs stay meaningful, while a short random suffix keeps
+//! slugs for the same title apart. The suffix ends in a check character covering the whole slug, so
+//! a mistyped link can be rejected without a lookup.
+
+use std::{fmt, str::FromStr};
+
+use rand_core::RngCore;
+
+use crate::{
+    Id,
+    error::IdError,
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+    tagged::{TAG_SEPARATOR, generate_sealed, parse_sealed},
+};
+
+/// Length of the random suffix, including the check character
+pub const SUFFIX_LENGTH: usize = MIN_LENGTH;
+
+/// A slug made of the words of a title and a checked random suffix, e.g. `my-blog-post-k3fa`
+///
+/// See [`Id::slug_from`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Slug {
+    /// Lowercase words joined by [`TAG_SEPARATOR`], possibly empty
+    words: String,
+    /// Normalized random characters followed by the check character
+    suffix: String,
+}
+
+impl Id {
+    /// Derive a slug from `title`, at most `max_len` characters long
+    ///
+    /// The title is lowercased and split into words on anything but ASCII letters and digits.
+    /// Whole words are kept while they fit before the suffix, so titles are cut at a word boundary
+    /// unless the first word alone is too long. A title without any usable words gives a slug that
+    /// is only the suffix. This method uses the default RNG backend, see [`crate::rng`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`Id::slug_from_with_rng`].
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, slug::Slug};
+    ///
+    /// let slug = Id::slug_from("My Blog Post!", 32).unwrap();
+    /// assert_eq!(slug.words(), "my-blog-post");
+    /// assert!(slug.to_string().starts_with("my-blog-post-"));
+    ///
+    /// // Slugs parse back, and edits to the words are caught.
+    /// assert_eq!(slug.to_string().parse::<Slug>(), Ok(slug.clone()));
+    /// let edited = slug.to_string().replace("blog", "blob");
+    /// assert!(edited.parse::<Slug>().is_err());
+    /// ```
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    pub fn slug_from(title: &str, max_len: usize) -> Result<Slug, IdError> {
+        let mut rng = crate::rng::default_rng();
+        Self::slug_from_with_rng(title, max_len, &mut rng)
+    }
+
+    /// Derive a slug from `title` using the given RNG, at most `max_len` characters long
+    ///
+    /// See [`Id::slug_from`].
+    ///
+    /// ## Errors
+    ///
+    /// - [`IdError::TooShort`] if `max_len` is below [`SUFFIX_LENGTH`]
+    pub fn slug_from_with_rng<R: RngCore + ?Sized>(
+        title: &str,
+        max_len: usize,
+        rng: &mut R,
+    ) -> Result<Slug, IdError> {
+        if max_len < SUFFIX_LENGTH {
+            return Err(IdError::TooShort);
+        }
+        let budget = max_len.saturating_sub(SUFFIX_LENGTH.saturating_add(1));
+        let words = slug_words(title, budget);
+        let suffix = generate_sealed(&words, SUFFIX_LENGTH, rng);
+        Ok(Slug { words, suffix })
+    }
+}
+
+/// Lowercase words of `title` joined by [`TAG_SEPARATOR`], at most `budget` bytes long
+fn slug_words(title: &str, budget: usize) -> String {
+    let mut words = String::with_capacity(budget);
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let needed = if words.is_empty() {
+            word.len()
+        } else {
+            word.len().saturating_add(1)
+        };
+        if words.len().saturating_add(needed) > budget {
+            if words.is_empty() {
+                words.push_str(word.get(..budget).unwrap_or_default());
+            }
+            break;
+        }
+        if !words.is_empty() {
+            words.push(TAG_SEPARATOR);
+        }
+        words.push_str(word);
+    }
+    words.make_ascii_lowercase();
+    words
+}
+
+impl Slug {
+    /// The words taken from the title, joined by [`TAG_SEPARATOR`]
+    #[must_use]
+    pub fn words(&self) -> &str {
+        &self.words
+    }
+
+    /// The random suffix, including the check character
+    #[must_use]
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+}
+
+impl FromStr for Slug {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_PARSE_LENGTH {
+            return Err(IdError::TooLong);
+        }
+        let (words, suffix) = s.rsplit_once(TAG_SEPARATOR).unwrap_or(("", s));
+        if !words.is_empty()
+            && !words
+                .split(TAG_SEPARATOR)
+                .all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            return Err(IdError::InvalidCharacter);
+        }
+        let words = words.to_ascii_lowercase();
+
+        let suffix = parse_sealed(&words, suffix)?;
+        Ok(Self { words, suffix })
+    }
+}
+
+impl TryFrom<String> for Slug {
+    type Error = IdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl fmt::Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.words.is_empty() {
+            write!(f, "{}", self.suffix)
+        } else {
+            write!(f, "{}{}{}", self.words, TAG_SEPARATOR, self.suffix)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize slugs in their joined string form
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::Slug;
+
+    impl Serialize for Slug {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Slug {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        for _ in 0..1_000 {
+            let slug = Id::slug_from("Rust 2024: What's New?", 64).expect("long enough");
+            assert_eq!(slug.words(), "rust-2024-what-s-new");
+            assert_eq!(slug.suffix().len(), SUFFIX_LENGTH);
+            assert_eq!(Slug::from_str(&slug.to_string().to_uppercase()), Ok(slug));
+        }
+    }
+
+    #[test]
+    fn titles_are_cut_at_word_boundaries() {
+        let slug = Id::slug_from("my blog post", 11).expect("long enough");
+        assert_eq!(slug.words(), "my");
+        assert!(slug.to_string().len() <= 11);
+
+        let slug = Id::slug_from("extraordinarily", 10).expect("long enough");
+        assert_eq!(slug.words(), "extra");
+        assert_eq!(slug.to_string().len(), 10);
+    }
+
+    #[test]
+    fn titles_without_words_give_bare_suffixes() {
+        let slug = Id::slug_from("!!!", 32).expect("long enough");
+        assert_eq!(slug.words(), "");
+        assert_eq!(slug.to_string(), slug.suffix());
+        assert_eq!(Slug::from_str(slug.suffix()), Ok(slug.clone()));
+        // With no words the check character is an ordinary one.
+        assert!(Id::from_str(slug.suffix()).is_ok());
+    }
+
+    #[test]
+    fn words_are_covered_by_check_char() {
+        for _ in 0..1_000 {
+            let slug = Id::slug_from("my blog post", 32).expect("long enough");
+            for other in ["my-blob-post", "my-post-blog", "my-blog"] {
+                assert_eq!(
+                    Slug::from_str(&format!("{other}-{}", slug.suffix())),
+                    Err(IdError::InvalidCheckBit),
+                    "{slug} accepted as {other}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_slugs_are_rejected() {
+        assert_eq!(Id::slug_from("title", 3), Err(IdError::TooShort));
+        assert_eq!(
+            Slug::from_str("my--post-abcd"),
+            Err(IdError::InvalidCharacter)
+        );
+        assert_eq!(
+            Slug::from_str("my_post-abcd"),
+            Err(IdError::InvalidCharacter)
+        );
+        assert_eq!(Slug::from_str("my-post-abc"), Err(IdError::TooShort));
+        assert_eq!(
+            Slug::from_str(&format!("my-post-{}", "a".repeat(MAX_PARSE_LENGTH))),
+            Err(IdError::TooLong)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let slug = Id::slug_from("Release notes", 32).expect("long enough");
+        let serialized = serde_json::to_string(&slug).expect("serializes");
+        assert_eq!(serialized, format!("\"{slug}\""));
+        let deserialized: Slug = serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, slug);
+    }
+}
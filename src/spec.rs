@@ -0,0 +1,328 @@
+// src/spec.rs
+//! Machine-readable description of the ID format, for implementations in other languages
+//!
+//! [`export_json`] describes everything needed to validate IDs: the alphabets, how input is
+//! normalized and how check characters are calculated in each [`FormatVersion`]. Ports should
+//! generate their tables from it rather than transcribing them from the source, and can prove
+//! conformance against [`test_vectors`].
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Id,
+    alphabet::{self, AMBIGUOUS_SEQUENCES, GEN_ALPHABET, TURKISH_FOLDINGS},
+    checksum::{
+        ChecksumAlgorithm, ChecksumInput, EXTENDED_WEIGHT_BASE, FormatVersion, WEIGHT_BASE,
+    },
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+};
+
+/// Version of the document produced by [`export_json`], bumped whenever its layout changes
+pub const SPEC_VERSION: u32 = 1;
+
+/// Describe the ID format as a pretty-printed JSON document
+///
+/// The document has the following fields:
+///
+/// - `spec_version`: [`SPEC_VERSION`]
+/// - `min_length`, `max_parse_length`: bounds on the length of an ID and of parser input
+/// - `generation_alphabet`: characters of an ID body, in order of their value
+/// - `normalization`: input is lowercased, except for characters in `case_foldings` which are
+///   replaced instead, then every character in `char_map` replaced, then every
+///   `ambiguous_sequences` entry replaced in a single left-to-right pass
+/// - `formats`: for each [`FormatVersion`], its checksum algorithm, whether the check character is
+///   calculated over the normalized or raw body, the check alphabet and any position weight base
+///
+/// # Example
+/// ```
+/// let spec: serde_json::Value =
+///     serde_json::from_str(&human_friendly_ids::spec::export_json()).unwrap();
+/// assert_eq!(spec["normalization"]["char_map"]["0"], "o");
+/// assert_eq!(spec["formats"]["v1"]["algorithm"], "sum");
+/// ```
+#[must_use]
+pub fn export_json() -> String {
+    let char_map = (b'!'..=b'~')
+        .map(char::from)
+        .filter(|c| !c.is_ascii_uppercase())
+        .filter_map(|c| {
+            let normalized = alphabet::normalize_char(c);
+            (normalized != c).then(|| (c.to_string(), Value::from(normalized.to_string())))
+        })
+        .collect::<Map<_, _>>();
+    let sequences = AMBIGUOUS_SEQUENCES
+        .iter()
+        .map(|(first, second, replacement)| {
+            json!({
+                "sequence": format!("{first}{second}"),
+                "replacement": replacement.to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let formats = FormatVersion::ALL
+        .iter()
+        .map(|version| (version.to_string(), format_json(*version)))
+        .collect::<Map<_, _>>();
+
+    let spec = json!({
+        "spec_version": SPEC_VERSION,
+        "min_length": MIN_LENGTH,
+        "max_parse_length": MAX_PARSE_LENGTH,
+        "generation_alphabet": GEN_ALPHABET.iter().collect::<String>(),
+        "normalization": {
+            "lowercase": true,
+            "case_foldings": TURKISH_FOLDINGS
+                .iter()
+                .map(|(from, to)| (from.to_string(), Value::from(to.to_string())))
+                .collect::<Map<_, _>>(),
+            "char_map": char_map,
+            "ambiguous_sequences": sequences,
+        },
+        "formats": formats,
+    });
+    serde_json::to_string_pretty(&spec).unwrap_or_default()
+}
+
+/// Describe how check characters are calculated in `version`
+fn format_json(version: FormatVersion) -> Value {
+    let algorithm = version.algorithm();
+    let (name, weight_base) = match algorithm {
+        ChecksumAlgorithm::Sum => ("sum", None),
+        ChecksumAlgorithm::Weighted => ("weighted", Some(WEIGHT_BASE)),
+        ChecksumAlgorithm::Extended => ("weighted", Some(EXTENDED_WEIGHT_BASE)),
+    };
+    let input = match version.checksum_input() {
+        ChecksumInput::Normalized => "normalized",
+        ChecksumInput::Raw => "raw",
+    };
+
+    json!({
+        "algorithm": name,
+        "checksum_input": input,
+        "check_alphabet": algorithm.check_alphabet().iter().collect::<String>(),
+        "weight_base": weight_base,
+    })
+}
+
+/// Expected behaviour of a conforming implementation for one input, see [`test_vectors`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    /// String as typed
+    pub input: String,
+    /// Input after normalization
+    pub normalized: String,
+    /// Check character expected after the normalized body, i.e. everything but the last character,
+    /// in [`FormatVersion::V1`], or `None` if the body has no check character
+    ///
+    /// Calculated as [`calculate_check_char`](alphabet::calculate_check_char) does, so characters
+    /// outside the alphabet count as zero even though they make the input invalid.
+    pub check_char: Option<char>,
+    /// Whether the input parses as a [`FormatVersion::V1`] ID
+    pub valid: bool,
+}
+
+impl TestVector {
+    /// Expected behaviour for `input`, as implemented by this crate
+    #[must_use]
+    pub fn new(input: impl Into<String>) -> Self {
+        let input = input.into();
+        let normalized = alphabet::normalize_string(&input);
+        let check_char = normalized
+            .char_indices()
+            .next_back()
+            .and_then(|(last, _)| normalized.get(..last))
+            .and_then(|body| alphabet::calculate_check_char(body).ok());
+        let valid = input.parse::<Id>().is_ok();
+        Self {
+            input,
+            normalized,
+            check_char,
+            valid,
+        }
+    }
+
+    /// The vector as a JSON object with the same field names, `check_char` being `null` if absent
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "input": self.input,
+            "normalized": self.normalized,
+            "check_char": self.check_char.map(String::from),
+            "valid": self.valid,
+        })
+    }
+}
+
+/// Hand-picked inputs exercising each rule, returned before any random ones
+const EDGE_CASES: &[&str] = &[
+    "",
+    "a",
+    "abc",
+    "abcd",
+    "wcfytxww4opin4jmjjes4ccfd",
+    "WCFYTXWW4OPIN4JMJJES4CCFD",
+    "wcfytxww40pin4jmjjes4ccfd",
+    "wcfytxww4opin4jmjjes4ccfa",
+    "wcfytxwwa4opin4jmjjes4ccfd",
+    "wcfytxvvvv4opin4jmjjes4ccfd",
+    "rn",
+    "rrnn",
+    "vvv",
+    "uuu",
+    "barnyard",
+    "9qg6G8B2Z5SIl170O",
+    "wcf-ytxw",
+    "wcf ytxw",
+    "wcf\u{e9}ytxw",
+    "WCFYTXWW4OP\u{130}N4JMJJES4CCFD",
+    "wcf\u{1f600}ytxw",
+];
+
+/// Characters random inputs are drawn from, the alphabet, characters normalization rewrites and
+/// characters that are always rejected
+const RANDOM_CHARS: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'h', 'i', 'j', 'k', 'm', 'n', 'o', 'p', 'r', 's', 't', 'w', 'x',
+    'y', '3', '4', 'v', 'A', 'R', 'N', 'V', '0', '1', '2', '5', '6', '7', '8', '9', 'g', 'l', 'q',
+    'u', 'z', '-', '*', '\u{e9}',
+];
+
+/// Confusable spellings of alphabet characters, substituted into valid IDs
+const CONFUSABLES: &[(char, &str)] = &[
+    ('o', "0"),
+    ('i', "1"),
+    ('i', "l"),
+    ('s', "5"),
+    ('s', "z"),
+    ('b', "8"),
+    ('b', "g"),
+    ('v', "u"),
+    ('w', "vv"),
+    ('m', "rn"),
+];
+
+/// Generate `n` test vectors, the same for a given `n` and `seed` on every platform
+///
+/// The hand-picked edge cases come first, covering ambiguous sequences, confusable characters,
+/// short strings and wrong check characters, followed by random inputs: valid IDs, valid IDs
+/// spelled with confusable characters, IDs with a single character changed and random strings.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::spec::test_vectors;
+///
+/// let vectors = test_vectors(500, 42);
+/// assert_eq!(vectors, test_vectors(500, 42));
+/// assert!(vectors.iter().any(|v| v.valid) && vectors.iter().any(|v| !v.valid));
+///
+/// let json = serde_json::Value::from_iter(vectors.iter().map(|v| v.to_json()));
+/// assert_eq!(json[0]["valid"], false);
+/// ```
+#[must_use]
+pub fn test_vectors(n: usize, seed: u64) -> Vec<TestVector> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    EDGE_CASES
+        .iter()
+        .map(|input| TestVector::new(*input))
+        .chain(std::iter::repeat_with(|| {
+            TestVector::new(random_input(&mut rng))
+        }))
+        .take(n)
+        .collect()
+}
+
+/// Index below `len`, which must not be zero
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "the result is below len, which is a usize"
+)]
+fn pick(rng: &mut impl RngCore, len: usize) -> usize {
+    (rng.next_u64() % len as u64) as usize
+}
+
+/// A random input of one of the kinds described in [`test_vectors`]
+fn random_input(rng: &mut impl RngCore) -> String {
+    let len = 4_usize.saturating_add(pick(rng, 22));
+    let id = Id::new_with_rng(len, rng);
+    match pick(rng, 4) {
+        0 => id.to_string(),
+        1 => {
+            let mut confused = String::with_capacity(len.saturating_mul(2));
+            for c in id.chars() {
+                let mut spellings = CONFUSABLES.iter().filter(|(from, _)| *from == c);
+                match spellings.nth(pick(rng, 2)) {
+                    Some((_, spelling)) if pick(rng, 3) == 0 => confused.push_str(spelling),
+                    _ if pick(rng, 4) == 0 => confused.push(c.to_ascii_uppercase()),
+                    _ => confused.push(c),
+                }
+            }
+            confused
+        }
+        2 => {
+            let position = pick(rng, len);
+            let replacement = RANDOM_CHARS[pick(rng, RANDOM_CHARS.len())];
+            id.chars()
+                .enumerate()
+                .map(|(i, c)| if i == position { replacement } else { c })
+                .collect()
+        }
+        _ => (0..pick(rng, 9))
+            .map(|_| RANDOM_CHARS[pick(rng, RANDOM_CHARS.len())])
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_spec() {
+        // Ports generate their tables from this, so any change must be caught in review.
+        insta::assert_snapshot!(export_json());
+    }
+
+    #[test]
+    fn char_map_matches_normalization() {
+        let spec: Value = serde_json::from_str(&export_json()).expect("spec is valid JSON");
+        let char_map = spec
+            .pointer("/normalization/char_map")
+            .and_then(Value::as_object)
+            .expect("char map is an object");
+        for c in (b'!'..=b'~').map(char::from) {
+            let expected = alphabet::normalize_char(c.to_ascii_lowercase());
+            let mapped = char_map
+                .get(&c.to_ascii_lowercase().to_string())
+                .and_then(Value::as_str)
+                .and_then(|s| s.chars().next())
+                .unwrap_or(c.to_ascii_lowercase());
+            assert_eq!(mapped, expected, "{c:?}");
+        }
+    }
+
+    #[test]
+    fn test_vectors_are_deterministic_and_cover_edge_cases() {
+        let vectors = test_vectors(1_000, 7);
+        assert_eq!(vectors.len(), 1_000);
+        assert_eq!(vectors, test_vectors(1_000, 7));
+        assert_ne!(vectors, test_vectors(1_000, 8));
+        assert_eq!(test_vectors(3, 7).len(), 3);
+
+        let valid = vectors.iter().filter(|v| v.valid).count();
+        assert!(valid > 200 && valid < 800, "{valid} valid vectors");
+        for vector in &vectors {
+            assert_eq!(*vector, TestVector::new(vector.input.clone()));
+            if vector.valid {
+                assert_eq!(vector.normalized.chars().next_back(), vector.check_char);
+            }
+        }
+        insta::assert_json_snapshot!(
+            vectors
+                .iter()
+                .take(EDGE_CASES.len())
+                .map(TestVector::to_json)
+                .collect::<Vec<_>>()
+        );
+    }
+}
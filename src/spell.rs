@@ -0,0 +1,420 @@
+// src/spell.rs
+//! Spelling IDs out character by character, for screen readers and reading IDs aloud
+//!
+//! Screen readers try to pronounce an ID such as `wcfytxww` as a word, or read it in one breath,
+//! so users relying on them can't tell which characters it is made of. [`Id::spelled`] formats an
+//! ID as a comma-separated list that screen readers pause between, e.g. for an `aria-label`, with
+//! code words for characters that are easily misheard. Code words come from the NATO alphabet by
+//! default, or from a [`SpellingAlphabet`] matching the listener's language.
+
+use std::fmt;
+
+use crate::Id;
+
+/// How many characters are spelled with a code word, e.g. `f as in foxtrot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// Only the characters, `w, c, f, y`
+    Characters,
+    /// Code words for characters that sound alike, such as `b`, `d` and `p`, or `m` and `n`
+    #[default]
+    SoundAlike,
+    /// Code words for every letter, and digits as words
+    Full,
+}
+
+/// Letters that are easily misheard for another letter of the alphabet
+const SOUND_ALIKE: [char; 11] = ['b', 'd', 'e', 'p', 't', 'v', 'f', 's', 'x', 'm', 'n'];
+
+/// Characters and the words they are spelled with
+type Table<const N: usize> = [(char, &'static str); N];
+
+/// Code words of the NATO phonetic alphabet, for every letter of the alphabet
+const NATO: Table<21> = [
+    ('a', "alfa"),
+    ('b', "bravo"),
+    ('c', "charlie"),
+    ('d', "delta"),
+    ('e', "echo"),
+    ('f', "foxtrot"),
+    ('h', "hotel"),
+    ('i', "india"),
+    ('j', "juliett"),
+    ('k', "kilo"),
+    ('m', "mike"),
+    ('n', "november"),
+    ('o', "oscar"),
+    ('p', "papa"),
+    ('r', "romeo"),
+    ('s', "sierra"),
+    ('t', "tango"),
+    ('w', "whiskey"),
+    ('x', "x-ray"),
+    ('y', "yankee"),
+    ('v', "victor"),
+];
+
+/// Code words of the German spelling alphabet, in the variant using first names throughout
+const GERMAN: Table<21> = [
+    ('a', "Anna"),
+    ('b', "Berta"),
+    ('c', "Carl"),
+    ('d', "Daniel"),
+    ('e', "Emil"),
+    ('f', "Friedrich"),
+    ('h', "Heinrich"),
+    ('i', "Ida"),
+    ('j', "Jakob"),
+    ('k', "Kaiser"),
+    ('m', "Marie"),
+    ('n', "Niklaus"),
+    ('o', "Otto"),
+    ('p', "Peter"),
+    ('r', "Rosa"),
+    ('s', "Sophie"),
+    ('t', "Theodor"),
+    ('w', "Wilhelm"),
+    ('x', "Xaver"),
+    ('y', "Yverdon"),
+    ('v', "Viktor"),
+];
+
+/// Code words of the French spelling alphabet
+const FRENCH: Table<21> = [
+    ('a', "Anatole"),
+    ('b', "Berthe"),
+    ('c', "C\u{e9}lestin"),
+    ('d', "D\u{e9}sir\u{e9}"),
+    ('e', "Eug\u{e8}ne"),
+    ('f', "Fran\u{e7}ois"),
+    ('h', "Henri"),
+    ('i', "Irma"),
+    ('j', "Joseph"),
+    ('k', "Kl\u{e9}ber"),
+    ('m', "Marcel"),
+    ('n', "Nicolas"),
+    ('o', "Oscar"),
+    ('p', "Pierre"),
+    ('r', "Raoul"),
+    ('s', "Suzanne"),
+    ('t', "Th\u{e9}r\u{e8}se"),
+    ('w', "William"),
+    ('x', "Xavier"),
+    ('y', "Yvonne"),
+    ('v', "Victor"),
+];
+
+/// Code words of the Spanish spelling alphabet
+const SPANISH: Table<21> = [
+    ('a', "Antonio"),
+    ('b', "Barcelona"),
+    ('c', "Carmen"),
+    ('d', "Dolores"),
+    ('e', "Enrique"),
+    ('f', "Francia"),
+    ('h', "Historia"),
+    ('i', "In\u{e9}s"),
+    ('j', "Jos\u{e9}"),
+    ('k', "Kilo"),
+    ('m', "Madrid"),
+    ('n', "Navarra"),
+    ('o', "Oviedo"),
+    ('p', "Par\u{ed}s"),
+    ('r', "Ram\u{f3}n"),
+    ('s', "S\u{e1}bado"),
+    ('t', "Tarragona"),
+    ('w', "Washington"),
+    ('x', "Xil\u{f3}fono"),
+    ('y', "Yegua"),
+    ('v', "Valencia"),
+];
+
+/// Names of digits and of the symbols check characters may use, which screen readers may
+/// otherwise skip
+const ENGLISH_NAMES: Table<8> = [
+    ('3', "three"),
+    ('4', "four"),
+    ('*', "star"),
+    ('=', "equals"),
+    ('+', "plus"),
+    ('#', "hash"),
+    ('%', "percent"),
+    ('~', "tilde"),
+];
+
+/// German names of digits and symbols, see [`ENGLISH_NAMES`]
+const GERMAN_NAMES: Table<8> = [
+    ('3', "drei"),
+    ('4', "vier"),
+    ('*', "Stern"),
+    ('=', "gleich"),
+    ('+', "plus"),
+    ('#', "Raute"),
+    ('%', "Prozent"),
+    ('~', "Tilde"),
+];
+
+/// French names of digits and symbols, see [`ENGLISH_NAMES`]
+const FRENCH_NAMES: Table<8> = [
+    ('3', "trois"),
+    ('4', "quatre"),
+    ('*', "\u{e9}toile"),
+    ('=', "\u{e9}gal"),
+    ('+', "plus"),
+    ('#', "di\u{e8}se"),
+    ('%', "pour cent"),
+    ('~', "tilde"),
+];
+
+/// Spanish names of digits and symbols, see [`ENGLISH_NAMES`]
+const SPANISH_NAMES: Table<8> = [
+    ('3', "tres"),
+    ('4', "cuatro"),
+    ('*', "asterisco"),
+    ('=', "igual"),
+    ('+', "m\u{e1}s"),
+    ('#', "almohadilla"),
+    ('%', "por ciento"),
+    ('~', "virgulilla"),
+];
+
+/// Look `c` up in `table`
+fn lookup<const N: usize>(table: &Table<N>, c: char) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == c).map(|(_, word)| *word)
+}
+
+/// Language of the code words and names used to spell an ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SpellingAlphabet {
+    /// The NATO phonetic alphabet, with English names for digits, `alfa`, `bravo`, ...
+    #[default]
+    Nato,
+    /// The German spelling alphabet, `Anna`, `Berta`, ...
+    German,
+    /// The French spelling alphabet, `Anatole`, `Berthe`, ...
+    French,
+    /// The Spanish spelling alphabet, `Antonio`, `Barcelona`, ...
+    Spanish,
+}
+
+impl SpellingAlphabet {
+    /// Every available spelling alphabet
+    pub const ALL: &'static [Self] = &[Self::Nato, Self::German, Self::French, Self::Spanish];
+
+    /// Pick the spelling alphabet for a locale such as `de`, `fr-CA` or `es_MX`
+    ///
+    /// Only the language is considered, falling back to [`SpellingAlphabet::Nato`] for languages
+    /// without a built-in alphabet.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::spell::SpellingAlphabet;
+    ///
+    /// assert_eq!(SpellingAlphabet::for_locale("de-AT"), SpellingAlphabet::German);
+    /// assert_eq!(SpellingAlphabet::for_locale("es_MX"), SpellingAlphabet::Spanish);
+    /// assert_eq!(SpellingAlphabet::for_locale("ja"), SpellingAlphabet::Nato);
+    /// ```
+    #[must_use]
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "de" => Self::German,
+            "fr" => Self::French,
+            "es" => Self::Spanish,
+            _ => Self::Nato,
+        }
+    }
+
+    /// Code word for a letter, e.g. `foxtrot` for `f`
+    #[must_use]
+    pub fn code_word(self, c: char) -> Option<&'static str> {
+        match self {
+            Self::Nato => lookup(&NATO, c),
+            Self::German => lookup(&GERMAN, c),
+            Self::French => lookup(&FRENCH, c),
+            Self::Spanish => lookup(&SPANISH, c),
+        }
+    }
+
+    /// Name of a digit or symbol, e.g. `three` for `3`
+    #[must_use]
+    pub fn name(self, c: char) -> Option<&'static str> {
+        match self {
+            Self::Nato => lookup(&ENGLISH_NAMES, c),
+            Self::German => lookup(&GERMAN_NAMES, c),
+            Self::French => lookup(&FRENCH_NAMES, c),
+            Self::Spanish => lookup(&SPANISH_NAMES, c),
+        }
+    }
+
+    /// Words joining a letter to its code word, as in `f as in foxtrot`
+    const fn connective(self) -> &'static str {
+        match self {
+            Self::Nato => "as in",
+            Self::German => "wie",
+            Self::French => "comme",
+            Self::Spanish => "de",
+        }
+    }
+}
+
+/// An ID spelled out character by character, see [`Id::spelled`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spelled<'a> {
+    /// Characters to spell
+    id: &'a str,
+    /// Which characters get a code word
+    verbosity: Verbosity,
+    /// Where code words and names come from
+    alphabet: SpellingAlphabet,
+}
+
+impl Spelled<'_> {
+    /// Use code words and names from `alphabet` instead of the NATO alphabet
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{
+    ///     Id,
+    ///     spell::{SpellingAlphabet, Verbosity},
+    /// };
+    ///
+    /// let id: Id = "wcfy3".parse().unwrap();
+    /// let spelled = id
+    ///     .spelled(Verbosity::SoundAlike)
+    ///     .with_alphabet(SpellingAlphabet::for_locale("de"));
+    /// assert_eq!(spelled.to_string(), "w, c, f wie Friedrich, y, 3");
+    /// ```
+    #[must_use]
+    pub const fn with_alphabet(mut self, alphabet: SpellingAlphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+}
+
+impl Id {
+    /// Spell this ID out, e.g. `w, c, f as in foxtrot, y` for screen readers
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::{Id, spell::Verbosity};
+    ///
+    /// let id: Id = "wcfy3".parse().unwrap();
+    /// assert_eq!(
+    ///     id.spelled(Verbosity::SoundAlike).to_string(),
+    ///     "w, c, f as in foxtrot, y, 3"
+    /// );
+    /// assert_eq!(id.spelled(Verbosity::Characters).to_string(), "w, c, f, y, 3");
+    /// assert_eq!(
+    ///     id.spelled(Verbosity::Full).to_string(),
+    ///     "w as in whiskey, c as in charlie, f as in foxtrot, y as in yankee, three"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn spelled(&self, verbosity: Verbosity) -> Spelled<'_> {
+        Spelled {
+            id: self.as_str(),
+            verbosity,
+            alphabet: SpellingAlphabet::Nato,
+        }
+    }
+}
+
+impl fmt::Display for Spelled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.id.chars().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            let name = self.alphabet.name(c);
+            let code_word = self.alphabet.code_word(c);
+            let connective = self.alphabet.connective();
+            match (self.verbosity, name, code_word) {
+                (Verbosity::Full, Some(name), _) => f.write_str(name)?,
+                (_, Some(name), _) if !c.is_ascii_digit() => f.write_str(name)?,
+                (Verbosity::Full, _, Some(word)) => write!(f, "{c} {connective} {word}")?,
+                (Verbosity::SoundAlike, _, Some(word)) if SOUND_ALIKE.contains(&c) => {
+                    write!(f, "{c} {connective} {word}")?;
+                }
+                _ => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alphabet::EXTENDED_CHECK_ALPHABET, checksum::FormatVersion};
+
+    #[test]
+    fn every_character_has_a_spelling() {
+        for alphabet in SpellingAlphabet::ALL {
+            for c in EXTENDED_CHECK_ALPHABET {
+                assert!(
+                    alphabet.name(c).is_some() != alphabet.code_word(c).is_some(),
+                    "{alphabet:?} {c:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn spells_every_character_once() {
+        for verbosity in [
+            Verbosity::Characters,
+            Verbosity::SoundAlike,
+            Verbosity::Full,
+        ] {
+            for _ in 0..100 {
+                let id = crate::generator::IdGenerator::new(12)
+                    .with_format(FormatVersion::V3)
+                    .generate();
+                let spelled = id.spelled(verbosity).to_string();
+                assert_eq!(spelled.split(", ").count(), id.len(), "{spelled}");
+            }
+        }
+    }
+
+    #[test]
+    fn symbols_are_named() {
+        let id = std::iter::repeat_with(|| {
+            crate::builder::IdBody::from(&crate::Id::new(8)).seal_as(FormatVersion::V3)
+        })
+        .flatten()
+        .find(|id| !id.check_char().is_ascii_alphanumeric())
+        .expect("a sixth of check characters are symbols");
+        let name = SpellingAlphabet::Nato
+            .name(id.check_char())
+            .expect("every symbol has a name");
+        for verbosity in [
+            Verbosity::Characters,
+            Verbosity::SoundAlike,
+            Verbosity::Full,
+        ] {
+            let spelled = id.spelled(verbosity).to_string();
+            assert!(spelled.ends_with(&format!(", {name}")), "{spelled}");
+        }
+    }
+
+    #[test]
+    fn localized_spellings() {
+        let id: crate::Id = "wcfy3".parse().expect("valid ID");
+        let spelled = |locale| {
+            id.spelled(Verbosity::Full)
+                .with_alphabet(SpellingAlphabet::for_locale(locale))
+                .to_string()
+        };
+        assert_eq!(
+            spelled("fr-FR"),
+            "w comme William, c comme C\u{e9}lestin, f comme Fran\u{e7}ois, y comme Yvonne, trois"
+        );
+        assert_eq!(
+            spelled("ES"),
+            "w de Washington, c de Carmen, f de Francia, y de Yegua, tres"
+        );
+        assert_eq!(spelled(""), id.spelled(Verbosity::Full).to_string());
+    }
+}
@@ -0,0 +1,405 @@
+// src/state.rs
+//! Persistent state for generators deriving IDs from a clock and a counter
+//!
+//! Time-ordered and sequential schemes, such as snowflake-style IDs built with
+//! [`Id::from_bytes`](crate::Id::from_bytes), repeat IDs if they restart with a clock that has
+//! gone backwards or a counter that has been reset. Saving a [`GeneratorState`] to a
+//! [`StateStore`] before handing out IDs, and loading it on start, rules that out.
+//!
+//! This module only keeps the state, it doesn't generate IDs: encode the `(timestamp, sequence)`
+//! pairs from [`GeneratorState::advance`] however the scheme needs, as below.
+//!
+//! [`MemoryStateStore`] suits tests and single-process use, and [`FileStateStore`] survives
+//! restarts on one machine. Implement [`StateStore`] to keep state in a shared service such as
+//! etcd or Redis, e.g. to lease node IDs to a fleet.
+//!
+//! # Example
+//! ```
+//! use std::time::{SystemTime, UNIX_EPOCH};
+//!
+//! use human_friendly_ids::{
+//!     Id,
+//!     state::{FileStateStore, GeneratorState, StateStore},
+//! };
+//!
+//! /// Issue a snowflake-style ID: 48 bits of milliseconds, a 16 bit node and a 32 bit sequence
+//! fn issue(store: &impl StateStore, state: &mut GeneratorState) -> std::io::Result<Id> {
+//!     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+//!     let (timestamp, sequence) = state.advance(now).expect("under 2^32 IDs per millisecond");
+//!     store.save(state)?;
+//!
+//!     let mut bytes = timestamp.to_be_bytes()[2..].to_vec();
+//!     bytes.extend_from_slice(&state.node_id.to_be_bytes());
+//!     bytes.extend_from_slice(&sequence.to_be_bytes());
+//!     Ok(Id::from_bytes(&bytes).expect("twelve bytes"))
+//! }
+//!
+//! let path = std::env::temp_dir().join(format!("snowflake-{}.state", std::process::id()));
+//! let store = FileStateStore::new(&path);
+//!
+//! let mut state = store.load()?.unwrap_or(GeneratorState::new(7));
+//! let first = issue(&store, &mut state)?;
+//!
+//! // After a restart, the saved state keeps new IDs distinct even if the clock went backwards.
+//! let mut state = store.load()?.expect("saved above");
+//! state.last_timestamp += 60_000;
+//! let second = issue(&store, &mut state)?;
+//! assert_ne!(first, second);
+//! # std::fs::remove_file(path)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Distinguishes temporary files written by concurrent saves within this process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Everything a clock-and-counter generator needs to resume without repeating IDs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GeneratorState {
+    /// Identifies this generator among all those issuing IDs in the same space
+    pub node_id: u16,
+    /// Timestamp of the last ID issued, in milliseconds since the Unix epoch
+    pub last_timestamp: u64,
+    /// Counter distinguishing IDs issued within `last_timestamp`
+    pub sequence: u32,
+}
+
+impl GeneratorState {
+    /// Fresh state for `node_id`, before any IDs have been issued
+    #[must_use]
+    pub const fn new(node_id: u16) -> Self {
+        Self {
+            node_id,
+            last_timestamp: 0,
+            sequence: 0,
+        }
+    }
+
+    /// Advance to the next `(timestamp, sequence)` pair to issue an ID with, given the current time
+    ///
+    /// If the clock has not moved past `last_timestamp`, including if it has gone backwards since
+    /// the state was saved, the sequence is incremented instead. Returns `None` once the sequence is
+    /// exhausted, in which case wait for the clock to advance.
+    ///
+    /// # Example
+    /// ```
+    /// use human_friendly_ids::state::GeneratorState;
+    ///
+    /// let mut state = GeneratorState::new(7);
+    /// assert_eq!(state.advance(1_000), Some((1_000, 0)));
+    /// assert_eq!(state.advance(1_000), Some((1_000, 1)));
+    /// // The clock went backwards, e.g. after a restart
+    /// assert_eq!(state.advance(900), Some((1_000, 2)));
+    /// assert_eq!(state.advance(1_001), Some((1_001, 0)));
+    /// ```
+    pub fn advance(&mut self, now: u64) -> Option<(u64, u32)> {
+        if now > self.last_timestamp {
+            self.last_timestamp = now;
+            self.sequence = 0;
+        } else {
+            self.sequence = self.sequence.checked_add(1)?;
+        }
+        Some((self.last_timestamp, self.sequence))
+    }
+}
+
+/// Storage for a [`GeneratorState`] that outlives the generator
+///
+/// Generators should [`load`](StateStore::load) once on start, and
+/// [`save`](StateStore::save) before handing out IDs issued with a new state. Saving ahead, e.g.
+/// a second into the future, trades a gap in the timestamps after a crash for fewer writes.
+pub trait StateStore {
+    /// The saved state, or `None` if nothing has been saved yet
+    ///
+    /// ## Errors
+    ///
+    /// If the state can't be read, or what was read isn't a valid state.
+    fn load(&self) -> io::Result<Option<GeneratorState>>;
+
+    /// Replace the saved state with `state`
+    ///
+    /// ## Errors
+    ///
+    /// If the state can't be written. The previously saved state must then still be loadable.
+    fn save(&self, state: &GeneratorState) -> io::Result<()>;
+}
+
+/// State kept in memory, lost when the process exits
+#[derive(Debug, Default)]
+pub struct MemoryStateStore(Mutex<Option<GeneratorState>>);
+
+impl MemoryStateStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateStore for MemoryStateStore {
+    fn load(&self) -> io::Result<Option<GeneratorState>> {
+        Ok(*self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn save(&self, state: &GeneratorState) -> io::Result<()> {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(*state);
+        Ok(())
+    }
+}
+
+/// State kept in a small text file, replaced atomically on every save
+///
+/// The file holds one `key=value` line per field of [`GeneratorState`], so it can be inspected
+/// or seeded by hand. Each save writes a temporary file unique to the process and call, syncs it,
+/// renames it over the state file and, on Unix, syncs the directory, so concurrent saves never
+/// interleave and a crash leaves either the old or the new state.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::state::{FileStateStore, GeneratorState, StateStore};
+///
+/// let path = std::env::temp_dir().join(format!("generator-{}.state", std::process::id()));
+/// let store = FileStateStore::new(&path);
+/// assert_eq!(store.load().unwrap(), None);
+///
+/// let mut state = GeneratorState::new(3);
+/// state.advance(1_700_000_000_000);
+/// store.save(&state).unwrap();
+/// assert_eq!(FileStateStore::new(&path).load().unwrap(), Some(state));
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStateStore {
+    /// File holding the state
+    path: PathBuf,
+}
+
+impl FileStateStore {
+    /// Keep state in the file at `path`, which is created on the first save
+    #[must_use]
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// File holding the state
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// File written before replacing the state, so a crash mid-write leaves the old state intact
+    ///
+    /// Unique to this process and call, so concurrent saves don't write to the same file.
+    fn temp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.path.with_file_name(name)
+    }
+
+    /// Write `contents` to `temp` and rename it over the state file
+    fn replace(&self, temp: &Path, contents: &str) -> io::Result<()> {
+        let mut file = fs::File::create(temp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(temp, &self.path)?;
+
+        // The rename itself is only durable once the directory entry is.
+        #[cfg(unix)]
+        {
+            let dir = self
+                .path
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+impl StateStore for FileStateStore {
+    fn load(&self) -> io::Result<Option<GeneratorState>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut node_id = None;
+        let mut last_timestamp = None;
+        let mut sequence = None;
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line.split_once('=').ok_or_else(|| invalid(line))?;
+            match key.trim() {
+                "node_id" => node_id = Some(parse_field(line, value)?),
+                "last_timestamp" => last_timestamp = Some(parse_field(line, value)?),
+                "sequence" => sequence = Some(parse_field(line, value)?),
+                _ => return Err(invalid(line)),
+            }
+        }
+
+        match (node_id, last_timestamp, sequence) {
+            (Some(node_id), Some(last_timestamp), Some(sequence)) => Ok(Some(GeneratorState {
+                node_id,
+                last_timestamp,
+                sequence,
+            })),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("incomplete generator state in {}", self.path.display()),
+            )),
+        }
+    }
+
+    fn save(&self, state: &GeneratorState) -> io::Result<()> {
+        let contents = format!(
+            "node_id={}\nlast_timestamp={}\nsequence={}\n",
+            state.node_id, state.last_timestamp, state.sequence
+        );
+        let temp = self.temp_path();
+        self.replace(&temp, &contents).inspect_err(|_| {
+            // Best effort, the state file itself is untouched.
+            let _ = fs::remove_file(&temp);
+        })
+    }
+}
+
+/// Parse the value of `line` in a state file
+fn parse_field<T: FromStr>(line: &str, value: &str) -> io::Result<T> {
+    value.trim().parse().map_err(|_| invalid(line))
+}
+
+/// Error for a line of a state file that can't be understood
+fn invalid(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid generator state line: {line:?}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path in the temporary directory unique to this process and `name`
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("hfid-{}-{name}", std::process::id()))
+    }
+
+    /// Number of temporary files left next to the state file at `path`
+    fn leftover_temp_files(path: &Path) -> usize {
+        let prefix = format!("{}.", path.file_name().unwrap().to_string_lossy());
+        fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&prefix)
+            })
+            .count()
+    }
+
+    #[test]
+    fn stores_round_trip_state() {
+        let path = temp_file("round-trip");
+        let stores: [Box<dyn StateStore>; 2] = [
+            Box::new(MemoryStateStore::new()),
+            Box::new(FileStateStore::new(&path)),
+        ];
+        for store in stores {
+            assert_eq!(store.load().unwrap(), None);
+            let mut state = GeneratorState::new(513);
+            for now in [10, 10, 12] {
+                state.advance(now);
+                store.save(&state).unwrap();
+                assert_eq!(store.load().unwrap(), Some(state));
+            }
+        }
+        assert_eq!(leftover_temp_files(&path), 0);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn concurrent_saves_do_not_clash() {
+        let path = temp_file("concurrent");
+        std::thread::scope(|scope| {
+            for node_id in 0..4 {
+                let store = FileStateStore::new(&path);
+                scope.spawn(move || {
+                    let mut state = GeneratorState::new(node_id);
+                    for now in 0..50 {
+                        state.advance(now);
+                        store.save(&state).unwrap();
+                    }
+                });
+            }
+        });
+
+        let state = FileStateStore::new(&path).load().unwrap().unwrap();
+        assert!(state.node_id < 4);
+        assert_eq!(state.last_timestamp, 49);
+        assert_eq!(leftover_temp_files(&path), 0);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn resuming_never_repeats() {
+        let store = MemoryStateStore::new();
+        let mut issued = Vec::new();
+        let mut state = GeneratorState::new(1);
+        for now in [5, 5, 5, 6] {
+            issued.push(state.advance(now).unwrap());
+        }
+        store.save(&state).unwrap();
+
+        // Restart with a clock that has gone backwards.
+        let mut state = store.load().unwrap().unwrap();
+        for now in [3, 4, 7] {
+            let next = state.advance(now).unwrap();
+            assert!(next > *issued.last().unwrap());
+            issued.push(next);
+        }
+
+        let mut exhausted = GeneratorState {
+            sequence: u32::MAX,
+            ..GeneratorState::new(1)
+        };
+        assert_eq!(exhausted.advance(0), None);
+        assert_eq!(exhausted.advance(1), Some((1, 0)));
+    }
+
+    #[test]
+    fn rejects_corrupt_files() {
+        let path = temp_file("corrupt");
+        let store = FileStateStore::new(&path);
+        for contents in [
+            "node_id=1\n",
+            "node_id=1\nlast_timestamp=x\nsequence=0\n",
+            "nonsense",
+        ] {
+            fs::write(&path, contents).unwrap();
+            let error = store.load().unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{contents:?}");
+        }
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+// src/suggest.rs
+//! "Did you mean" suggestions for IDs that fail validation
+//!
+//! Hand-typed IDs most often fail because a neighbouring key was hit instead of the intended one.
+//! Candidates are generated by swapping each typed character for its keyboard neighbours, ranked
+//! by how close the keys are, and only then re-verified against the check character.
+
+use crate::{Id, checksum::FormatVersion};
+
+/// Weight of a neighbouring key on the same row
+const ROW_NEIGHBOUR_WEIGHT: f32 = 1.0;
+
+/// Weight of a neighbouring key on the row above or below
+const COLUMN_NEIGHBOUR_WEIGHT: f32 = 0.5;
+
+/// Keyboard layout used to decide which keys are adjacent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyboardLayout {
+    /// US/UK QWERTY layout
+    #[default]
+    Qwerty,
+    /// French AZERTY layout
+    Azerty,
+}
+
+impl KeyboardLayout {
+    /// Rows of the layout, top to bottom, each row staggered half a key right of the one above
+    const fn rows(self) -> &'static [&'static str] {
+        match self {
+            Self::Qwerty => &["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            Self::Azerty => &["1234567890", "azertyuiop", "qsdfghjklm", "wxcvbn"],
+        }
+    }
+
+    /// Keys adjacent to `c` along with how likely they are to be hit by mistake
+    fn neighbours(self, c: char) -> Vec<(char, f32)> {
+        let rows = self.rows();
+        let Some((row, col)) = rows
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.chars().position(|k| k == c).map(|col| (row, col)))
+        else {
+            return Vec::new();
+        };
+
+        let key_at = |row: Option<usize>, col: Option<usize>| {
+            rows.get(row?).and_then(|keys| keys.chars().nth(col?))
+        };
+
+        [
+            (key_at(Some(row), col.checked_sub(1)), ROW_NEIGHBOUR_WEIGHT),
+            (key_at(Some(row), col.checked_add(1)), ROW_NEIGHBOUR_WEIGHT),
+            (
+                key_at(row.checked_sub(1), Some(col)),
+                COLUMN_NEIGHBOUR_WEIGHT,
+            ),
+            (
+                key_at(row.checked_sub(1), col.checked_add(1)),
+                COLUMN_NEIGHBOUR_WEIGHT,
+            ),
+            (
+                key_at(row.checked_add(1), col.checked_sub(1)),
+                COLUMN_NEIGHBOUR_WEIGHT,
+            ),
+            (
+                key_at(row.checked_add(1), Some(col)),
+                COLUMN_NEIGHBOUR_WEIGHT,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(key, weight)| key.map(|key| (key, weight)))
+        .collect()
+    }
+}
+
+/// Suggest corrections for an ID that failed validation
+///
+/// Every single-key slip on the given layout is tried, and candidates which pass the check
+/// character are returned best first. If `input` is already a valid ID it is returned as the only
+/// suggestion.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, suggest::{suggest, KeyboardLayout}};
+///
+/// // The final 'd' was typed as an 's'.
+/// let suggestions = suggest("wcfytxww4opin4jmjjes4ccfs", KeyboardLayout::Qwerty);
+/// assert!(suggestions.contains(&"wcfytxww4opin4jmjjes4ccfd".parse::<Id>().unwrap()));
+/// ```
+#[must_use]
+pub fn suggest(input: &str, layout: KeyboardLayout) -> Vec<Id> {
+    if let Ok(id) = Id::parse_versioned(input, FormatVersion::V1) {
+        return vec![id];
+    }
+
+    let typed = input.to_lowercase();
+    let mut candidates: Vec<(Id, f32)> = Vec::new();
+
+    for (offset, c) in typed.char_indices() {
+        for (replacement, weight) in layout.neighbours(c) {
+            let mut candidate = String::with_capacity(typed.len());
+            candidate.push_str(typed.get(..offset).unwrap_or_default());
+            candidate.push(replacement);
+            candidate.push_str(
+                typed
+                    .get(offset.saturating_add(c.len_utf8())..)
+                    .unwrap_or_default(),
+            );
+
+            let Ok(id) = Id::parse_versioned(&candidate, FormatVersion::V1) else {
+                continue;
+            };
+
+            match candidates.iter_mut().find(|(existing, _)| *existing == id) {
+                Some((_, best)) => *best = best.max(weight),
+                None => candidates.push((id, weight)),
+            }
+        }
+    }
+
+    // Stable sort, so equally weighted candidates keep their left-to-right order.
+    candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    candidates.into_iter().map(|(id, _)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn valid_input_is_returned_as_is() {
+        let suggestions = suggest("wcfytxww4opin4jmjjes4ccfd", KeyboardLayout::Qwerty);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].as_str(), "wcfytxww4opin4jmjjes4ccfd");
+    }
+
+    #[test]
+    fn adjacent_key_slip_is_suggested() {
+        // 'd' typed as its neighbour 's'
+        let suggestions = suggest("wcfytxww4opin4jmjjes4ccfs", KeyboardLayout::Qwerty);
+        assert!(
+            suggestions
+                .iter()
+                .any(|id| id.as_str() == "wcfytxww4opin4jmjjes4ccfd"),
+            "expected original ID in {suggestions:?}"
+        );
+        for suggestion in &suggestions {
+            assert!(
+                Id::from_str(suggestion).is_ok(),
+                "suggestions must validate"
+            );
+        }
+    }
+
+    #[test]
+    fn layouts_differ() {
+        assert!(
+            KeyboardLayout::Qwerty
+                .neighbours('a')
+                .iter()
+                .any(|(c, _)| *c == 's')
+        );
+        assert!(
+            KeyboardLayout::Azerty
+                .neighbours('a')
+                .iter()
+                .any(|(c, _)| *c == 'z')
+        );
+        assert!(KeyboardLayout::Qwerty.neighbours('🦀').is_empty());
+    }
+
+    #[test]
+    fn same_row_ranks_above_other_rows() {
+        let neighbours = KeyboardLayout::Qwerty.neighbours('s');
+        let weight = |key| {
+            neighbours
+                .iter()
+                .find(|(c, _)| *c == key)
+                .map(|(_, w)| *w)
+                .expect("key should be adjacent")
+        };
+        assert!(weight('a') > weight('w'));
+        assert!(weight('d') > weight('x'));
+    }
+}
@@ -0,0 +1,335 @@
+// src/tagged.rs
+//! IDs carrying a short tag, such as a tenant or region, e.g. `eu-wcfytd3`
+//!
+//! Unlike a [`crate::composite::CompositeId`] segment kind, the tag is covered by the check
+//! character, so a mistyped tag is usually caught.
+//!
+//! The check character is not a signature: anyone can recompute it after editing the tag. It also
+//! has only 23 values, so about one in 23 pairs of tags give every body the same check character,
+//! e.g. `fr` and `jp`, and an ID stays valid when moved between them. Servers must still look up
+//! which tenant or region each ID belongs to.
+
+use std::{fmt, str::FromStr};
+
+use rand_core::RngCore;
+use thiserror::Error;
+
+use crate::{
+    alphabet::{self, CHECK_ALPHABET},
+    error::IdError,
+    generator::IdGenerator,
+    id::{MAX_PARSE_LENGTH, MIN_LENGTH},
+};
+
+/// Separator between the tag and the rest of the ID
+pub const TAG_SEPARATOR: char = '-';
+
+/// Maximum length of a tag
+pub const MAX_TAG_LENGTH: usize = 8;
+
+/// Why a tagged ID was rejected
+#[derive(Error, Debug, PartialEq, Eq, Clone, Hash)]
+pub enum TagError {
+    /// The part after the tag is invalid
+    #[error(transparent)]
+    Id(#[from] IdError),
+    /// There is no separator between the tag and the rest of the ID
+    #[error("Tag separator is missing")]
+    MissingSeparator,
+    /// The tag is empty
+    #[error("Tag is empty")]
+    EmptyTag,
+    /// The tag is longer than [`MAX_TAG_LENGTH`] or contains anything but ASCII letters and digits
+    #[error("Invalid tag")]
+    InvalidTag,
+    /// There are more parts separated by [`TAG_SEPARATOR`] than the format has
+    #[error("Too many parts")]
+    TooManyParts,
+}
+
+impl TagError {
+    /// Stable, machine readable code for the error, see [`IdError::code`]
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Id(e) => e.code(),
+            Self::MissingSeparator => "missing_separator",
+            Self::EmptyTag => "empty_tag",
+            Self::InvalidTag => "invalid_tag",
+            Self::TooManyParts => "too_many_parts",
+        }
+    }
+}
+
+/// An ID whose check character also covers a short tag, e.g. `eu-wcfytd3`
+///
+/// The tag is made of ASCII letters and digits and is stored lowercase. It is not normalized like
+/// the rest of the ID, so tags such as `eu` or `us` keep their usual spelling.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::tagged::TaggedId;
+///
+/// let id = TaggedId::new("eu", 10).unwrap();
+/// assert!(id.to_string().starts_with("eu-"));
+///
+/// let parsed: TaggedId = id.to_string().to_uppercase().parse().unwrap();
+/// assert_eq!(parsed.tag(), "eu");
+/// assert_eq!(parsed, id);
+///
+/// // A mistyped tag is caught.
+/// let mistyped = id.to_string().replacen("eu", "ev", 1);
+/// assert!(mistyped.parse::<TaggedId>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaggedId {
+    /// Lowercase tag
+    tag: String,
+    /// Normalized body followed by the check character
+    id: String,
+}
+
+impl TaggedId {
+    /// Generate a new tagged ID, where `len` is the length after the separator
+    ///
+    /// This method uses the default RNG backend, see [`crate::rng`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`TaggedId::new_with_rng`].
+    #[cfg(any(feature = "rand", feature = "getrandom", feature = "fastrand"))]
+    pub fn new(tag: &str, len: usize) -> Result<Self, TagError> {
+        let mut rng = crate::rng::default_rng();
+        Self::new_with_rng(tag, len, &mut rng)
+    }
+
+    /// Generate a new tagged ID using the given RNG, where `len` is the length after the separator
+    ///
+    /// ## Errors
+    ///
+    /// - [`TagError::EmptyTag`] if `tag` is empty
+    /// - [`TagError::InvalidTag`] if `tag` is longer than [`MAX_TAG_LENGTH`] or contains anything
+    ///   but ASCII letters and digits
+    /// - [`TagError::Id`] with [`IdError::TooShort`] if `len` is below [`MIN_LENGTH`]
+    pub fn new_with_rng<R: RngCore + ?Sized>(
+        tag: &str,
+        len: usize,
+        rng: &mut R,
+    ) -> Result<Self, TagError> {
+        let tag = validate_tag(tag)?;
+        if len < MIN_LENGTH {
+            return Err(IdError::TooShort.into());
+        }
+        let id = generate_sealed(&tag, len, rng);
+        Ok(Self { tag, id })
+    }
+
+    /// The tag, in lowercase
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The part after the separator, including the check character
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Validate a tag, returning it in lowercase
+pub(crate) fn validate_tag(tag: &str) -> Result<String, TagError> {
+    if tag.is_empty() {
+        return Err(TagError::EmptyTag);
+    }
+    if tag.len() > MAX_TAG_LENGTH || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(TagError::InvalidTag);
+    }
+    Ok(tag.to_ascii_lowercase())
+}
+
+/// Generate `len` characters ending in a check character that also covers `tag`
+///
+/// Shared by every ID type whose check character covers extra text, such as coupons and slugs.
+pub(crate) fn generate_sealed<R: RngCore + ?Sized>(tag: &str, len: usize, rng: &mut R) -> String {
+    // The generator never ends a body with a character that could combine with the check
+    // character into an ambiguous sequence, so swapping the check character is safe.
+    let mut id = IdGenerator::new(len).generate_with_rng(rng).0.into_owned();
+    id.pop();
+    let check_char =
+        tagged_check_char(tag, &id).expect("generated bodies are in the generation alphabet");
+    id.push(check_char);
+    id
+}
+
+/// Parse `s`, written by [`generate_sealed`] under `tag`, returning it normalized
+///
+/// Callers must bound the length of their input by [`MAX_PARSE_LENGTH`] first.
+pub(crate) fn parse_sealed(tag: &str, s: &str) -> Result<String, IdError> {
+    let normalized = alphabet::normalize_string(s);
+    if normalized.len() < MIN_LENGTH {
+        return Err(IdError::TooShort);
+    }
+
+    let (body, check_char) = normalized
+        .split_at_checked(normalized.len().saturating_sub(1))
+        .ok_or(IdError::InvalidCharacter)?;
+    for c in body.chars() {
+        alphabet::validate_gen_char(c)?;
+    }
+    if check_char != tagged_check_char(tag, body)?.to_string() {
+        return Err(IdError::InvalidCheckBit);
+    }
+    Ok(normalized)
+}
+
+/// Check character for `body` under `tag`
+///
+/// The regular check character is offset by a position-weighted sum of the tag bytes, so both
+/// substituted and reordered tag characters are caught.
+fn tagged_check_char(tag: &str, body: &str) -> Result<char, IdError> {
+    let untagged = alphabet::calculate_check_char(body)?;
+    let offset = tag.bytes().zip(1_u64..).fold(0_u64, |acc, (b, weight)| {
+        acc.saturating_add(u64::from(b).saturating_mul(weight))
+    });
+
+    let sum = offset.saturating_add(u64::from(
+        alphabet::check_value(untagged).ok_or(IdError::InvalidCheckBit)?,
+    ));
+    let index = sum
+        .checked_rem(CHECK_ALPHABET.len() as u64)
+        .and_then(|index| usize::try_from(index).ok())
+        .ok_or(IdError::InvalidCheckBit)?;
+    CHECK_ALPHABET
+        .get(index)
+        .copied()
+        .ok_or(IdError::InvalidCheckBit)
+}
+
+impl FromStr for TaggedId {
+    type Err = TagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_PARSE_LENGTH {
+            return Err(IdError::TooLong.into());
+        }
+        let (tag, rest) = s
+            .split_once(TAG_SEPARATOR)
+            .ok_or(TagError::MissingSeparator)?;
+        let tag = validate_tag(tag)?;
+        let id = parse_sealed(&tag, rest)?;
+        Ok(Self { tag, id })
+    }
+}
+
+impl TryFrom<String> for TaggedId {
+    type Error = TagError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl fmt::Display for TaggedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.tag, TAG_SEPARATOR, self.id)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Serialize tagged IDs in their joined string form
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::TaggedId;
+
+    impl Serialize for TaggedId {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TaggedId {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        for _ in 0..1_000 {
+            let id = TaggedId::new("EU1", 8).expect("valid tag");
+            assert_eq!(id.tag(), "eu1");
+            assert_eq!(id.id().len(), 8);
+            assert_eq!(TaggedId::from_str(&id.to_string().to_uppercase()), Ok(id));
+        }
+    }
+
+    #[test]
+    fn tag_is_covered_by_check_char() {
+        for _ in 0..1_000 {
+            let id = TaggedId::new("eu", 8).expect("valid tag");
+            for other in ["us", "ue", "ev", "eu2"] {
+                assert_eq!(
+                    TaggedId::from_str(&format!("{other}-{}", id.id())),
+                    Err(TagError::Id(IdError::InvalidCheckBit)),
+                    "{id} accepted under {other}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn some_tags_share_check_chars() {
+        // 'f' + 2 * 'r' and 'j' + 2 * 'p' are both 330, so they offset check characters equally.
+        for _ in 0..100 {
+            let id = TaggedId::new("fr", 8).expect("valid tag");
+            let moved = TaggedId::from_str(&format!("jp-{}", id.id())).expect("same check char");
+            assert_eq!(moved.tag(), "jp");
+        }
+    }
+
+    #[test]
+    fn tag_is_validated() {
+        assert_eq!(TaggedId::new("", 8), Err(TagError::EmptyTag));
+        assert_eq!(TaggedId::new("eu", 3), Err(TagError::Id(IdError::TooShort)));
+        assert_eq!(TaggedId::new("europe-west", 8), Err(TagError::InvalidTag));
+        assert_eq!(TaggedId::new("e_u", 8), Err(TagError::InvalidTag));
+        assert_eq!(
+            TaggedId::from_str("wcfytd3"),
+            Err(TagError::MissingSeparator)
+        );
+        assert_eq!(TaggedId::from_str("-wcfytd3"), Err(TagError::EmptyTag));
+        assert_eq!(
+            TaggedId::from_str(&format!("eu-{}", "a".repeat(MAX_PARSE_LENGTH))),
+            Err(TagError::Id(IdError::TooLong))
+        );
+        assert_eq!(TagError::MissingSeparator.code(), "missing_separator");
+        assert_eq!(
+            TagError::Id(IdError::TooLong).code(),
+            IdError::TooLong.code()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let id = TaggedId::new("us", 10).expect("valid tag");
+        let serialized = serde_json::to_string(&id).expect("serializes");
+        assert_eq!(serialized, format!("\"{id}\""));
+        let deserialized: TaggedId = serde_json::from_str(&serialized).expect("deserializes");
+        assert_eq!(deserialized, id);
+    }
+}
@@ -0,0 +1,346 @@
+// src/telemetry.rs
+//! Operational metrics and traces for ID generation and parsing
+//!
+//! With the `metrics` feature, IDs generated, parse failures and generation retries are recorded
+//! through the [`metrics`](https://docs.rs/metrics) facade, so any installed recorder, such as a
+//! Prometheus exporter, picks them up. A spike in [`PARSE_FAILURES`] with the
+//! `invalid_check_bit` code usually points at a client mangling IDs, or at someone guessing them.
+//!
+//! With the `tracing` feature, batch generation and
+//! [`IdGenerator::generate_unique`](crate::generator::IdGenerator::generate_unique) run in
+//! `debug` spans, discarded candidates are `trace` events, and parse failures are `debug` events
+//! with a `code` field. Without either feature nothing is recorded.
+
+use crate::error::IdError;
+
+/// Counter of IDs generated by [`IdGenerator`](crate::generator::IdGenerator)
+pub const IDS_GENERATED: &str = "ids_generated_total";
+
+/// Counter of failed parses, labelled by `code`, see [`IdError::code`]
+pub const PARSE_FAILURES: &str = "id_parse_failures_total";
+
+/// Counter of candidates discarded during generation, labelled by `reason`: `unpronounceable` or
+/// `collision`
+pub const GENERATION_RETRIES: &str = "id_generation_retries_total";
+
+/// Gauge of the length of IDs issued by
+/// [`AdaptiveGenerator`](crate::adaptive::AdaptiveGenerator)s, set whenever they lengthen IDs
+pub const ADAPTIVE_LENGTH: &str = "id_adaptive_length";
+
+/// Why a generation candidate was discarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RetryReason {
+    /// The candidate fell short of the pronounceability policy
+    Unpronounceable,
+    /// The candidate was already taken
+    Collision,
+}
+
+impl RetryReason {
+    /// Label value recorded for this reason
+    #[cfg(any(feature = "metrics", feature = "tracing"))]
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Unpronounceable => "unpronounceable",
+            Self::Collision => "collision",
+        }
+    }
+}
+
+/// Register descriptions of every metric with the installed recorder
+///
+/// Optional, exporters that support descriptions show them as help text. Call it once after
+/// installing the recorder.
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    metrics::describe_counter!(IDS_GENERATED, "IDs generated");
+    metrics::describe_counter!(PARSE_FAILURES, "IDs that failed to parse, by error code");
+    metrics::describe_counter!(
+        GENERATION_RETRIES,
+        "Candidates discarded while generating IDs, by reason"
+    );
+    metrics::describe_gauge!(
+        ADAPTIVE_LENGTH,
+        "Length of IDs issued by adaptive generators"
+    );
+}
+
+/// Span entered while generating, exited when dropped
+#[cfg(feature = "tracing")]
+pub(crate) type Entered = tracing::span::EnteredSpan;
+
+/// Span entered while generating, exited when dropped
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Entered;
+
+/// Enter a span covering the generation of a batch of `count` IDs of length `len`
+#[cfg_attr(
+    not(feature = "tracing"),
+    allow(unused_variables, reason = "only read when tracing")
+)]
+pub(crate) fn enter_batch(count: usize, len: usize) -> Entered {
+    #[cfg(feature = "tracing")]
+    return tracing::debug_span!("generate_batch", count, len).entered();
+    #[cfg(not(feature = "tracing"))]
+    return Entered;
+}
+
+/// Enter a span covering the generation of a unique ID of length `len`
+#[cfg_attr(
+    not(feature = "tracing"),
+    allow(unused_variables, reason = "only read when tracing")
+)]
+pub(crate) fn enter_unique(len: usize) -> Entered {
+    #[cfg(feature = "tracing")]
+    return tracing::debug_span!("generate_unique", len).entered();
+    #[cfg(not(feature = "tracing"))]
+    return Entered;
+}
+
+/// Record a generated ID
+pub(crate) fn record_generated() {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(IDS_GENERATED).increment(1);
+}
+
+/// Record a failed parse
+#[cfg_attr(
+    not(any(feature = "metrics", feature = "tracing")),
+    allow(
+        unused_variables,
+        reason = "only read when recording metrics or tracing"
+    )
+)]
+pub(crate) fn record_parse_failure(error: &IdError) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(PARSE_FAILURES, "code" => error.code()).increment(1);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(code = error.code(), "failed to parse ID");
+}
+
+/// Record a discarded generation candidate
+#[cfg_attr(
+    not(any(feature = "metrics", feature = "tracing")),
+    allow(
+        unused_variables,
+        reason = "only read when recording metrics or tracing"
+    )
+)]
+pub(crate) fn record_retry(reason: RetryReason) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(GENERATION_RETRIES, "reason" => reason.label()).increment(1);
+    #[cfg(feature = "tracing")]
+    tracing::trace!(reason = reason.label(), "discarded ID candidate");
+}
+
+/// Record an adaptive generator lengthening IDs
+///
+/// Only the new length is recorded as a metric, `from` and `issued` are only traced.
+#[cfg_attr(
+    not(feature = "tracing"),
+    allow(
+        unused_variables,
+        reason = "from and issued are only read when tracing"
+    )
+)]
+pub(crate) fn record_length_change(from: usize, to: usize, issued: u64) {
+    #[cfg(feature = "metrics")]
+    #[allow(clippy::cast_precision_loss, reason = "lengths are tiny")]
+    metrics::gauge!(ADAPTIVE_LENGTH).set(to as f64);
+    #[cfg(feature = "tracing")]
+    tracing::info!(from, to, issued, "lengthened IDs");
+}
+
+#[cfg(all(test, any(feature = "metrics", feature = "tracing")))]
+mod tests {
+    use std::str::FromStr;
+    #[cfg(feature = "metrics")]
+    use std::{
+        collections::HashMap,
+        fmt::Write,
+        sync::{Arc, Mutex, atomic::AtomicU64, atomic::Ordering},
+    };
+
+    #[cfg(feature = "metrics")]
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+
+    #[cfg(feature = "metrics")]
+    use super::*;
+    use crate::{Id, generator::IdGenerator};
+
+    /// Recorder keeping counters in memory, keyed by name and labels
+    #[cfg(feature = "metrics")]
+    #[derive(Default)]
+    struct TestRecorder(Mutex<HashMap<String, Arc<AtomicU64>>>);
+
+    #[cfg(feature = "metrics")]
+    impl TestRecorder {
+        fn get(&self, key: &str) -> u64 {
+            let counters = self.0.lock().unwrap();
+            counters
+                .get(key)
+                .map(|c| c.load(Ordering::Relaxed))
+                .unwrap_or_default()
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let mut name = key.name().to_string();
+            for label in key.labels() {
+                let _ = write!(name, ",{}={}", label.key(), label.value());
+            }
+            let counter = Arc::clone(self.0.lock().unwrap().entry(name).or_default());
+            Counter::from_arc(counter)
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn records_generation_and_parse_failures() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            describe_metrics();
+
+            let id = Id::new(12);
+            let _ = Id::from_str(&id);
+            let _ = Id::from_str("ab");
+            let _ = Id::from_str("wcfytxww4opin4jmjjes4ccfe");
+
+            let mut taken = 1;
+            let _ = IdGenerator::new(12)
+                .with_min_pronounceability(2.0)
+                .generate_unique(|_| {
+                    taken -= 1;
+                    taken >= 0
+                });
+        });
+
+        assert_eq!(recorder.get(IDS_GENERATED), 2);
+        assert_eq!(recorder.get(&format!("{PARSE_FAILURES},code=too_short")), 1);
+        assert_eq!(
+            recorder.get(&format!("{PARSE_FAILURES},code=invalid_check_bit")),
+            1
+        );
+        assert_eq!(
+            recorder.get(&format!("{GENERATION_RETRIES},reason=collision")),
+            1
+        );
+        assert!(recorder.get(&format!("{GENERATION_RETRIES},reason=unpronounceable")) > 0);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn internal_parses_are_not_recorded() {
+        let recorder = TestRecorder::default();
+        let id = Id::new(12);
+        metrics::with_local_recorder(&recorder, || {
+            let text = format!(
+                "Customer complaint: shipment {id} delivered incorrectly, refund requested"
+            );
+            assert!(Id::extract_all(&text).iter().any(|(_, found)| *found == id));
+            let _ = crate::suggest::suggest(
+                "wcfytxww4opin4jmjjes4ccfs",
+                crate::suggest::KeyboardLayout::Qwerty,
+            );
+            let _ = id.matches("wcfytxww4opin4jmjjes4ccfa");
+            let _ = Id::validate_prefix("wcfytxww");
+            let _ = crate::audit::validate_lines(["wcfytxww4opin4jmjjes4ccfa"]);
+        });
+
+        let failures = recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.starts_with(PARSE_FAILURES))
+            .map(|(_, count)| count.load(Ordering::Relaxed))
+            .sum::<u64>();
+        assert_eq!(failures, 0);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn traces_generation_and_parse_failures() {
+        use std::sync::{Arc, Mutex};
+
+        use tracing::{
+            Event, Metadata, Subscriber,
+            field::{Field, Visit},
+            span::{Attributes, Id as SpanId, Record},
+        };
+
+        /// Subscriber logging span names and the `code` field of events
+        #[derive(Default, Clone)]
+        struct TestSubscriber(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for TestSubscriber {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "code" {
+                    self.0.lock().unwrap().push(format!("code={value:?}"));
+                }
+            }
+
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == "code" {
+                    self.0.lock().unwrap().push(format!("code={value}"));
+                }
+            }
+        }
+
+        impl Subscriber for TestSubscriber {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> SpanId {
+                let mut log = self.0.lock().unwrap();
+                log.push(format!("span={}", span.metadata().name()));
+                SpanId::from_u64(log.len() as u64)
+            }
+
+            fn record(&self, _: &SpanId, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &SpanId, _: &SpanId) {}
+
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut self.clone());
+            }
+
+            fn enter(&self, _: &SpanId) {}
+            fn exit(&self, _: &SpanId) {}
+        }
+
+        let subscriber = TestSubscriber::default();
+        let log = Arc::clone(&subscriber.0);
+        tracing::subscriber::with_default(subscriber, || {
+            let generator = IdGenerator::new(12);
+            let _ = generator.generate_batch(3);
+            let _ = generator.generate_unique(|_| false);
+            let _ = Id::from_str("ab");
+        });
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "span=generate_batch",
+                "span=generate_unique",
+                "code=too_short"
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+// src/token.rs
+//! Short signed tokens carrying a few claims, e.g. for device pairing or support PINs
+//!
+//! A token packs an issuer, a subject and an expiry time into an ID through [`Id::from_bytes`],
+//! followed by a 48-bit MAC under a server-side key. Tokens are around 30 characters, short enough
+//! to read out over the phone or type from another screen, where a JWT is not. The claims are
+//! readable by anyone holding a token, so keep secrets out of them.
+//!
+//! The hash function behind the MAC is passed explicitly when signing and verifying, so enabling a
+//! feature elsewhere in the dependency graph can't invalidate tokens already handed out.
+
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use thiserror::Error;
+
+use crate::{
+    Id,
+    content::ContentHash,
+    cursor::{read_varint, write_varint},
+    error::IdError,
+    secret::constant_time_eq,
+};
+
+/// Format version, the first byte of every token
+const FORMAT_VERSION: u8 = 0x01;
+
+/// Number of MAC bytes at the end of a token
+pub const MAC_BYTES: usize = 6;
+
+/// Domain separator, so MACs can't be confused with other keyed digests under the same key
+const MAC_CONTEXT: &[u8] = b"human-friendly-ids Claims\0";
+
+/// Why a token was rejected
+#[derive(Error, Debug, PartialEq, Eq, Clone, Hash)]
+pub enum TokenError {
+    /// The token was mistyped, forged or signed with another key
+    #[error(transparent)]
+    Invalid(#[from] IdError),
+    /// The token was genuine but has expired
+    #[error("Token has expired")]
+    Expired,
+    /// The token was genuine but is in a format this version can't read
+    #[error("Token is in an unknown format")]
+    UnknownFormat,
+}
+
+/// Claims signed into a token
+///
+/// # Example
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use human_friendly_ids::{
+///     content::ContentHash,
+///     token::{Claims, TokenError},
+/// };
+///
+/// let hash = ContentHash::ALL[0];
+/// let key = b"server-side secret";
+/// let expires = SystemTime::now() + Duration::from_mins(10);
+/// let token = Claims::new(7, 1_234_567, expires).sign(hash, key);
+///
+/// let claims = Claims::verify(hash, key, &token.to_uppercase()).unwrap();
+/// assert_eq!((claims.issuer(), claims.subject()), (7, 1_234_567));
+/// assert!(Claims::verify(hash, b"another secret", &token).is_err());
+///
+/// let expired = Claims::new(7, 1_234_567, SystemTime::UNIX_EPOCH).sign(hash, key);
+/// assert_eq!(Claims::verify(hash, key, &expired), Err(TokenError::Expired));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Claims {
+    /// Who issued the token, e.g. a service or tenant number
+    issuer: u32,
+    /// What the token is about, e.g. a device or user number
+    subject: u64,
+    /// Seconds since the Unix epoch after which the token is rejected
+    expires: u64,
+}
+
+impl Claims {
+    /// Claims about `subject` by `issuer`, valid until `expires`, truncated to whole seconds
+    ///
+    /// Times before the Unix epoch are stored as the epoch.
+    #[must_use]
+    pub fn new(issuer: u32, subject: u64, expires: SystemTime) -> Self {
+        let expires = expires
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self {
+            issuer,
+            subject,
+            expires,
+        }
+    }
+
+    /// Who issued the token
+    #[must_use]
+    pub const fn issuer(&self) -> u32 {
+        self.issuer
+    }
+
+    /// What the token is about
+    #[must_use]
+    pub const fn subject(&self) -> u64 {
+        self.subject
+    }
+
+    /// When the token expires, at second resolution
+    #[must_use]
+    pub fn expires(&self) -> SystemTime {
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(self.expires))
+            .unwrap_or(UNIX_EPOCH)
+    }
+
+    /// Whether the token has expired at `now`
+    #[must_use]
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        now >= self.expires()
+    }
+
+    /// Sign the claims under `key` into a token using `hash`
+    #[allow(
+        clippy::missing_panics_doc,
+        reason = "Internal invariant - the version byte and MAC give the ID its minimum length."
+    )]
+    #[must_use]
+    pub fn sign(&self, hash: ContentHash, key: &[u8]) -> Id {
+        let mut bytes = vec![FORMAT_VERSION];
+        write_varint(&mut bytes, u64::from(self.issuer));
+        write_varint(&mut bytes, self.subject);
+        write_varint(&mut bytes, self.expires);
+        let mac = mac(hash, key, &bytes);
+        bytes.extend_from_slice(&mac);
+        Id::from_bytes(&bytes).expect("tokens are at least seven bytes")
+    }
+
+    /// Decode a token, check its MAC under `key` using `hash` and that it hasn't expired
+    ///
+    /// ## Errors
+    ///
+    /// See [`Claims::verify_at`].
+    pub fn verify(hash: ContentHash, key: &[u8], s: &str) -> Result<Self, TokenError> {
+        Self::verify_at(hash, key, s, SystemTime::now())
+    }
+
+    /// Decode a token, check its MAC under `key` using `hash` and that it hasn't expired at `now`
+    ///
+    /// The MAC is compared in constant time.
+    ///
+    /// ## Errors
+    ///
+    /// - [`TokenError::Invalid`] with [`IdError::InvalidCheckBit`] if the check character or the
+    ///   MAC doesn't match
+    /// - [`TokenError::Invalid`] with [`IdError::InvalidSequence`] if the token is too short to
+    ///   hold a MAC
+    /// - [`TokenError::Invalid`] with any other error from [`Id::from_str`] for an invalid ID
+    /// - [`TokenError::UnknownFormat`] if a genuine token is in a format this version can't read
+    /// - [`TokenError::Expired`] if the token has expired at `now`
+    pub fn verify_at(
+        hash: ContentHash,
+        key: &[u8],
+        s: &str,
+        now: SystemTime,
+    ) -> Result<Self, TokenError> {
+        let bytes = Id::from_str(s)?.to_bytes()?;
+        let split = bytes
+            .len()
+            .checked_sub(MAC_BYTES)
+            .ok_or(IdError::InvalidSequence)?;
+        let (payload, mac_bytes) = bytes.split_at(split);
+        if !constant_time_eq(&mac(hash, key, payload), mac_bytes) {
+            return Err(IdError::InvalidCheckBit.into());
+        }
+
+        let claims = Self::decode(payload).ok_or(TokenError::UnknownFormat)?;
+        if claims.is_expired_at(now) {
+            return Err(TokenError::Expired);
+        }
+        Ok(claims)
+    }
+
+    /// Read claims from a token's payload, after its MAC has been checked
+    fn decode(payload: &[u8]) -> Option<Self> {
+        let (&version, mut rest) = payload.split_first()?;
+        if version != FORMAT_VERSION {
+            return None;
+        }
+        let issuer = u32::try_from(read_varint(&mut rest).ok()?).ok()?;
+        let subject = read_varint(&mut rest).ok()?;
+        let expires = read_varint(&mut rest).ok()?;
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(Self {
+            issuer,
+            subject,
+            expires,
+        })
+    }
+}
+
+/// First [`MAC_BYTES`] bytes of the keyed digest of `payload`
+fn mac(hash: ContentHash, key: &[u8], payload: &[u8]) -> [u8; MAC_BYTES] {
+    let mut input = Vec::with_capacity(MAC_CONTEXT.len().saturating_add(payload.len()));
+    input.extend_from_slice(MAC_CONTEXT);
+    input.extend_from_slice(payload);
+    hash.keyed_digest(key, &input)
+        .first_chunk::<MAC_BYTES>()
+        .copied()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fixed time well before every expiry used in these tests
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let hash = ContentHash::ALL[0];
+        let expires = now() + Duration::from_mins(5);
+        for (issuer, subject) in [(0, 0), (1, 42), (u32::MAX, u64::MAX), (7, 1 << 40)] {
+            let claims = Claims::new(issuer, subject, expires);
+            let token = claims.sign(hash, b"key");
+            assert_eq!(Claims::verify_at(hash, b"key", &token, now()), Ok(claims));
+        }
+
+        let pairing = Claims::new(3, 9_001, expires).sign(hash, b"key");
+        assert!(pairing.len() <= 30, "{pairing}");
+    }
+
+    #[test]
+    fn forged_and_expired_tokens_are_rejected() {
+        let hash = ContentHash::ALL[0];
+        let expires = now() + Duration::from_mins(5);
+        let token = Claims::new(1, 2, expires).sign(hash, b"key");
+        assert_eq!(
+            Claims::verify_at(hash, b"other key", &token, now()),
+            Err(TokenError::Invalid(IdError::InvalidCheckBit))
+        );
+        assert_eq!(
+            Claims::verify_at(hash, b"key", &token, expires),
+            Err(TokenError::Expired)
+        );
+
+        // Extending the expiry without the key fails the MAC.
+        let mut bytes = token.to_bytes().unwrap();
+        let expiry_byte = bytes.len() - MAC_BYTES - 1;
+        bytes[expiry_byte] ^= 1;
+        let extended = Id::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            Claims::verify_at(hash, b"key", &extended, now()),
+            Err(TokenError::Invalid(IdError::InvalidCheckBit))
+        );
+
+        assert_eq!(
+            Claims::verify_at(hash, b"key", "wcfytxww4opin4jmjjes4ccfa", now()),
+            Err(TokenError::Invalid(IdError::InvalidCheckBit))
+        );
+        let short = Id::from_bytes(&[1, 2, 3]).unwrap();
+        assert_eq!(
+            Claims::verify_at(hash, b"key", &short, now()),
+            Err(TokenError::Invalid(IdError::InvalidSequence))
+        );
+    }
+
+    #[test]
+    fn genuine_payloads_in_unknown_formats_are_rejected() {
+        let hash = ContentHash::ALL[0];
+        for payload in [vec![2, 1, 2, 3], vec![1, 1, 2], vec![1, 1, 2, 3, 4]] {
+            let mut bytes = payload.clone();
+            bytes.extend_from_slice(&mac(hash, b"key", &payload));
+            let token = Id::from_bytes(&bytes).unwrap();
+            assert_eq!(
+                Claims::verify_at(hash, b"key", &token, now()),
+                Err(TokenError::UnknownFormat),
+                "{payload:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,179 @@
+// src/validation.rs
+//! Integrations with form validation crates
+//!
+//! With the `validator` feature, [`validate_id`] checks string fields holding IDs, and [`Id`]
+//! fields support `#[validate(length(...))]`.
+//!
+//! With the `garde` feature, [`validate_id_garde`] checks string fields holding IDs, and [`Id`]
+//! fields, including `Option<Id>`, support `#[garde(dive)]`.
+
+use std::str::FromStr;
+
+use crate::Id;
+
+/// Validate that a field holds a valid ID, for `#[validate(custom(function = "validate_id"))]`
+///
+/// The error code is the [`IdError::code`](crate::error::IdError::code) of the failure, and the
+/// message its English description.
+///
+/// ## Errors
+///
+/// - A [`validator::ValidationError`] if `value` is not a valid ID after normalization
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::validation::validate_id;
+/// use validator::Validate;
+///
+/// #[derive(Validate)]
+/// struct LookupForm {
+///     #[validate(custom(function = "validate_id"))]
+///     order: String,
+/// }
+///
+/// let form = LookupForm { order: String::from("WCFYTXWW4OPIN4JMJJES4CCFD") };
+/// assert!(form.validate().is_ok());
+///
+/// let form = LookupForm { order: String::from("wcfytxww4opin4jmjjes4ccfe") };
+/// let errors = form.validate().unwrap_err();
+/// assert_eq!(errors.field_errors()["order"][0].code, "invalid_check_bit");
+/// ```
+#[cfg(feature = "validator")]
+pub fn validate_id<T: AsRef<str> + ?Sized>(value: &T) -> Result<(), validator::ValidationError> {
+    match Id::from_str(value.as_ref()) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(validator::ValidationError::new(e.code()).with_message(e.to_string().into())),
+    }
+}
+
+#[cfg(feature = "validator")]
+impl validator::ValidateLength<u64> for Id {
+    fn length(&self) -> Option<u64> {
+        u64::try_from(self.len()).ok()
+    }
+}
+
+/// Validate that a field holds a valid ID, for `#[garde(custom(validate_id_garde))]`
+///
+/// The error message is the English description of the failure, reported at the path of the
+/// field.
+///
+/// ## Errors
+///
+/// - A [`garde::Error`] if `value` is not a valid ID after normalization
+///
+/// # Example
+/// ```
+/// use garde::Validate;
+/// use human_friendly_ids::validation::validate_id_garde;
+///
+/// #[derive(Validate)]
+/// struct LookupForm {
+///     #[garde(custom(validate_id_garde))]
+///     order: String,
+/// }
+///
+/// let form = LookupForm { order: String::from("WCFYTXWW4OPIN4JMJJES4CCFD") };
+/// assert!(form.validate().is_ok());
+///
+/// let form = LookupForm { order: String::from("wcfytxww4opin4jmjjes4ccfe") };
+/// let report = form.validate().unwrap_err();
+/// assert_eq!(report.iter().next().unwrap().0.to_string(), "order");
+/// ```
+#[cfg(feature = "garde")]
+pub fn validate_id_garde<T: AsRef<str> + ?Sized, C>(value: &T, _context: &C) -> garde::Result {
+    match Id::from_str(value.as_ref()) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(garde::Error::new(e.to_string())),
+    }
+}
+
+/// An [`Id`] is checked when it is parsed or deserialized, so diving into one always succeeds
+#[cfg(feature = "garde")]
+impl garde::Validate for Id {
+    type Context = ();
+
+    #[cfg_attr(test, mutants::skip)]
+    fn validate_into(
+        &self,
+        _context: &Self::Context,
+        _parent: &mut dyn FnMut() -> garde::Path,
+        _report: &mut garde::Report,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "validator")]
+    #[test]
+    fn validator_integration() {
+        use validator::Validate;
+
+        use super::*;
+
+        #[derive(Validate)]
+        struct Form {
+            #[validate(custom(function = "validate_id"))]
+            raw: String,
+            #[validate(length(min = 10, max = 16))]
+            parsed: Id,
+        }
+
+        let valid = Form {
+            raw: Id::new(8).to_uppercase(),
+            parsed: Id::new(12),
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = Form {
+            raw: String::from("ab"),
+            parsed: Id::new(8),
+        };
+        let errors = invalid.validate().expect_err("both fields are invalid");
+        let fields = errors.field_errors();
+        assert_eq!(fields["raw"][0].code, "too_short");
+        assert_eq!(fields["parsed"][0].code, "length");
+    }
+
+    #[cfg(feature = "garde")]
+    #[test]
+    fn garde_integration() {
+        use garde::Validate;
+
+        use super::*;
+        use crate::error::IdError;
+
+        #[derive(Validate)]
+        struct Form {
+            #[garde(custom(validate_id_garde))]
+            raw: String,
+            #[garde(dive)]
+            parsed: Id,
+            #[garde(dive)]
+            optional: Option<Id>,
+        }
+
+        let valid = Form {
+            raw: Id::new(8).to_uppercase(),
+            parsed: Id::new(12),
+            optional: Some(Id::new(12)),
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = Form {
+            raw: String::from("ab"),
+            parsed: Id::new(8),
+            optional: None,
+        };
+        let report = invalid.validate().expect_err("the raw field is invalid");
+        let errors = report
+            .iter()
+            .map(|(path, error)| (path.to_string(), error.message().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![(String::from("raw"), IdError::TooShort.to_string())]
+        );
+    }
+}
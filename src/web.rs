@@ -0,0 +1,254 @@
+// src/web.rs
+//! Integrations with web frameworks
+//!
+//! With the `warp` feature, [`id_param`] extracts an [`Id`] from the next path segment, rejecting
+//! invalid IDs with an [`InvalidId`] that carries the [`IdError`] and its code.
+//!
+//! With the `salvo` feature, [`Id`] can be taken directly as a `#[handler]` argument, read from the
+//! path parameter of the same name. Invalid IDs are answered with a 400 and a JSON body holding the
+//! error code and message, see [`InvalidId`].
+
+use std::{fmt, str::FromStr};
+
+use crate::{Id, error::IdError};
+
+/// Rejection for a path segment that is not a valid ID
+///
+/// With `warp`, recover it with `Rejection::find` to turn the [`IdError::code`] into a response.
+/// With `salvo`, it is written as a 400 with a body like
+/// `{"code":"invalid_check_bit","message":"Invalid check bit"}`.
+#[cfg(any(feature = "warp", feature = "salvo"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidId(IdError);
+
+#[cfg(any(feature = "warp", feature = "salvo"))]
+impl InvalidId {
+    /// The reason the segment was rejected
+    #[must_use]
+    pub const fn error(&self) -> &IdError {
+        &self.0
+    }
+
+    /// Stable, machine-readable code of the error, see [`IdError::code`]
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        self.0.code()
+    }
+}
+
+#[cfg(any(feature = "warp", feature = "salvo"))]
+impl fmt::Display for InvalidId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid ID: {}", self.0)
+    }
+}
+
+#[cfg(feature = "warp")]
+impl warp::reject::Reject for InvalidId {}
+
+#[cfg(feature = "salvo")]
+impl salvo::writing::Scribe for InvalidId {
+    fn render(self, res: &mut salvo::Response) {
+        res.status_code(salvo::http::StatusCode::BAD_REQUEST);
+        let body = serde_json::json!({
+            "code": self.code(),
+            "message": self.0.to_string(),
+        });
+        res.render(salvo::writing::Text::Json(body.to_string()));
+    }
+}
+
+/// Extract an [`Id`] from a path parameter
+///
+/// As a `#[handler]` argument, the ID is read from the path parameter named like the argument.
+/// Otherwise it is read from the `id` parameter. A missing parameter is treated like an empty one.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::Id;
+/// use salvo::{prelude::*, test::{ResponseExt, TestClient}};
+///
+/// #[handler]
+/// async fn show_order(id: Id) -> String {
+///     format!("order {id}")
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let service = Service::new(Router::with_path("orders/{id}").get(show_order));
+///
+/// let mut res = TestClient::get("http://localhost/orders/WCFYTXWW4OPIN4JMJJES4CCFD")
+///     .send(&service)
+///     .await;
+/// assert_eq!(res.take_string().await.unwrap(), "order wcfytxww4opin4jmjjes4ccfd");
+///
+/// let mut res = TestClient::get("http://localhost/orders/wcfytxww4opin4jmjjes4ccfe")
+///     .send(&service)
+///     .await;
+/// assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+/// assert!(res.take_string().await.unwrap().contains(r#""code":"invalid_check_bit""#));
+/// # });
+/// ```
+#[cfg(feature = "salvo")]
+impl<'ex> salvo::Extractible<'ex> for Id {
+    fn metadata() -> &'static salvo::extract::Metadata {
+        use salvo::extract::metadata::{Metadata, Source, SourceFrom, SourceParser};
+
+        static METADATA: std::sync::OnceLock<Metadata> = std::sync::OnceLock::new();
+        METADATA.get_or_init(|| {
+            Metadata::new("Id")
+                .add_default_source(Source::new(SourceFrom::Param, SourceParser::Flat))
+        })
+    }
+
+    #[allow(
+        refining_impl_trait,
+        reason = "The error type is part of the public API."
+    )]
+    async fn extract(
+        req: &'ex mut salvo::Request,
+        depot: &'ex mut salvo::Depot,
+    ) -> Result<Self, InvalidId> {
+        Self::extract_with_arg(req, depot, "id").await
+    }
+
+    #[allow(
+        refining_impl_trait,
+        reason = "The error type is part of the public API."
+    )]
+    async fn extract_with_arg(
+        req: &'ex mut salvo::Request,
+        _depot: &'ex mut salvo::Depot,
+        arg: &str,
+    ) -> Result<Self, InvalidId> {
+        let param = req
+            .params()
+            .get(arg)
+            .map(String::as_str)
+            .unwrap_or_default();
+        Id::from_str(param).map_err(InvalidId)
+    }
+}
+
+/// Filter extracting an [`Id`] from the next path segment
+///
+/// Segments are normalized like [`Id::from_str`], so `/orders/WCFY...` and `/orders/wcfy...` match
+/// the same ID. Invalid segments are rejected with [`InvalidId`], which can be recovered to report
+/// the error code to the client.
+///
+/// # Example
+/// ```
+/// use human_friendly_ids::{Id, web::{InvalidId, id_param}};
+/// use warp::Filter;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let route = warp::path("orders").and(id_param()).and(warp::path::end());
+///
+/// let id = warp::test::request()
+///     .path("/orders/WCFYTXWW4OPIN4JMJJES4CCFD")
+///     .filter(&route)
+///     .await
+///     .unwrap();
+/// assert_eq!(id.as_str(), "wcfytxww4opin4jmjjes4ccfd");
+///
+/// let rejection = warp::test::request()
+///     .path("/orders/wcfytxww4opin4jmjjes4ccfe")
+///     .filter(&route)
+///     .await
+///     .unwrap_err();
+/// assert_eq!(rejection.find::<InvalidId>().unwrap().code(), "invalid_check_bit");
+/// # });
+/// ```
+#[cfg(feature = "warp")]
+#[must_use]
+pub fn id_param() -> impl warp::Filter<Extract = (Id,), Error = warp::Rejection> + Clone {
+    use warp::Filter;
+
+    warp::path::param::<String>().and_then(|segment: String| async move {
+        Id::from_str(&segment).map_err(|e| warp::reject::custom(InvalidId(e)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "warp", feature = "salvo"))]
+    use super::*;
+
+    #[cfg(feature = "warp")]
+    #[tokio::test]
+    async fn warp_extracts_ids() {
+        use warp::Filter;
+
+        let route = warp::path("orders").and(id_param());
+        let id = Id::new(12);
+        let extracted = warp::test::request()
+            .path(&format!("/orders/{}", id.to_uppercase()))
+            .filter(&route)
+            .await
+            .expect("valid ID");
+        assert_eq!(extracted, id);
+
+        for (path, error) in [
+            ("/orders/ab", IdError::TooShort),
+            (
+                "/orders/wcfytxww4opin4jmjjes4ccfe",
+                IdError::InvalidCheckBit,
+            ),
+        ] {
+            let rejection = warp::test::request()
+                .path(path)
+                .filter(&route)
+                .await
+                .expect_err("invalid ID");
+            let invalid = rejection.find::<InvalidId>().expect("custom rejection");
+            assert_eq!(invalid.error(), &error);
+            assert_eq!(invalid.code(), error.code());
+        }
+
+        // Paths that don't reach the parameter are left to other routes.
+        let rejection = warp::test::request()
+            .path("/users/wcfytxww4opin4jmjjes4ccfd")
+            .filter(&route)
+            .await
+            .expect_err("wrong path");
+        assert!(rejection.is_not_found());
+    }
+
+    #[cfg(feature = "salvo")]
+    #[tokio::test]
+    async fn salvo_extracts_ids() {
+        use salvo::{
+            prelude::*,
+            test::{ResponseExt, TestClient},
+        };
+
+        #[handler]
+        async fn show(order_id: Id) -> String {
+            order_id.to_string()
+        }
+
+        let service = Service::new(Router::with_path("orders/{order_id}").get(show));
+        let id = Id::new(12);
+        let mut res = TestClient::get(format!("http://localhost/orders/{}", id.to_uppercase()))
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.ok(), Some(id.to_string()));
+
+        for (segment, error) in [
+            ("ab", IdError::TooShort),
+            ("wcfytxww4opin4jmjjes4ccfe", IdError::InvalidCheckBit),
+        ] {
+            let mut res = TestClient::get(format!("http://localhost/orders/{segment}"))
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+            let body = res.take_string().await.unwrap_or_default();
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&body).ok(),
+                Some(serde_json::json!({
+                    "code": error.code(),
+                    "message": error.to_string(),
+                }))
+            );
+        }
+    }
+}